@@ -0,0 +1,6 @@
+CREATE TABLE library(
+    name        TEXT        NOT NULL,
+    version     INTEGER     NOT NULL,
+    definition  TEXT        NOT NULL,
+    PRIMARY KEY (name, version)
+)
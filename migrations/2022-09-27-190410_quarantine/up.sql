@@ -0,0 +1,10 @@
+ALTER TABLE reports ADD COLUMN error TEXT;
+
+CREATE TABLE quarantine(
+    id          TEXT        NOT NULL PRIMARY KEY,
+    task_id     TEXT        NOT NULL,
+    input       TEXT        NOT NULL,
+    error       TEXT        NOT NULL,
+    created_at  DATETIME    NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(uuid)
+)
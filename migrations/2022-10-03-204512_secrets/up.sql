@@ -0,0 +1,5 @@
+CREATE TABLE secrets(
+    name        TEXT        NOT NULL PRIMARY KEY,
+    nonce       TEXT        NOT NULL,
+    ciphertext  TEXT        NOT NULL
+)
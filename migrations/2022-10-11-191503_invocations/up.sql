@@ -0,0 +1,2 @@
+ALTER TABLE tasks ADD COLUMN invocations INTEGER;
+ALTER TABLE tasks ADD COLUMN ends_at DATETIME;
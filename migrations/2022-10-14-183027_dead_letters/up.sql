@@ -0,0 +1,12 @@
+ALTER TABLE tasks ADD COLUMN retry_policy TEXT;
+
+CREATE TABLE dead_letters(
+    id          TEXT        NOT NULL PRIMARY KEY,
+    task_id     TEXT        NOT NULL,
+    input       TEXT        NOT NULL,
+    phase       TEXT        NOT NULL,
+    error       TEXT        NOT NULL,
+    attempts    INTEGER     NOT NULL,
+    created_at  DATETIME    NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(uuid)
+)
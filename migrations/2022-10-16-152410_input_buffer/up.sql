@@ -0,0 +1,2 @@
+ALTER TABLE tasks ADD COLUMN buffer TEXT;
+ALTER TABLE tasks ADD COLUMN dropped_inputs BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE tasks DROP COLUMN batch;
+ALTER TABLE reports DROP COLUMN items;
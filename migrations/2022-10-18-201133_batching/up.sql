@@ -0,0 +1,2 @@
+ALTER TABLE tasks ADD COLUMN batch TEXT;
+ALTER TABLE reports ADD COLUMN items TEXT;
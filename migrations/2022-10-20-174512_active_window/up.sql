@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN active_window TEXT;
@@ -0,0 +1,7 @@
+CREATE TABLE inbox(
+    id          TEXT        NOT NULL PRIMARY KEY,
+    task_id     TEXT        NOT NULL,
+    input       TEXT        NOT NULL,
+    created_at  DATETIME    NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(uuid)
+)
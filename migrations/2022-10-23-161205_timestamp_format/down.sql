@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE tasks DROP COLUMN timestamp_format;
+ALTER TABLE tasks DROP COLUMN timestamp_timezone;
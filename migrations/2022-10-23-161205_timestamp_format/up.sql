@@ -0,0 +1,2 @@
+ALTER TABLE tasks ADD COLUMN timestamp_format TEXT NOT NULL DEFAULT '%Y-%m-%d %H:%M:%S';
+ALTER TABLE tasks ADD COLUMN timestamp_timezone TEXT NOT NULL DEFAULT 'UTC';
@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN write_mode TEXT NOT NULL DEFAULT '"append"';
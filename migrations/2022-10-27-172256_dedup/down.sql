@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE tasks DROP COLUMN dedup;
+DROP TABLE fingerprints;
@@ -0,0 +1,7 @@
+ALTER TABLE tasks ADD COLUMN dedup TEXT;
+CREATE TABLE fingerprints(
+    task_id     TEXT        NOT NULL PRIMARY KEY,
+    hash        TEXT        NOT NULL,
+    numbers     TEXT        NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(uuid)
+)
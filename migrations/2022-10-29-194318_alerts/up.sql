@@ -0,0 +1,13 @@
+ALTER TABLE tasks ADD COLUMN alerts TEXT;
+CREATE TABLE alerts(
+    task_id     TEXT        NOT NULL,
+    rule        TEXT        NOT NULL,
+    severity    TEXT        NOT NULL,
+    status      TEXT        NOT NULL,
+    value       TEXT        NOT NULL,
+    fired_at    TIMESTAMP   NOT NULL,
+    notified_at TIMESTAMP,
+    resolved_at TIMESTAMP,
+    PRIMARY KEY (task_id, rule),
+    FOREIGN KEY (task_id) REFERENCES tasks(uuid)
+)
@@ -0,0 +1,10 @@
+CREATE TABLE backfills(
+    task_id     TEXT        NOT NULL PRIMARY KEY,
+    total       INTEGER     NOT NULL,
+    processed   INTEGER     NOT NULL,
+    failed      INTEGER     NOT NULL,
+    status      TEXT        NOT NULL,
+    started_at  TIMESTAMP   NOT NULL,
+    finished_at TIMESTAMP,
+    FOREIGN KEY (task_id) REFERENCES tasks(uuid)
+)
@@ -0,0 +1,6 @@
+CREATE TABLE library_versions(
+    name            TEXT        NOT NULL PRIMARY KEY,
+    last_version    INTEGER     NOT NULL
+);
+INSERT INTO library_versions (name, last_version)
+    SELECT name, MAX(version) FROM library GROUP BY name;
@@ -0,0 +1,5 @@
+CREATE TABLE grid_columns(
+    task_id     TEXT        NOT NULL PRIMARY KEY,
+    columns     TEXT        NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(uuid)
+)
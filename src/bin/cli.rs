@@ -1,3 +1,5 @@
+use datatracker_rust::lang::process::Process;
+use datatracker_rust::lang::registry::Registry;
 use datatracker_rust::stats::GetStatsRequest;
 use datatracker_rust::stats::{stats_client::StatsClient, GetStatsResponse};
 use std::{sync::Arc, time::Duration};
@@ -86,8 +88,57 @@ impl App {
     }
 }
 
+/// Runs test cases declared in process file and prints pass/fail report.
+/// Exits with non-zero code if any of test cases failed. Library definitions are stored
+/// by the server, so processes with imports have to be tested with `POST /process/test`.
+fn run_process_tests(path: &str) -> Result<(), Box<dyn Error>> {
+    let reader = io::BufReader::new(std::fs::File::open(path)?);
+    let process: Process = serde_json::from_reader(reader)?;
+    if process
+        .imports
+        .as_ref()
+        .map_or(false, |imports| !imports.is_empty())
+    {
+        return Err(format!(
+            "{} imports library definitions, which are not supported by cli, use POST /process/test",
+            process.name
+        )
+        .into());
+    }
+
+    let report = process.run_tests(&Registry::default());
+    for result in &report.results {
+        match &result.message {
+            None => println!("ok     {}", result.name),
+            Some(message) => println!("FAILED {} - {}", result.name, message),
+        }
+    }
+    println!(
+        "{} passed; {} failed; {}",
+        report.passed, report.failed, process.name
+    );
+
+    if !report.success() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    // `cli test <process.json>` runs process test cases instead of stats dashboard.
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, command, path] = args.as_slice() {
+        if command == "test" {
+            let path = path.clone();
+            return tokio::task::spawn_blocking(move || {
+                run_process_tests(&path).map_err(|err| err.to_string())
+            })
+            .await?
+            .map_err(|err| err.into());
+        }
+    }
+
     let mut client = StatsClient::connect("http://[::1]:10000").await.unwrap();
     let mut timer = tokio::time::interval(Duration::new(5, 0));
     let state: Arc<Mutex<Vec<GetStatsResponse>>> = Arc::default();
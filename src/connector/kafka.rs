@@ -1,9 +1,12 @@
 use crate::core::task::InputData;
 use rdkafka::{
-    consumer::{stream_consumer::StreamConsumer, Consumer},
+    consumer::{stream_consumer::StreamConsumer, CommitMode, Consumer},
     ClientConfig, Message,
 };
-use tokio::sync::{broadcast, mpsc::Sender};
+use tokio::sync::{
+    broadcast,
+    mpsc::{Receiver, Sender},
+};
 
 #[derive(Debug, Clone)]
 /// Configuration for kafka client.
@@ -14,16 +17,19 @@ pub struct KafkaConfig {
 }
 
 /// Starts consuming messages from kafka. Client is configured using KafkaConfig.  
+/// Offset of a message is committed once the task acknowledges its input with acks.
 pub async fn consume_topic(
     cfg: KafkaConfig,
     sender: Sender<InputData>,
+    mut acks: Receiver<()>,
     shutdown: &mut broadcast::Receiver<()>,
 ) {
     let consumer: StreamConsumer = ClientConfig::new()
         .set("group.id", &cfg.group_id)
         .set("bootstrap.servers", &cfg.brokers)
         .set("session.timeout.ms", "6000")
-        .set("enable.auto.commit", "true")
+        // offsets are committed after message's input is pushed into task's buffer.
+        .set("enable.auto.commit", "false")
         .set("api.version.request.timeout.ms", "6000")
         .set("api.version.request", "true")
         .set("broker.version.fallback", "2.3.1")
@@ -56,12 +62,30 @@ pub async fn consume_topic(
                         debug!("consume_topic: received message: {:?}, {}", m, payload);
 
                         match InputData::try_from(payload) {
-                            Ok(id) => sender
-                                .send(id)
-                                .await
-                                .expect("Can't send InputData from consume_topic"),
+                            Ok(id) => {
+                                if sender.send(id).await.is_err() {
+                                    warn!("consume_topic: task's input channel closed");
+                                    return;
+                                }
+                                // input is in the inbox of durable task once it's acknowledged.
+                                tokio::select! {
+                                    _ = shutdown.recv() => {
+                                        debug!("consume_topic: closing before input was acknowledged");
+                                        return;
+                                    }
+                                    ack = acks.recv() => {
+                                        if ack.is_none() {
+                                            warn!("consume_topic: task's ack channel closed");
+                                            return;
+                                        }
+                                    }
+                                }
+                            }
                             Err(e) => warn!("Error while deserializing message payload: {:?}", e),
                         }
+                        if let Err(e) = consumer.commit_message(&m, CommitMode::Async) {
+                            warn!("Failed to commit offset: {}", e);
+                        }
                     }
                 }
             }
@@ -1,3 +1,4 @@
+use crate::core::secret::Credential;
 use crate::core::task::{BoxFnThatReturnsAFuture, InputData};
 use crate::error::types::{Error, Result};
 use futures::{stream, StreamExt};
@@ -12,7 +13,7 @@ pub struct PSQLConfig {
     pub host: String,
     pub port: u16,
     pub user: String,
-    pub password: String,
+    pub password: Credential,
     pub db: String,
 
     // fields for changes monitor.
@@ -24,7 +25,7 @@ impl PSQLConfig {
         host: String,
         port: u16,
         user: String,
-        password: String,
+        password: Credential,
         db: String,
         channel_name: Option<String>,
     ) -> Self {
@@ -38,16 +39,21 @@ impl PSQLConfig {
         }
     }
 
-    pub fn to_conn_str(&self) -> String {
-        format!(
+    /// Fails if password references secret that was not resolved.
+    pub fn to_conn_str(&self) -> Result<String> {
+        Ok(format!(
             "host={} port={} user={} password={} dbname={}",
-            self.host, self.port, self.user, self.password, self.db,
-        )
+            self.host,
+            self.port,
+            self.user,
+            self.password.expose()?,
+            self.db,
+        ))
     }
 }
 
 fn new_psql_client(cfg: &PSQLConfig) -> Result<Client> {
-    Client::connect(cfg.to_conn_str().as_str(), NoTls).map_err(|err| {
+    Client::connect(cfg.to_conn_str()?.as_str(), NoTls).map_err(|err| {
         Error::new_internal(
             String::from("psql_wrap"),
             String::from("failed to create psql client"),
@@ -96,7 +102,14 @@ pub async fn monitor_changes(
     sender: Sender<InputData>,
     mut shutdown: broadcast::Receiver<()>,
 ) {
-    let (client, mut connection) = tokio_postgres::connect(cfg.to_conn_str().as_str(), NoTls)
+    let conn_str = match cfg.to_conn_str() {
+        Ok(conn_str) => conn_str,
+        Err(err) => {
+            error!("monitor_changes: {}", err);
+            return;
+        }
+    };
+    let (client, mut connection) = tokio_postgres::connect(conn_str.as_str(), NoTls)
         .await
         .unwrap();
     let client = Arc::new(client);
@@ -115,10 +128,14 @@ pub async fn monitor_changes(
                 let msg = n.unwrap();
                 if let AsyncMessage::Notification(notification) = msg {
                     debug!("notification: {:?}", notification);
-                    sender
+                    if sender
                         .send(InputData::String(notification.payload().to_string()))
                         .await
-                        .unwrap();
+                        .is_err()
+                    {
+                        warn!("monitor_changes: task's input channel closed");
+                        return;
+                    }
                 } else {
                     return;
                 }
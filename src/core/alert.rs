@@ -0,0 +1,389 @@
+use super::notifier::Notifier;
+use crate::error::types::{Error, Result};
+use crate::lang::engine::Engine;
+use crate::lang::lexer::{Keyword, Lexer, Parser};
+use crate::lang::node::{Node, NodeEnum};
+use crate::lang::process::{Definition, Process};
+use crate::lang::registry::Registry;
+use crate::lang::variable::Variable;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Default for Severity {
+    fn default() -> Self {
+        Self::Warning
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Rule checked on task's output after every run. Expressions use steps language,
+/// output is available as IN and expression has to return BOOL: GT(EXTRACT(IN, price), INT(100)).
+pub struct AlertRule {
+    /// Identifies alert of the rule, unique within a task.
+    pub name: String,
+    /// Alert fires when condition is true.
+    pub condition: String,
+    /// Firing alert resolves when resolve is true, when condition stops being true by default.
+    #[serde(default)]
+    pub resolve: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Alert that fires again within cooldown since the last notification is not notified.
+    #[serde(default)]
+    pub cooldown_secs: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Alerting of a task.
+pub struct AlertConfig {
+    pub rules: Vec<AlertRule>,
+    /// Notifiers of fired and resolved alerts, alerts are logged by default.
+    #[serde(default = "default_notifiers")]
+    pub notifiers: Vec<Notifier>,
+}
+
+fn default_notifiers() -> Vec<Notifier> {
+    vec![Notifier::Log]
+}
+
+impl AlertConfig {
+    /// Validates rules, their expressions are parsed with functions of given Registry.
+    pub fn validate(&self, registry: &Registry) -> Result<()> {
+        let mut names = HashSet::new();
+        for rule in &self.rules {
+            if rule.name.is_empty() {
+                return Err(Error::new_validation(
+                    "AlertConfig",
+                    "rule name cannot be empty",
+                    "rules",
+                ));
+            }
+            if !names.insert(rule.name.as_str()) {
+                return Err(Error::new_validation(
+                    "AlertConfig",
+                    "rule names have to be unique",
+                    "rules",
+                ));
+            }
+            // rule name is sent in subject of alert emails.
+            if rule.name.contains(['\r', '\n']) {
+                return Err(Error::new_validation(
+                    "AlertConfig",
+                    "rule name cannot contain line breaks",
+                    "rules",
+                ));
+            }
+            for expr in std::iter::once(&rule.condition).chain(&rule.resolve) {
+                check_expression(expr, registry).map_err(|err| {
+                    Error::new_validation(
+                        "AlertConfig",
+                        format!("invalid {} rule expression {}: {}", rule.name, expr, err).as_str(),
+                        "rules",
+                    )
+                })?;
+            }
+        }
+        for notifier in &self.notifiers {
+            notifier.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// State of task's rule, persisted so firing alerts survive restarts.
+pub struct Alert {
+    pub task_id: Uuid,
+    pub rule: String,
+    pub severity: Severity,
+    pub status: AlertStatus,
+    /// Output that caused the last change of the status.
+    pub value: Value,
+    pub fired_at: DateTime<Utc>,
+    /// Time of the last firing notification, None if alert fired within cooldown.
+    pub notified_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl AlertRule {
+    /// Checks rule against task's output. Returns changed alert together with information
+    /// if notifiers have to be called, None if status of the alert did not change.
+    pub fn check(
+        &self,
+        task_id: Uuid,
+        out: &Variable,
+        last: Option<&Alert>,
+        registry: &Registry,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(Alert, bool)>> {
+        match last {
+            Some(alert) if alert.status == AlertStatus::Firing => {
+                let resolved = match &self.resolve {
+                    Some(resolve) => evaluate(resolve, out, registry)?,
+                    None => !evaluate(&self.condition, out, registry)?,
+                };
+                if !resolved {
+                    return Ok(None);
+                }
+                // resolution is notified only if firing was.
+                let notify = alert.notified_at.map_or(false, |at| at >= alert.fired_at);
+                let alert = Alert {
+                    status: AlertStatus::Resolved,
+                    value: Value::from(out.clone()),
+                    resolved_at: Some(now),
+                    ..alert.clone()
+                };
+                Ok(Some((alert, notify)))
+            }
+            _ => {
+                if !evaluate(&self.condition, out, registry)? {
+                    return Ok(None);
+                }
+                let notified_at = last.and_then(|alert| alert.notified_at);
+                let notify = notified_at.map_or(true, |at| {
+                    now - at >= Duration::seconds(self.cooldown_secs as i64)
+                });
+                let alert = Alert {
+                    task_id,
+                    rule: self.name.clone(),
+                    severity: self.severity,
+                    status: AlertStatus::Firing,
+                    value: Value::from(out.clone()),
+                    fired_at: now,
+                    notified_at: if notify { Some(now) } else { notified_at },
+                    resolved_at: None,
+                };
+                Ok(Some((alert, notify)))
+            }
+        }
+    }
+}
+
+/// Parses rule's expression and checks used functions and their arguments count,
+/// so the expression does not fail on every run.
+fn check_expression(expr: &str, registry: &Registry) -> Result<()> {
+    let tokens = Lexer::new_with_registry(expr, registry).make_tokens()?;
+    let node = Parser::new(tokens).parse()?;
+    check_arguments(&node, registry)
+}
+
+/// Checks arguments count of every function in parsed expression.
+fn check_arguments(node: &Node, registry: &Registry) -> Result<()> {
+    if let NodeEnum::Keyword(keyword) = &node.value {
+        match keyword {
+            Keyword::Native(name) => {
+                let function = registry.get(name).ok_or_else(|| {
+                    Error::new_eval_internal(
+                        String::from("check_arguments"),
+                        format!("unknown function {}", name),
+                    )
+                })?;
+                if function.arity() != node.nodes.len() {
+                    return Err(Error::new_eval_internal(
+                        String::from("check_arguments"),
+                        format!(
+                            "function {} wanted {} arguments, got {}",
+                            name,
+                            function.arity(),
+                            node.nodes.len()
+                        ),
+                    ));
+                }
+            }
+            keyword => keyword.check_arity(node.nodes.len())?,
+        }
+    }
+    node.nodes
+        .iter()
+        .try_for_each(|node| check_arguments(node, registry))
+}
+
+/// Evaluates rule's expression with output set as IN.
+fn evaluate(expr: &str, out: &Variable, registry: &Registry) -> Result<bool> {
+    let process = Process::new(
+        "alert",
+        vec![Definition::new(vec![format!("DEFINE(OUT, {})", expr)])],
+        None,
+    );
+    let mut engine = Engine::builder()
+        .with_registry(registry.clone())
+        .build(out.clone(), process)?;
+    engine.fire()?;
+    match engine.get("OUT") {
+        Some(Variable::Bool(value)) => Ok(*value),
+        other => Err(Error::new_internal(
+            String::from("AlertRule::evaluate"),
+            format!("{} expression has to return BOOL", expr),
+            format!("{:?}", other),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alert, AlertConfig, AlertRule, AlertStatus, Severity};
+    use crate::core::notifier::Notifier;
+    use crate::lang::registry::{NativeFunction, Registry};
+    use crate::lang::variable::Variable;
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    fn rule(resolve: Option<&str>) -> AlertRule {
+        AlertRule {
+            name: String::from("price"),
+            condition: String::from("GT(IN, INT(100))"),
+            resolve: resolve.map(String::from),
+            severity: Severity::Critical,
+            cooldown_secs: 60,
+        }
+    }
+
+    #[test]
+    fn test_alert_rule_check() {
+        let id = Uuid::new_v4();
+        let registry = Registry::default();
+        let rule = rule(None);
+        let now = Utc::now();
+        let check = |out: isize, last: Option<&Alert>, now| {
+            rule.check(id, &Variable::Int(out), last, &registry, now)
+                .unwrap()
+        };
+
+        assert!(check(50, None, now).is_none());
+
+        let (fired, notify) = check(120, None, now).unwrap();
+        assert!(notify);
+        assert_eq!(fired.status, AlertStatus::Firing);
+        assert_eq!(fired.severity, Severity::Critical);
+        assert_eq!(fired.notified_at, Some(now));
+
+        // firing alert does not change till it's resolved.
+        assert!(check(130, Some(&fired), now).is_none());
+
+        let (resolved, notify) = check(90, Some(&fired), now).unwrap();
+        assert!(notify);
+        assert_eq!(resolved.status, AlertStatus::Resolved);
+        assert_eq!(resolved.resolved_at, Some(now));
+
+        // alert fires again within cooldown, nothing is notified.
+        let later = now + Duration::seconds(30);
+        let (refired, notify) = check(120, Some(&resolved), later).unwrap();
+        assert!(!notify);
+        assert_eq!(refired.notified_at, Some(now));
+        let (_, notify) = check(90, Some(&refired), later).unwrap();
+        assert!(!notify);
+
+        let later = now + Duration::seconds(60);
+        let (_, notify) = check(120, Some(&resolved), later).unwrap();
+        assert!(notify);
+    }
+
+    #[test]
+    fn test_alert_rule_resolve() {
+        let id = Uuid::new_v4();
+        let registry = Registry::default();
+        // hysteresis, alert fires above 100 and resolves below 80.
+        let rule = rule(Some("LT(IN, INT(80))"));
+        let (fired, _) = rule
+            .check(id, &Variable::Int(120), None, &registry, Utc::now())
+            .unwrap()
+            .unwrap();
+        assert!(rule
+            .check(id, &Variable::Int(90), Some(&fired), &registry, Utc::now())
+            .unwrap()
+            .is_none());
+        assert!(rule
+            .check(id, &Variable::Int(70), Some(&fired), &registry, Utc::now())
+            .unwrap()
+            .is_some());
+
+        let rule = AlertRule {
+            condition: String::from("ADD(IN, INT(1))"),
+            ..rule
+        };
+        assert!(rule
+            .check(id, &Variable::Int(1), None, &registry, Utc::now())
+            .is_err());
+    }
+
+    #[test]
+    fn test_alert_config() {
+        let config: AlertConfig = serde_json::from_str(
+            r#"{"rules": [{"name": "price", "condition": "GT(IN, INT(100))"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.notifiers, vec![Notifier::Log]);
+        assert_eq!(config.rules[0].severity, Severity::Warning);
+        let registry = Registry::default();
+        assert!(config.validate(&registry).is_ok());
+
+        let mut duplicated = config.clone();
+        duplicated.rules.push(config.rules[0].clone());
+        assert!(duplicated.validate(&registry).is_err());
+
+        for invalid in [
+            "LT(IN, INT(80)",
+            "LT(IN, INT(80)))",
+            "LT(IN, INT(80), INT(1))",
+            "BELOW(IN, INT(80))",
+            "IN",
+            "LT(IN, 'open)",
+            "LT(IN, INT(-1))",
+            "",
+            "LT(IN, INT(80)) GT(IN, INT(1))",
+        ] {
+            let mut config = config.clone();
+            config.rules[0].resolve = Some(String::from(invalid));
+            assert!(config.validate(&registry).is_err(), "{}", invalid);
+        }
+
+        // rule name is sent in email subject.
+        let mut injected = config.clone();
+        injected.rules[0].name = String::from("price\r\nBcc: other@localhost");
+        assert!(injected.validate(&registry).is_err());
+
+        // native functions are resolved against registry.
+        let mut native = config;
+        native.rules[0].condition = String::from("ABOVE(IN)");
+        assert!(native.validate(&registry).is_err());
+        let mut registry = Registry::default();
+        registry.register(Above).unwrap();
+        assert!(native.validate(&registry).is_ok());
+        native.rules[0].condition = String::from("ABOVE(IN, INT(1))");
+        assert!(native.validate(&registry).is_err());
+    }
+
+    struct Above;
+
+    impl NativeFunction for Above {
+        fn name(&self) -> &str {
+            "ABOVE"
+        }
+
+        fn arity(&self) -> usize {
+            1
+        }
+
+        fn evaluate(&self, _: &[Variable]) -> crate::error::types::Result<Variable> {
+            Ok(Variable::Bool(true))
+        }
+    }
+}
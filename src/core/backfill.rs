@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackfillStatus {
+    Running,
+    Finished,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Progress of the last backfill of a task, saved after every handled input.
+pub struct Backfill {
+    pub task_id: Uuid,
+    /// Number of inputs to backfill.
+    pub total: i32,
+    /// Number of handled inputs, both written and failed ones.
+    pub processed: i32,
+    pub failed: i32,
+    pub status: BackfillStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl Backfill {
+    pub fn new(task_id: Uuid, total: usize) -> Self {
+        Self {
+            task_id,
+            total: total as i32,
+            processed: 0,
+            failed: 0,
+            status: BackfillStatus::Running,
+            started_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+
+    /// Counts handled input.
+    pub fn record(&mut self, failed: bool) {
+        self.processed += 1;
+        if failed {
+            self.failed += 1;
+        }
+    }
+
+    pub fn finish(&mut self, status: BackfillStatus) {
+        self.status = status;
+        self.finished_at = Some(Utc::now());
+    }
+}
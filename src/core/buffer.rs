@@ -0,0 +1,343 @@
+use super::inbox::InboxEntry;
+use super::task::InputData;
+use crate::error::types::LogExt;
+use crate::persistance::interface::Db;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+/// Behavior of InputBuffer when input arrives and buffer is full.
+pub enum OverflowPolicy {
+    #[default]
+    Block, // wait until handler takes input, slows producer down.
+    DropOldest, // discard the oldest buffered input.
+    DropNewest, // discard the arriving input.
+    Coalesce,   // keep only the latest input, everything buffered is discarded.
+}
+
+fn default_capacity() -> usize {
+    10
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// Configuration of buffer between task's input source and its handler.
+pub struct BufferConfig {
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+
+    #[serde(default)]
+    pub overflow: OverflowPolicy,
+
+    /// Inputs are persisted in task's inbox, so they survive restarts.
+    #[serde(default)]
+    pub durable: bool,
+}
+
+impl Default for BufferConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_capacity(),
+            overflow: OverflowPolicy::default(),
+            durable: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// Configuration of micro-batching, inputs are accumulated and written to a spreadsheet at once.
+/// Batch is closed when it has max_items inputs or max_wait_ms passed since its first input.
+pub struct BatchConfig {
+    pub max_items: usize,
+    pub max_wait_ms: u64,
+}
+
+/// Input waiting in the buffer, with id of its inbox entry if buffer is durable.
+struct Buffered {
+    input_data: InputData,
+    entry_id: Option<Uuid>,
+}
+
+/// Persisted copy of buffer's inputs.
+#[derive(Clone)]
+struct Inbox {
+    db: Db,
+    task_id: Uuid,
+}
+
+/// Bounded queue of inputs waiting to be handled, full buffer is handled according to OverflowPolicy.
+/// Buffer is cheap to clone, clones share the same queue.
+#[derive(Clone)]
+pub struct InputBuffer {
+    config: BufferConfig,
+    queue: Arc<Mutex<VecDeque<Buffered>>>,
+    dropped: Arc<AtomicI64>,
+    pushed: Arc<Notify>,
+    popped: Arc<Notify>,
+    inbox: Option<Inbox>,
+    /// Inbox entries of popped inputs which handling is not finished yet.
+    in_flight: Arc<Mutex<Vec<Uuid>>>,
+}
+
+impl InputBuffer {
+    pub fn new(config: BufferConfig) -> Self {
+        Self {
+            config,
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            dropped: Arc::new(AtomicI64::new(0)),
+            pushed: Arc::new(Notify::new()),
+            popped: Arc::new(Notify::new()),
+            inbox: None,
+            in_flight: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Persists pushed inputs in task's inbox, entries are removed when inputs are acknowledged or dropped.
+    pub fn with_inbox(mut self, db: Db, task_id: Uuid) -> Self {
+        self.inbox = Some(Inbox { db, task_id });
+        self
+    }
+
+    /// Queues inputs read from task's inbox, e.g. after restart. Capacity of the buffer is not checked.
+    pub fn restore(&self, entries: Vec<InboxEntry>) {
+        self.queue
+            .lock()
+            .unwrap()
+            .extend(entries.into_iter().map(|entry| Buffered {
+                input_data: entry.input,
+                entry_id: Some(entry.id),
+            }));
+        self.pushed.notify_waiters();
+    }
+
+    /// Adds input to the buffer, waits for free space only with OverflowPolicy::Block.
+    /// Input is saved in the inbox before it's queued.
+    pub async fn push(&self, input_data: InputData) {
+        let capacity = self.config.capacity.max(1);
+        let mut entry_id = None;
+        if let Some(inbox) = &self.inbox {
+            let entry = InboxEntry::new(inbox.task_id, input_data.clone());
+            if inbox.db.save_inbox_entry(&entry).await.log().is_ok() {
+                entry_id = Some(entry.id);
+            }
+        }
+        let buffered = Buffered {
+            input_data,
+            entry_id,
+        };
+        let discarded = loop {
+            let popped = self.popped.notified();
+            tokio::pin!(popped);
+            popped.as_mut().enable();
+            {
+                let mut queue = self.queue.lock().unwrap();
+                if queue.len() < capacity && self.config.overflow != OverflowPolicy::Coalesce {
+                    queue.push_back(buffered);
+                    break Vec::new();
+                }
+                match self.config.overflow {
+                    OverflowPolicy::Block => {}
+                    OverflowPolicy::DropOldest => {
+                        let oldest = queue.pop_front();
+                        queue.push_back(buffered);
+                        break oldest.into_iter().collect();
+                    }
+                    OverflowPolicy::DropNewest => break vec![buffered],
+                    OverflowPolicy::Coalesce => {
+                        let discarded = queue.drain(..).collect();
+                        queue.push_back(buffered);
+                        break discarded;
+                    }
+                }
+            }
+            debug!("input buffer is full, waiting");
+            popped.await;
+        };
+        self.pushed.notify_waiters();
+        self.drop_inputs(discarded).await;
+    }
+
+    /// Takes the oldest input, waits if buffer is empty. Method is cancel safe.
+    pub async fn pop(&self) -> InputData {
+        loop {
+            let pushed = self.pushed.notified();
+            tokio::pin!(pushed);
+            pushed.as_mut().enable();
+            if let Some(buffered) = self.queue.lock().unwrap().pop_front() {
+                self.popped.notify_waiters();
+                self.in_flight.lock().unwrap().extend(buffered.entry_id);
+                return buffered.input_data;
+            }
+            pushed.await;
+        }
+    }
+
+    /// Collects batch that starts with given input, waits for more inputs as long as batch is open.
+    pub async fn fill_batch(&self, first: InputData, config: &BatchConfig) -> Vec<InputData> {
+        let deadline = Instant::now() + Duration::from_millis(config.max_wait_ms);
+        let mut batch = vec![first];
+        while batch.len() < config.max_items {
+            match tokio::time::timeout_at(deadline, self.pop()).await {
+                Ok(input_data) => batch.push(input_data),
+                Err(_) => break,
+            }
+        }
+        batch
+    }
+
+    /// Returns number of inputs discarded because of overflow.
+    pub fn dropped(&self) -> i64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes inbox entries of popped inputs, should be called when their handling is finished.
+    pub async fn ack(&self) {
+        let entry_ids = std::mem::take(&mut *self.in_flight.lock().unwrap());
+        self.remove_entries(entry_ids).await;
+    }
+
+    async fn drop_inputs(&self, discarded: Vec<Buffered>) {
+        if discarded.is_empty() {
+            return;
+        }
+        warn!("input buffer overflow, dropping {} inputs", discarded.len());
+        self.dropped
+            .fetch_add(discarded.len() as i64, Ordering::Relaxed);
+        self.remove_entries(discarded.into_iter().filter_map(|b| b.entry_id).collect())
+            .await;
+    }
+
+    async fn remove_entries(&self, entry_ids: Vec<Uuid>) {
+        if let Some(inbox) = &self.inbox {
+            for id in entry_ids {
+                let _ = inbox.db.delete_inbox_entry(id).await.log();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchConfig, BufferConfig, InputBuffer, OverflowPolicy};
+    use crate::core::task::InputData;
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn input(value: &str) -> InputData {
+        InputData::String(String::from(value))
+    }
+
+    fn new_buffer(overflow: OverflowPolicy) -> InputBuffer {
+        InputBuffer::new(BufferConfig {
+            capacity: 2,
+            overflow,
+            durable: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_drop_policies() {
+        let buffer = new_buffer(OverflowPolicy::DropOldest);
+        for value in ["1", "2", "3"] {
+            buffer.push(input(value)).await;
+        }
+        assert_eq!(buffer.dropped(), 1);
+        assert_eq!(buffer.pop().await, input("2"));
+        assert_eq!(buffer.pop().await, input("3"));
+
+        let buffer = new_buffer(OverflowPolicy::DropNewest);
+        for value in ["1", "2", "3"] {
+            buffer.push(input(value)).await;
+        }
+        assert_eq!(buffer.dropped(), 1);
+        assert_eq!(buffer.pop().await, input("1"));
+        assert_eq!(buffer.pop().await, input("2"));
+
+        let buffer = new_buffer(OverflowPolicy::Coalesce);
+        for value in ["1", "2", "3"] {
+            buffer.push(input(value)).await;
+        }
+        assert_eq!(buffer.dropped(), 2);
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.pop().await, input("3"));
+    }
+
+    #[tokio::test]
+    async fn test_block() {
+        let buffer = new_buffer(OverflowPolicy::Block);
+        buffer.push(input("1")).await;
+        buffer.push(input("2")).await;
+
+        let producer = buffer.clone();
+        let push = tokio::spawn(async move { producer.push(input("3")).await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!push.is_finished());
+
+        assert_eq!(buffer.pop().await, input("1"));
+        push.await.unwrap();
+        assert_eq!(buffer.dropped(), 0);
+        assert_eq!(buffer.pop().await, input("2"));
+        assert_eq!(buffer.pop().await, input("3"));
+    }
+
+    #[tokio::test]
+    async fn test_fill_batch() {
+        let buffer = InputBuffer::new(BufferConfig::default());
+        let config = BatchConfig {
+            max_items: 3,
+            max_wait_ms: 100,
+        };
+        for value in ["2", "3", "4"] {
+            buffer.push(input(value)).await;
+        }
+        // batch is closed after max_items.
+        let batch = buffer.fill_batch(input("1"), &config).await;
+        assert_eq!(batch, vec![input("1"), input("2"), input("3")]);
+
+        // batch is closed after max_wait_ms.
+        let batch = buffer.fill_batch(buffer.pop().await, &config).await;
+        assert_eq!(batch, vec![input("4")]);
+    }
+
+    #[tokio::test]
+    async fn test_inbox() {
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let task_id = Uuid::new_v4();
+        let buffer = new_buffer(OverflowPolicy::DropOldest).with_inbox(db.clone(), task_id);
+        for value in ["1", "2", "3"] {
+            buffer.push(input(value)).await;
+        }
+        // entry of dropped input is removed.
+        let entries = db.read_inbox(task_id).await.unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // entry is kept until input is acknowledged.
+        assert_eq!(buffer.pop().await, input("2"));
+        assert_eq!(db.read_inbox(task_id).await.unwrap().len(), 2);
+        buffer.ack().await;
+        assert_eq!(db.read_inbox(task_id).await.unwrap().len(), 1);
+
+        // restored buffer gets inputs that were not acknowledged.
+        let restored = new_buffer(OverflowPolicy::DropOldest).with_inbox(db.clone(), task_id);
+        restored.restore(db.read_inbox(task_id).await.unwrap());
+        assert_eq!(restored.pop().await, input("3"));
+        restored.ack().await;
+        assert!(db.read_inbox(task_id).await.unwrap().is_empty());
+    }
+}
@@ -8,6 +8,8 @@ use super::task::InputData;
 pub struct ChannelsManager {
     pub clicked_channels: Arc<Mutex<HashMap<Uuid, Sender<()>>>>,
     pub triggered_channels: Arc<Mutex<HashMap<Uuid, Sender<InputData>>>>,
+    /// Tasks triggered by evaluated data of upstream task, keyed by upstream task id.
+    pub downstream_tasks: Arc<Mutex<HashMap<Uuid, Vec<Uuid>>>>,
 }
 
 impl ChannelsManager {
@@ -18,4 +20,41 @@ impl ChannelsManager {
     pub async fn add_triggered(&self, uuid: Uuid, s: Sender<InputData>) {
         self.triggered_channels.lock().await.insert(uuid, s);
     }
+
+    pub async fn add_downstream(&self, upstream: Uuid, downstream: Uuid) {
+        let mut downstream_tasks = self.downstream_tasks.lock().await;
+        let tasks = downstream_tasks.entry(upstream).or_default();
+        if !tasks.contains(&downstream) {
+            tasks.push(downstream);
+        }
+    }
+
+    pub async fn remove_downstream(&self, upstream: Uuid, downstream: Uuid) {
+        if let Some(tasks) = self.downstream_tasks.lock().await.get_mut(&upstream) {
+            tasks.retain(|uuid| *uuid != downstream);
+        }
+    }
+
+    /// Sends data to triggered channels of all downstream tasks of given task.
+    pub async fn send_downstream(&self, upstream: Uuid, input_data: InputData) {
+        let downstream = self
+            .downstream_tasks
+            .lock()
+            .await
+            .get(&upstream)
+            .cloned()
+            .unwrap_or_default();
+        for uuid in downstream {
+            // clone sender, so the lock is not held while waiting for a free place in the channel.
+            let sender = self.triggered_channels.lock().await.get(&uuid).cloned();
+            match sender {
+                Some(sender) => {
+                    if sender.send(input_data.clone()).await.is_err() {
+                        warn!("downstream task {} is not running", uuid);
+                    }
+                }
+                None => warn!("downstream task {} has no triggered channel", uuid),
+            }
+        }
+    }
 }
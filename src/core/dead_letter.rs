@@ -0,0 +1,39 @@
+use super::retry::RetryPhase;
+use super::task::InputData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Input which handling failed after all retries, kept so it can be replayed or discarded.
+pub struct DeadLetter {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub input: InputData,
+    /// Phase in which handling failed.
+    pub phase: RetryPhase,
+    pub error: String,
+    /// Number of attempts made before giving up.
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    pub fn new(
+        task_id: Uuid,
+        input: InputData,
+        phase: RetryPhase,
+        error: String,
+        attempts: u32,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            task_id,
+            input,
+            phase,
+            error,
+            attempts: attempts as i32,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::types::{Error, Result};
+use crate::lang::variable::Variable;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Replaces numbers in hashed structure of output, so they can be compared with tolerance.
+const NUMBER_PLACEHOLDER: &str = "\u{0}number";
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+/// Enables change detection, write is skipped if output did not change since the last write.
+pub struct DedupConfig {
+    /// Numbers that differ by at most tolerance are considered equal, output has to be identical by default.
+    #[serde(default)]
+    pub tolerance: f64,
+}
+
+impl DedupConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.tolerance.is_nan() || self.tolerance < 0.0 {
+            return Err(Error::new_validation(
+                "DedupConfig",
+                "tolerance has to be a non-negative number",
+                "tolerance",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Fingerprint of task's output. Hash covers structure and non-numeric values of the output,
+/// numbers are kept aside so they can be compared with tolerance.
+pub struct Fingerprint {
+    pub task_id: Uuid,
+    pub hash: String,
+    pub numbers: Vec<f64>,
+}
+
+impl Fingerprint {
+    pub fn new(task_id: Uuid, out: &Variable) -> Self {
+        let mut numbers = vec![];
+        let skeleton = extract_numbers(Value::from(out.clone()), &mut numbers);
+        Self {
+            task_id,
+            hash: hex::encode(Sha256::digest(skeleton.to_string().as_bytes())),
+            numbers,
+        }
+    }
+
+    /// Checks if both fingerprints describe the same output, numbers can differ by at most tolerance.
+    pub fn matches(&self, other: &Fingerprint, tolerance: f64) -> bool {
+        self.hash == other.hash
+            && self.numbers.len() == other.numbers.len()
+            && self
+                .numbers
+                .iter()
+                .zip(&other.numbers)
+                .all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+}
+
+/// Moves numbers of the value into numbers, in order of appearance, and returns value with placeholders.
+/// Keys of json objects are sorted, so order of numbers is stable.
+fn extract_numbers(value: Value, numbers: &mut Vec<f64>) -> Value {
+    match value {
+        Value::Number(n) => {
+            numbers.push(n.as_f64().unwrap_or_default());
+            Value::String(String::from(NUMBER_PLACEHOLDER))
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| extract_numbers(item, numbers))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, extract_numbers(v, numbers)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DedupConfig, Fingerprint};
+    use crate::lang::variable::Variable;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_fingerprint_matches() {
+        let id = Uuid::new_v4();
+        let price = |p: f32| {
+            Fingerprint::new(
+                id,
+                &Variable::Vector(vec![
+                    Variable::String(String::from("BTC")),
+                    Variable::Float(p),
+                ]),
+            )
+        };
+        assert!(price(1.5).matches(&price(1.5), 0.0));
+        assert!(!price(1.5).matches(&price(1.75), 0.0));
+        assert!(price(1.5).matches(&price(1.75), 0.5));
+
+        // strings are always compared exactly.
+        let other = Fingerprint::new(
+            id,
+            &Variable::Vector(vec![
+                Variable::String(String::from("ETH")),
+                Variable::Float(1.5),
+            ]),
+        );
+        assert!(!price(1.5).matches(&other, 10.0));
+
+        // number is not confused with null.
+        let a = Fingerprint::new(id, &Variable::Json(json!([null, 1])));
+        let b = Fingerprint::new(id, &Variable::Json(json!([1, null])));
+        assert!(!a.matches(&b, 0.0));
+    }
+
+    #[test]
+    fn test_dedup_config_validate() {
+        assert!(DedupConfig::default().validate().is_ok());
+        assert!(DedupConfig { tolerance: -1.0 }.validate().is_err());
+        assert!(DedupConfig {
+            tolerance: f64::NAN
+        }
+        .validate()
+        .is_err());
+    }
+}
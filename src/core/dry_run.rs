@@ -0,0 +1,205 @@
+use super::grid::{to_grid_with_columns, Cell, Grid};
+use super::handler::{add_timestamp, evaluate_data, plan_write};
+use super::secret::{redact, redact_error, redact_value};
+use super::task::{InputData, TrackingTask};
+use crate::error::types::{Error, Result};
+use crate::lang::engine::TraceEntry;
+use crate::lang::registry::Registry;
+use crate::persistance::interface::Db;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+/// Outcome of a task's run that was evaluated without being written. Values of used secrets are redacted.
+pub struct DryRun {
+    /// Input the task was evaluated with, sample one or fetched with task's input.
+    pub input: InputData,
+    pub out: Value,
+    /// Cells that would be written, timestamp included. Overwrite of a range pads them to its size.
+    pub grid: Grid,
+    /// A1 range the grid would be written to. Upsert writes lines with known keys in place,
+    /// so for upsert tasks it's a range the grid would take if all keys were new.
+    pub range: String,
+    /// Every evaluated step of the process, with variables it changed.
+    pub trace: Vec<TraceEntry>,
+}
+
+/// Evaluates task's process like its handler would and returns what would be written, without writing it.
+/// Input is fetched with task's input if no sample is given. Location of the task is only read.
+pub async fn dry_run(
+    mut task: TrackingTask,
+    db: &Db,
+    registry: &Registry,
+    sample: Option<InputData>,
+) -> Result<DryRun> {
+    let input = match sample {
+        Some(input) => input,
+        None => {
+            task.resolve_secrets(db).await?;
+            task.data().await?
+        }
+    };
+
+    let process = task.process.clone();
+    let secrets = db.resolve_secrets(&process.referenced_secrets()).await?;
+    let params = process.resolve_params(&task.params)?;
+
+    // evaluation may perform blocking http calls.
+    let (engine_input, registry, engine_secrets) =
+        (input.clone(), registry.clone(), secrets.clone());
+    let evaluated = tokio::task::spawn_blocking(move || {
+        evaluate_data(
+            engine_input,
+            process,
+            &registry,
+            engine_secrets,
+            params,
+            true,
+        )
+    })
+    .await
+    .map_err(|err| {
+        Error::new_internal(
+            String::from("dry_run"),
+            String::from("failed to evaluate task"),
+            err.to_string(),
+        )
+    })?;
+    let secrets: Vec<String> = secrets.into_values().collect();
+    let (out, trace) = evaluated.map_err(|err| redact_error(err, &secrets))?;
+
+    // columns are read but not saved, so dry run doesn't change order of the next write.
+    let mut columns = db.read_columns(task.id).await?.unwrap_or_default();
+    let grid = to_grid_with_columns(out.clone(), &mut columns);
+    let grid = add_timestamp(&task, grid, Utc::now());
+    let last_place = db.get(&task.id).await.unwrap_or(0);
+    let (grid, offset) = plan_write(&task, grid, last_place)?;
+    let range = match grid.first() {
+        Some(line) => task.write_range(offset, grid.len() as u32, line.len() as u32)?,
+        None => task.starting_range()?.to_string(),
+    };
+
+    Ok(DryRun {
+        input,
+        out: redact_value(Value::from(out), &secrets),
+        grid: redact_grid(grid, &secrets),
+        range,
+        trace: trace
+            .into_iter()
+            .map(|entry| TraceEntry {
+                step: entry.step,
+                result: redact_value(entry.result, &secrets),
+                changed: entry
+                    .changed
+                    .into_iter()
+                    .map(|(name, value)| (name, redact_value(value, &secrets)))
+                    .collect(),
+            })
+            .collect(),
+    })
+}
+
+fn redact_grid(grid: Grid, secrets: &[String]) -> Grid {
+    grid.into_iter()
+        .map(|line| {
+            line.into_iter()
+                .map(|cell| match cell {
+                    Cell::String(s) => Cell::String(redact(&s, secrets)),
+                    other => other,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dry_run;
+    use crate::core::grid::Cell;
+    use crate::core::task::{InputData, TrackingTask};
+    use crate::core::types::Direction;
+    use crate::core::write_mode::WriteMode;
+    use crate::lang::process::{Definition, Process};
+    use crate::lang::registry::Registry;
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::task::TaskKindRequest;
+    use serde_json::json;
+
+    fn task(steps: Vec<&str>) -> TrackingTask {
+        let mut task = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "B2".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Clicked,
+        );
+        task.process = Process::new("dry run", vec![Definition::new(steps)], None);
+        task
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let task = task(vec!["DEFINE(OUT, VEC(GET(IN), INT(2)))"]);
+        db.save(task.id, 3).await.unwrap();
+
+        let input = InputData::String(String::from("BTC"));
+        let result = dry_run(task.clone(), &db, &Registry::default(), Some(input.clone()))
+            .await
+            .unwrap();
+        assert_eq!(result.input, input);
+        assert_eq!(result.out, json!(["BTC", 2]));
+        assert_eq!(
+            result.grid,
+            vec![vec![Cell::String(String::from("BTC")), Cell::Int(2)]]
+        );
+        assert_eq!(result.range, "B5:C5");
+        assert_eq!(result.trace.len(), 1);
+        assert_eq!(result.trace[0].changed.get("OUT"), Some(&json!(["BTC", 2])));
+        // location is only read.
+        assert_eq!(db.get(&task.id).await.unwrap(), 3);
+
+        let mut overwrite = task;
+        overwrite.write_mode = WriteMode::Overwrite;
+        let result = dry_run(
+            overwrite.clone(),
+            &db,
+            &Registry::default(),
+            Some(input.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.range, "B2:C2");
+
+        // overwrite of a range is padded to its size, grid that doesn't fit fails like the write would.
+        overwrite.starting_position = String::from("B2:D3");
+        let result = dry_run(
+            overwrite.clone(),
+            &db,
+            &Registry::default(),
+            Some(input.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.range, "B2:D3");
+        assert_eq!(result.grid.len(), 2);
+        assert_eq!(result.grid[1], vec![Cell::Empty; 3]);
+        overwrite.starting_position = String::from("B2:B3");
+        assert!(dry_run(overwrite, &db, &Registry::default(), Some(input))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_without_input() {
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        // task without input has nothing to fetch.
+        let task = task(vec!["DEFINE(OUT, INT(1))"]);
+        assert!(dry_run(task, &db, &Registry::default(), None)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::lang::variable::{serde_value_to_variable, Variable};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Display};
+
+/// Typed value of a single spreadsheet cell.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Cell {
+    Empty,
+    Bool(bool),
+    Int(isize),
+    Float(f32),
+    String(String),
+    Timestamp(String), // formatted date, spreadsheet parses it as a date.
+}
+
+/// Cells of a single write. Every line is a row for vertical tasks and a column for horizontal ones.
+pub type Grid = Vec<Vec<Cell>>;
+
+impl Cell {
+    /// Returns value as it would be typed by a user, so spreadsheet keeps numbers and booleans typed.
+    /// Strings are prefixed with an apostrophe, so they are never parsed as numbers, dates or formulas.
+    pub fn to_user_entered(&self) -> String {
+        match self {
+            Cell::Empty => String::default(),
+            Cell::Bool(b) => String::from(if *b { "TRUE" } else { "FALSE" }),
+            Cell::Int(i) => i.to_string(),
+            Cell::Float(f) => f.to_string(),
+            Cell::String(s) => format!("'{}", s),
+            Cell::Timestamp(t) => t.clone(),
+        }
+    }
+}
+
+impl Display for Cell {
+    /// Formats value as it's displayed by a spreadsheet, so cells can be compared with values read from it.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cell::String(s) => write!(f, "{}", s),
+            other => write!(f, "{}", other.to_user_entered()),
+        }
+    }
+}
+
+impl From<Variable> for Cell {
+    /// Scalars are kept typed, nested vectors and objects are written as json.
+    fn from(variable: Variable) -> Self {
+        match variable {
+            Variable::None => Cell::Empty,
+            Variable::Bool(b) => Cell::Bool(b),
+            Variable::Int(i) => Cell::Int(i),
+            Variable::Float(f) => Cell::Float(f),
+            Variable::String(s) => Cell::String(s),
+            nested => Cell::String(Value::from(nested).to_string()),
+        }
+    }
+}
+
+/// Converts evaluated data into a grid. Scalar is a single cell, vector is a single line,
+/// vector of vectors is a line per inner vector and objects are lines with values ordered by keys.
+pub fn to_grid(data: Variable) -> Grid {
+    to_grid_with_columns(data, &mut Vec::new())
+}
+
+/// Converts evaluated data into a grid like `to_grid`, values of objects are ordered by known `columns`.
+/// Keys that are not known yet are appended to `columns` in sorted order, missing keys are empty cells.
+pub fn to_grid_with_columns(data: Variable, columns: &mut Vec<String>) -> Grid {
+    let mut grid = match unwrap_json(data) {
+        Variable::Vector(items) if !items.is_empty() && items.iter().all(is_object) => {
+            objects_to_grid(items, columns)
+        }
+        Variable::Vector(items) if items.iter().any(|item| matches!(item, Variable::Vector(_))) => {
+            items
+                .into_iter()
+                .map(|item| match item {
+                    Variable::Vector(cells) => cells.into_iter().map(Cell::from).collect(),
+                    scalar => vec![Cell::from(scalar)],
+                })
+                .collect()
+        }
+        Variable::Vector(items) => vec![items.into_iter().map(Cell::from).collect()],
+        object @ Variable::Object(_) => objects_to_grid(vec![object], columns),
+        scalar => vec![vec![Cell::from(scalar)]],
+    };
+    pad(&mut grid);
+    grid
+}
+
+/// Pads lines with empty cells, so every line has the same length and grid is not empty.
+pub fn pad(grid: &mut Grid) {
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0).max(1);
+    if grid.is_empty() {
+        grid.push(Vec::new());
+    }
+    for line in grid.iter_mut() {
+        line.resize(width, Cell::Empty);
+    }
+}
+
+fn is_object(variable: &Variable) -> bool {
+    matches!(variable, Variable::Object(_))
+}
+
+/// Every object is a line, new keys extend known columns so order is stable between writes.
+fn objects_to_grid(objects: Vec<Variable>, columns: &mut Vec<String>) -> Grid {
+    let objects: Vec<HashMap<String, Variable>> = objects
+        .into_iter()
+        .filter_map(|object| match object {
+            Variable::Object(map) => Some(map),
+            _ => None,
+        })
+        .collect();
+    let new_keys: BTreeSet<&String> = objects
+        .iter()
+        .flat_map(|map| map.keys())
+        .filter(|key| !columns.contains(*key))
+        .collect();
+    columns.extend(new_keys.into_iter().cloned());
+    objects
+        .iter()
+        .map(|map| {
+            columns
+                .iter()
+                .map(|key| map.get(key).cloned().map_or(Cell::Empty, Cell::from))
+                .collect()
+        })
+        .collect()
+}
+
+/// Translates json into Variables, so json arrays and objects are converted the same way as native ones.
+fn unwrap_json(variable: Variable) -> Variable {
+    match variable {
+        Variable::Json(json) => unwrap_json(serde_value_to_variable(json)),
+        Variable::Vector(items) => Variable::Vector(items.into_iter().map(unwrap_json).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_grid, to_grid_with_columns, Cell};
+    use crate::lang::variable::Variable;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_to_grid() {
+        assert_eq!(to_grid(Variable::Int(1)), vec![vec![Cell::Int(1)]]);
+        assert_eq!(to_grid(Variable::None), vec![vec![Cell::Empty]]);
+        assert_eq!(
+            to_grid(Variable::Vector(vec![
+                Variable::Int(1),
+                Variable::String(String::from("a"))
+            ])),
+            vec![vec![Cell::Int(1), Cell::String(String::from("a"))]]
+        );
+        // ragged lines are padded.
+        assert_eq!(
+            to_grid(Variable::Vector(vec![
+                Variable::Vector(vec![Variable::Int(1), Variable::Int(2)]),
+                Variable::Vector(vec![Variable::Bool(true)]),
+            ])),
+            vec![
+                vec![Cell::Int(1), Cell::Int(2)],
+                vec![Cell::Bool(true), Cell::Empty]
+            ]
+        );
+        assert_eq!(to_grid(Variable::Vector(vec![])), vec![vec![Cell::Empty]]);
+    }
+
+    #[test]
+    fn test_objects_to_grid() {
+        let object = |pairs: &[(&str, Variable)]| {
+            Variable::Object(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.clone()))
+                    .collect::<HashMap<String, Variable>>(),
+            )
+        };
+        let grid = to_grid(Variable::Vector(vec![
+            object(&[("price", Variable::Float(1.5)), ("name", Variable::Int(1))]),
+            object(&[("volume", Variable::Int(10)), ("name", Variable::Int(2))]),
+        ]));
+        // columns: name, price, volume.
+        assert_eq!(
+            grid,
+            vec![
+                vec![Cell::Int(1), Cell::Float(1.5), Cell::Empty],
+                vec![Cell::Int(2), Cell::Empty, Cell::Int(10)],
+            ]
+        );
+
+        let grid = to_grid(Variable::Json(json!([{"b": [1, 2], "a": "x"}])));
+        assert_eq!(
+            grid,
+            vec![vec![
+                Cell::String(String::from("x")),
+                Cell::String(String::from("[1,2]"))
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_to_grid_with_columns() {
+        let mut columns = Vec::new();
+        let grid = to_grid_with_columns(Variable::Json(json!({"c": 3, "a": 1})), &mut columns);
+        assert_eq!(grid, vec![vec![Cell::Int(1), Cell::Int(3)]]);
+        assert_eq!(columns, vec![String::from("a"), String::from("c")]);
+
+        // new key is appended, so cells of known columns keep their place.
+        let grid = to_grid_with_columns(Variable::Json(json!({"b": 2, "c": 4})), &mut columns);
+        assert_eq!(grid, vec![vec![Cell::Empty, Cell::Int(4), Cell::Int(2)]]);
+        assert_eq!(
+            columns,
+            vec![String::from("a"), String::from("c"), String::from("b")]
+        );
+
+        // non-object data ignores columns.
+        let grid = to_grid_with_columns(Variable::Int(1), &mut columns);
+        assert_eq!(grid, vec![vec![Cell::Int(1)]]);
+        assert_eq!(columns.len(), 3);
+    }
+
+    #[test]
+    fn test_to_user_entered() {
+        assert_eq!(Cell::Bool(true).to_user_entered(), "TRUE");
+        assert_eq!(Cell::Float(2.5).to_user_entered(), "2.5");
+        assert_eq!(
+            Cell::String(String::from("=SUM(A1:A2)")).to_user_entered(),
+            "'=SUM(A1:A2)"
+        );
+        assert_eq!(Cell::String(String::from("BTC")).to_string(), "BTC");
+        assert_eq!(Cell::Int(10).to_string(), "10");
+    }
+}
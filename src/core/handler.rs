@@ -1,17 +1,33 @@
+use super::backfill::{Backfill, BackfillStatus};
+use super::buffer::InputBuffer;
 use super::channels::ChannelsManager;
+use super::dead_letter::DeadLetter;
+use super::dedup::Fingerprint;
+use super::grid::{pad, to_grid_with_columns, Cell, Grid};
+use super::inbox::InboxEntry;
 use super::manager::Command;
+use super::pipeline::upstream_of;
+use super::quarantine::QuarantinedInput;
+use super::range::A1Range;
+use super::retry::{RetryPhase, RetryPolicy};
+use super::schedule::{ActiveWindow, OutsideWindow};
+use super::secret::{redact, redact_error, redact_value};
 use super::task::InputData;
 use super::task::TrackingTask;
 use super::types::Direction;
 use super::types::State;
+use super::types::TimestampPosition;
+use super::write_mode::{plan_upsert, WriteMode};
 use crate::core::types::TaskKind;
 use crate::error::types::LogExt;
 use crate::error::types::{Error, Result};
-use crate::lang::engine::Engine;
+use crate::lang::engine::{Engine, TraceEntry};
 use crate::lang::process::Process;
+use crate::lang::registry::Registry;
 use crate::lang::variable::Variable;
 use crate::models::report::ReportModel;
 use crate::persistance::interface::Db;
+use crate::server::task::TaskUpdateRequest;
 use crate::shutdown::Shutdown;
 use crate::wrap::API;
 use anyhow::Context;
@@ -19,13 +35,14 @@ use chrono::{DateTime, Utc};
 use futures::Future;
 use log::info;
 use serde::ser::{Serialize, Serializer};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::result::Result as StdResult;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::mpsc;
-use tokio::sync::mpsc::Receiver;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -35,6 +52,15 @@ pub struct Report {
     start_instant: Option<Instant>, // duration of phase will be calculated from this value.
     pub phases: HashMap<String, u64>,
     pub success: bool,
+    pub error: Option<String>, // describes why handling failed, e.g. invalid input.
+    pub items: Vec<ItemOutcome>, // outcomes of batched inputs, empty if task is not batched.
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+/// Outcome of handling single input of a batch.
+pub struct ItemOutcome {
+    pub input: InputData,
+    pub error: Option<String>, // None if input was written.
 }
 
 impl Serialize for Report {
@@ -48,12 +74,17 @@ impl Serialize for Report {
             start: String,
             pub phases: HashMap<String, u64>,
             pub success: bool,
+            pub error: Option<String>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            pub items: Vec<ItemOutcome>,
         }
         TempReport {
             task_id: self.task_id,
             start: self.start.to_string(),
             phases: self.phases.clone(),
             success: self.success,
+            error: self.error.clone(),
+            items: self.items.clone(),
         }
         .serialize(serializer)
     }
@@ -67,6 +98,8 @@ impl Report {
             start_instant: Some(Instant::now()),
             success: false,
             phases: HashMap::default(),
+            error: None,
+            items: vec![],
         }
     }
 
@@ -77,6 +110,11 @@ impl Report {
             phases: serde_json::from_str(&model.phases).unwrap(),
             success: !model.failed,
             start_instant: None,
+            error: model.error,
+            items: model
+                .items
+                .map(|items| serde_json::from_str(&items).unwrap())
+                .unwrap_or_default(),
         }
     }
 
@@ -95,13 +133,15 @@ impl Report {
         db.save_report(self).await.unwrap();
     }
 
-    async fn run<Fut>(task_id: Uuid, db: Db, func: impl FnOnce(Self) -> Fut)
+    /// Runs func with new report and saves it, returns true if report was marked successful.
+    async fn run<Fut>(task_id: Uuid, db: Db, func: impl FnOnce(Self) -> Fut) -> bool
     where
         Fut: Future<Output = Self>,
     {
         let mut report = func(Report::new(task_id)).await;
         report.add_phase(String::from("EXECUTION"));
-        report.save(db).await
+        report.save(db).await;
+        report.success
     }
 
     async fn section<T, Fut>(&mut self, phase: String, func: impl FnOnce() -> Fut) -> T
@@ -130,6 +170,14 @@ pub struct TaskHandler<A: API> {
     /// Receives Command regarding running task.
     receiver: Receiver<Command>,
     channels_manager: ChannelsManager,
+    /// Native functions available in task's process.
+    registry: Registry,
+    /// Inputs waiting to be handled.
+    buffer: InputBuffer,
+    /// Connector job started by TrackingTask::init_channels, e.g. kafka consumer.
+    background_job: Option<JoinHandle<()>>,
+    /// Job that pushes task's inputs into the buffer.
+    receiver_job: Option<ReceiverJob>,
 }
 
 impl<A> TaskHandler<A>
@@ -145,18 +193,119 @@ where
         receiver: Receiver<Command>,
         channels_manager: ChannelsManager,
     ) -> Self {
+        let buffer = new_buffer(&task, &db);
         TaskHandler {
             db,
             shutdown,
             api,
             receiver,
+            buffer,
             task,
             channels_manager,
+            registry: Registry::default(),
+            background_job: None,
+            receiver_job: None,
+        }
+    }
+
+    /// Sets Registry of native functions used during evaluation.
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Queues inputs persisted in task's inbox before restart, they are handled before new ones.
+    pub fn with_inbox(self, entries: Vec<InboxEntry>) -> Self {
+        if !entries.is_empty() {
+            info!(
+                "restoring {} inputs of {} task from inbox",
+                entries.len(),
+                self.task.info()
+            );
+            self.buffer.restore(entries);
         }
+        self
     }
 
     async fn apply(&mut self, cmd: Command) -> Result<()> {
-        self.change_status(State::from_cmd(cmd)).await
+        if let Some(status) = State::from_cmd(&cmd) {
+            return self.change_status(status).await;
+        }
+        match cmd {
+            Command::Replay(id, input_data) => {
+                // replayed input is handled like any other input, only by running task.
+                if !matches!(self.task.status, State::Created | State::Running) {
+                    warn!(
+                        "{} task is {}, dead letter {} is not replayed",
+                        self.task.info(),
+                        self.task.status,
+                        id
+                    );
+                    return Ok(());
+                }
+                info!("replaying dead letter {} for {} task", id, self.task.info());
+                if self.handle(&input_data, Some(id)).await {
+                    self.db.delete_dead_letter(id).await?;
+                }
+            }
+            Command::Update(update) => {
+                info!("updating {} task", self.task.info());
+                self.update(*update).await?;
+            }
+            Command::CancelBackfill => {
+                info!(
+                    "{} task is not backfilled, nothing to cancel",
+                    self.task.info()
+                );
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Swaps changed fields of the task, it's called between runs so handled input is not affected.
+    /// Input side of the task is restarted only if its kind changed, inputs waiting in the buffer are kept.
+    async fn update(&mut self, update: TaskUpdateRequest) -> Result<()> {
+        let previous_kind = self.task.kind_request.clone();
+        self.task.apply_update(update)?;
+        if self.task.kind_request != previous_kind {
+            if let Some(upstream_id) = upstream_of(&previous_kind) {
+                self.channels_manager
+                    .remove_downstream(upstream_id, self.task.id)
+                    .await;
+            }
+            self.stop_input().await;
+            let _ = self.task.resolve_secrets(&self.db).await.log();
+            self.start_input().await?;
+        }
+        Ok(())
+    }
+
+    /// Creates task's channels and spawns jobs that push its inputs into the buffer.
+    async fn start_input(&mut self) -> Result<()> {
+        self.background_job = self
+            .task
+            .init_channels(&self.channels_manager, self.shutdown.subscribe())
+            .await?;
+        self.receiver_job = Some(receive_input_data(self.task.clone(), self.buffer.clone()));
+        Ok(())
+    }
+
+    /// Stops jobs started by start_input, inputs that already reached task's channel are moved to the buffer.
+    /// Receiver job finishes pushing input it already holds before it stops.
+    async fn stop_input(&mut self) {
+        if let Some(job) = self.receiver_job.take() {
+            job.stop();
+        }
+        if let Some(job) = self.background_job.take() {
+            job.abort();
+        }
+        if let Some(TaskKind::Triggered { ch, ack }) = &self.task.kind {
+            while let Ok(input_data) = ch.lock().await.try_recv() {
+                self.buffer.push(input_data).await;
+                acknowledge(ack.as_ref());
+            }
+        }
     }
 
     async fn change_status(&mut self, status: State) -> Result<()> {
@@ -166,10 +315,7 @@ where
 
     pub async fn start(&mut self) {
         debug!("start");
-        let background_job = self
-            .task
-            .init_channels(&self.channels_manager, self.shutdown.subscribe())
-            .await;
+        let _ = self.task.resolve_secrets(&self.db).await.log();
 
         if self.task.status == State::Created {
             debug!("saving task on receive: {:?}", self.task);
@@ -186,11 +332,23 @@ where
                 })
                 .log();
         }
+        if let Err(err) = self.start_input().await {
+            // task without input is stopped till its kind is updated.
+            error!(
+                "failed to start input of {} task, stopping it: {:?}",
+                self.task.info(),
+                err
+            );
+            let _ = self.change_status(State::Stopped).await.log();
+        }
 
-        let (sender, mut input_data_receiver) = mpsc::channel(10);
-        receive_input_data(self.task.clone(), sender);
+        let buffer = self.buffer.clone();
+        // inputs dropped before restart.
+        let dropped_before = self.task.dropped_inputs;
 
         while !self.shutdown.is_shutdown() {
+            // deferred inputs wait in the buffer till task's active window opens.
+            let deferring = self.outside_window() == Some(OutsideWindow::Defer);
             tokio::select! {
                 _ = self.shutdown.recv() => {
                     info!("handler is shutting down");
@@ -205,6 +363,7 @@ where
                             info!("receiver has been closed for {} task, returning", self.task.info());
                             break
                         }
+                        Some(Command::Backfill(inputs)) => self.backfill(inputs).await,
                         Some(command) => {
                             if let Err(err) = self.apply(command).await {
                                 error!("failed to apply command to task: {:?}", err)
@@ -212,38 +371,65 @@ where
                         }
                     }
                 }
-                id = input_data_receiver.recv() => match id {
-                        Some(input_data) => {
+                _ = window_opens(self.task.active_window.as_ref()), if deferring => {
+                    debug!("checking active window of {} task", self.task.info());
+                }
+                _ = end_date(self.task.ends_at), if self.task.ends_at.is_some() && self.task.status != State::Quit => {
+                    // task can reach its end date without any input.
+                    info!("Task {} reached its end date", self.task.info());
+                    let _ = self.change_status(State::Finished).await.log();
+                    break
+                }
+                input_data = buffer.pop(), if !deferring => {
+                            if self.outside_window() == Some(OutsideWindow::Drop) {
+                                info!("dropping input of {} task received outside of active window", self.task.info());
+                                buffer.ack().await;
+                                continue
+                            }
+                            self.update_dropped_inputs(dropped_before + buffer.dropped()).await;
                             info!("got data from run_signal: {:?}", input_data);
-                            match self.task.status{
+                            let inputs = match &self.task.batch {
+                                Some(batch) => buffer.fill_batch(input_data, batch).await,
+                                None => vec![input_data],
+                            };
+                            let finished = match self.task.status{
                                 State::Created => {
                                     if let Err(e) = self.change_status(State::Running).await{
                                         error!("failed to change status to Running: {:?}", e);
                                         break
                                     };
-                                    self.handle(&input_data).await;
-                                }
-                                State::Running => {
-                                    self.handle(&input_data).await;
+                                    self.run(inputs).await
                                 }
+                                State::Running => self.run(inputs).await,
                                 State::Stopped => {
                                     info!("Task {} stopped", self.task.info());
+                                    false
                                 }
                                 State::Quit => {
                                     if let Err(err) = self.db.delete_task(self.task.id).await {
                                         error!("failed to delete task: {:?}", err);
                                     }
                                     info!("Task {} is quitting", self.task.info());
-                                    break
+                                    true
+                                }
+                                State::Finished => {
+                                    info!("Task {} is finished", self.task.info());
+                                    true
                                 }
                             };
-                        },
-                        None => error!("got None from input_data_channel"),
+                            // handled and discarded inputs are removed from task's inbox.
+                            buffer.ack().await;
+                            if finished {
+                                break
+                            }
                 }
             }
         }
 
-        if let Some(join) = background_job {
+        if let Some(job) = self.receiver_job.take() {
+            job.abort();
+        }
+        if let Some(join) = self.background_job.take() {
             info!("waiting for task background jobs");
             join.await.expect("failed to wait for task background job")
         }
@@ -251,117 +437,924 @@ where
         info!("TaskHandler for {} task closed", self.task.id);
     }
 
-    /// Performs single handling of task.
-    async fn handle(&self, input_data: &InputData) {
+    /// Handles inputs unless task is exhausted and counts invocations, every input is one invocation.
+    /// Returns true if task reached its limits and was marked as Finished.
+    async fn run(&mut self, mut inputs: Vec<InputData>) -> bool {
+        if !self.task.is_exhausted(Utc::now()) {
+            if let Some(left) = self.task.invocations {
+                inputs.truncate(left.max(0) as usize);
+            }
+            if self.task.batch.is_some() {
+                self.handle_batch(&inputs).await;
+            } else {
+                for input_data in &inputs {
+                    self.handle(input_data, None).await;
+                }
+            }
+            if let Some(left) = self.task.invocations {
+                self.task.invocations = Some(left - inputs.len() as i32);
+                let _ = self
+                    .db
+                    .update_task_invocations(self.task.id, self.task.invocations)
+                    .await
+                    .log();
+            }
+        }
+
+        if self.task.is_exhausted(Utc::now()) {
+            info!("Task {} reached its limits", self.task.info());
+            let _ = self.change_status(State::Finished).await.log();
+            return true;
+        }
+        false
+    }
+
+    /// Handles historical inputs one by one, regardless of task's status, limits and active window.
+    /// Outputs are written with task's write mode, but they are not sent downstream, checked for alerts
+    /// or deduplicated. Progress is saved after every input, outcome of the whole backfill is saved as a single report.
+    async fn backfill(&mut self, inputs: Vec<InputData>) {
+        info!(
+            "backfilling {} inputs of {} task",
+            inputs.len(),
+            self.task.info()
+        );
+        let mut report = Report::new(self.task.id);
+        let mut progress = Backfill::new(self.task.id, inputs.len());
+        let _ = self.db.save_backfill(&progress).await.log();
+        let policy = self.task.retry_policy.clone().unwrap_or_default();
+
+        let mut status = BackfillStatus::Finished;
+        for input_data in inputs {
+            if self.backfill_cancelled().await {
+                status = BackfillStatus::Cancelled;
+                break;
+            }
+            let result = self.backfill_input(&input_data, &policy).await;
+            progress.record(result.is_err());
+            report.items.push(ItemOutcome {
+                input: input_data,
+                error: result.err().map(|err| err.to_string()),
+            });
+            let _ = self.db.save_backfill(&progress).await.log();
+        }
+        progress.finish(status);
+        let _ = self.db.save_backfill(&progress).await.log();
+        info!(
+            "backfill of {} task {:?}, {} of {} inputs handled, {} failed",
+            self.task.info(),
+            status,
+            progress.processed,
+            progress.total,
+            progress.failed
+        );
+
+        report.add_phase(String::from("BACKFILL"));
+        if status == BackfillStatus::Cancelled {
+            report.error = Some(format!(
+                "backfill cancelled after {} of {} inputs",
+                progress.processed, progress.total
+            ));
+        } else if progress.failed > 0 {
+            report.error = Some(format!(
+                "{} of {} inputs failed",
+                progress.failed, progress.total
+            ));
+        } else {
+            report.mark_successful();
+        }
+        report.add_phase(String::from("EXECUTION"));
+        report.save(self.db.clone()).await;
+    }
+
+    /// Applies commands received during backfill. Returns true if backfill has to be cancelled,
+    /// it's cancelled by CancelBackfill, Stop and Delete commands and on shutdown.
+    async fn backfill_cancelled(&mut self) -> bool {
+        while let Ok(cmd) = self.receiver.try_recv() {
+            info!(
+                "applying {:?} cmd for {} task during backfill",
+                cmd,
+                self.task.info()
+            );
+            match cmd {
+                Command::CancelBackfill => return true,
+                Command::Backfill(_) => warn!(
+                    "{} task is already being backfilled, another backfill is ignored",
+                    self.task.info()
+                ),
+                command => {
+                    let cancel = matches!(command, Command::Stop | Command::Delete);
+                    if let Err(err) = self.apply(command).await {
+                        error!("failed to apply command to task: {:?}", err)
+                    }
+                    if cancel {
+                        return true;
+                    }
+                }
+            }
+        }
+        self.shutdown.try_recv()
+    }
+
+    /// Returns A1 range of key cells of given number of lines written before, key is index of a cell in a line.
+    fn key_range(&self, key: u32, lines: u32) -> Result<String> {
+        let start = self.task.starting_range()?;
+        let key_start = match self.task.direction {
+            Direction::Vertical => start.start.offset(key, 0)?,
+            Direction::Horizontal => start.start.offset(0, key)?,
+        };
+        let range = A1Range::for_write(key_start, start.sheet, self.task.direction, 0, lines, 1)?;
+        Ok(range.to_string())
+    }
+
+    /// Returns policy of handling inputs if task's active window is closed, None if inputs can be handled.
+    fn outside_window(&self) -> Option<OutsideWindow> {
+        self.task
+            .active_window
+            .as_ref()
+            .filter(|window| !window.is_active(Utc::now()))
+            .map(|window| window.outside)
+    }
+
+    /// Saves number of inputs dropped on buffer overflow if it changed.
+    async fn update_dropped_inputs(&mut self, dropped_inputs: i64) {
+        if dropped_inputs != self.task.dropped_inputs {
+            self.task.dropped_inputs = dropped_inputs;
+            let _ = self
+                .db
+                .update_task_dropped_inputs(self.task.id, dropped_inputs)
+                .await
+                .log();
+        }
+    }
+
+    /// Performs single handling of task, returns true if input was handled successfully.
+    /// Failed input is saved as dead letter, replayed dead letter is replaced.
+    async fn handle(&self, input_data: &InputData, dead_letter_id: Option<Uuid>) -> bool {
         Report::run(self.task.id, self.db.clone(), async move |mut report| {
             info!("Handling task {}", self.task.info());
 
-            let evaluated = report
+            let validated = report
+                .section(String::from("VALIDATE"), async || {
+                    self.validate(input_data).await
+                })
+                .await;
+            if let Err(err) = validated {
+                report.error = Some(err.to_string());
+                self.task.run_callbacks(Err(err));
+                return report;
+            }
+
+            let policy = self.task.retry_policy.clone().unwrap_or_default();
+            let (evaluated, attempts) = report
                 .section(String::from("EVALUATE"), async || {
-                    // tokio::time::sleep(Duration::from_millis(50)).await;
-                    evaluate_data(input_data.clone(), self.task.process.clone())
+                    policy
+                        .run(RetryPhase::Evaluate, move || self.evaluate(input_data))
+                        .await
                 })
                 .await;
 
             match evaluated {
-                Ok(data) => {
-                    info!("evaluated from engine: {:?}", &data);
-
-                    let data = create_write_vec(self.task.direction, data);
-
-                    let last_place = self.db.get(&self.task.id).await.unwrap_or(0);
-                    let data_len = data.len() as u32;
-                    debug!("last_place: {}, data_len: {}", last_place, data_len);
-
-                    let result = report
-                        .section(String::from("DATA_WRITE"), async || {
-                            self.api
-                                .write(
-                                    data,
-                                    &self.task.spreadsheet_id,
-                                    &create_range(
-                                        last_place, // TODO: calculations are not working properly.
-                                        &self.task.starting_position,
-                                        &self.task.sheet,
-                                        self.task.direction,
-                                        data_len,
-                                    ),
-                                )
-                                .await
-                        })
+                Ok((data, secrets)) => {
+                    let secrets: Vec<String> = secrets.into_values().collect();
+                    info!(
+                        "evaluated from engine: {}",
+                        redact(&format!("{:?}", data), &secrets)
+                    );
+                    self.channels_manager
+                        .send_downstream(self.task.id, InputData::from(data.clone()))
                         .await;
+                    self.check_alerts(&data, &secrets).await;
+
+                    let fingerprint = self
+                        .task
+                        .dedup
+                        .as_ref()
+                        .map(|_| Fingerprint::new(self.task.id, &data));
+                    if let Some(fingerprint) = &fingerprint {
+                        if self.unchanged(fingerprint).await {
+                            info!(
+                                "output of {} task did not change, skipping write",
+                                self.task.info()
+                            );
+                            report.add_phase(String::from("SKIPPED"));
+                            self.task.run_callbacks(Ok(()));
+                            report.mark_successful();
+                            return report;
+                        }
+                    }
 
-                    debug!("saving to db");
-                    if let Err(err) = self.db.save(self.task.id, data_len + last_place).await {
-                        debug!("save failed");
-                        self.task.run_callbacks(Err(err));
-                    } else {
-                        debug!("save successful");
-                        self.task.run_callbacks(result);
+                    let mut columns = self.columns().await;
+                    let known = columns.len();
+                    let data = to_grid_with_columns(data, &mut columns);
+                    let data = add_timestamp(&self.task, data, Utc::now());
+                    let (result, attempts) = report
+                        .section(String::from("DATA_WRITE"), || self.write(data, &policy))
+                        .await;
+                    let result = result.map_err(|err| redact_error(err, &secrets));
+                    match &result {
+                        Ok(()) => {
+                            if let Some(fingerprint) = &fingerprint {
+                                let _ = self.db.save_fingerprint(fingerprint).await.log();
+                            }
+                            self.save_columns(&columns, known).await;
+                        }
+                        Err(err) => {
+                            report.error = Some(err.to_string());
+                            self.dead_letter(
+                                input_data,
+                                dead_letter_id,
+                                RetryPhase::Write,
+                                err,
+                                attempts,
+                            )
+                            .await;
+                        }
+                    }
+                    let failed = result.is_err();
+                    self.task.run_callbacks(result);
+                    if failed {
+                        return report;
                     }
                 }
                 Err(err) => {
+                    // evaluation errors are redacted by evaluate, values of secrets are not returned with them.
                     error!("{:?}", err);
+                    report.error = Some(err.to_string());
+                    self.dead_letter(
+                        input_data,
+                        dead_letter_id,
+                        RetryPhase::Evaluate,
+                        &err,
+                        attempts,
+                    )
+                    .await;
                     self.task.run_callbacks(Err(Error::new_internal(
                         String::from("get"),
                         String::from("failed to evaluate"),
                         err.to_string(),
                     )));
+                    return report;
                 }
             }
-            if self.task.invocations.is_some() {
-                self.task.invocations.map(|i| i - 1);
+            report.mark_successful(); // report is failed by default.
+            report
+        })
+        .await
+    }
+}
+
+impl<A> TaskHandler<A>
+where
+    A: API,
+{
+    /// Handles batch of inputs. Process is run for every input and resulting rows are written
+    /// to a spreadsheet at once, report of the batch contains outcome of every input.
+    async fn handle_batch(&self, inputs: &[InputData]) {
+        Report::run(self.task.id, self.db.clone(), async move |mut report| {
+            info!(
+                "Handling batch of {} inputs for task {}",
+                inputs.len(),
+                self.task.info()
+            );
+            let policy = self.task.retry_policy.clone().unwrap_or_default();
+
+            // rows are paired with index of input they were evaluated from.
+            let (mut items, rows, secrets) = report
+                .section(String::from("EVALUATE"), async || {
+                    let mut items = vec![];
+                    let mut rows = vec![];
+                    let mut secrets: Vec<String> = vec![];
+                    for (i, input_data) in inputs.iter().enumerate() {
+                        let mut item = ItemOutcome {
+                            input: input_data.clone(),
+                            error: None,
+                        };
+                        if let Err(err) = self.validate(input_data).await {
+                            item.error = Some(err.to_string());
+                        } else {
+                            match policy
+                                .run(RetryPhase::Evaluate, || self.evaluate(input_data))
+                                .await
+                            {
+                                (Ok((data, used)), _) => {
+                                    secrets.extend(used.into_values());
+                                    self.channels_manager
+                                        .send_downstream(
+                                            self.task.id,
+                                            InputData::from(data.clone()),
+                                        )
+                                        .await;
+                                    self.check_alerts(&data, &secrets).await;
+                                    rows.push((i, data));
+                                }
+                                (Err(err), attempts) => {
+                                    self.dead_letter(
+                                        input_data,
+                                        None,
+                                        RetryPhase::Evaluate,
+                                        &err,
+                                        attempts,
+                                    )
+                                    .await;
+                                    item.error = Some(err.to_string());
+                                }
+                            }
+                        }
+                        items.push(item);
+                    }
+                    (items, rows, secrets)
+                })
+                .await;
+
+            if !rows.is_empty() {
+                let mut columns = self.columns().await;
+                let known = columns.len();
+                let (evaluated, mut data): (Vec<usize>, Grid) = rows
+                    .into_iter()
+                    .flat_map(|(i, data)| {
+                        to_grid_with_columns(data, &mut columns)
+                            .into_iter()
+                            .map(move |line| (i, line))
+                    })
+                    .unzip();
+                // grids of inputs can have different widths.
+                pad(&mut data);
+                let data = add_timestamp(&self.task, data, Utc::now());
+                // appended batch is written to a single contiguous range.
+                let (result, attempts) = report
+                    .section(String::from("DATA_WRITE"), || self.write(data, &policy))
+                    .await;
+                match result.map_err(|err| redact_error(err, &secrets)) {
+                    Ok(()) => self.save_columns(&columns, known).await,
+                    Err(err) => {
+                        for i in evaluated {
+                            if items[i].error.is_none() {
+                                self.dead_letter(
+                                    &items[i].input,
+                                    None,
+                                    RetryPhase::Write,
+                                    &err,
+                                    attempts,
+                                )
+                                .await;
+                                items[i].error = Some(err.to_string());
+                            }
+                        }
+                    }
+                }
             }
 
-            report.mark_successful(); // report is failed by default.
+            let failed = items.iter().filter(|item| item.error.is_some()).count();
+            report.items = items;
+            if failed > 0 {
+                let msg = format!("{} of {} inputs failed", failed, inputs.len());
+                report.error = Some(msg.clone());
+                self.task.run_callbacks(Err(Error::new_internal(
+                    String::from("handle_batch"),
+                    String::from("failed to handle batch"),
+                    msg,
+                )));
+                return report;
+            }
+            self.task.run_callbacks(Ok(()));
+            report.mark_successful();
             report
         })
         .await;
     }
+
+    /// Validates, evaluates and writes single input of a backfill, failed phases are retried with task's policy.
+    async fn backfill_input(&self, input_data: &InputData, policy: &RetryPolicy) -> Result<()> {
+        self.validate(input_data).await?;
+        let (data, secrets) = policy
+            .run(RetryPhase::Evaluate, || self.evaluate(input_data))
+            .await
+            .0?;
+        let secrets: Vec<String> = secrets.into_values().collect();
+        let mut columns = self.columns().await;
+        let known = columns.len();
+        let data = to_grid_with_columns(data, &mut columns);
+        let data = add_timestamp(&self.task, data, Utc::now());
+        self.write(data, policy)
+            .await
+            .0
+            .map_err(|err| redact_error(err, &secrets))?;
+        self.save_columns(&columns, known).await;
+        Ok(())
+    }
+
+    /// Returns keys of objects written by the task so far, in order of their columns.
+    async fn columns(&self) -> Vec<String> {
+        self.db
+            .read_columns(self.task.id)
+            .await
+            .log()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Saves columns of written objects when the write added new ones, so next writes keep their order.
+    async fn save_columns(&self, columns: &[String], known: usize) {
+        if columns.len() > known {
+            let _ = self.db.save_columns(self.task.id, columns).await.log();
+        }
+    }
+
+    /// Checks task's alert rules against output, changed alerts are saved and sent to task's notifiers.
+    /// Values of used secrets are redacted from saved and sent alerts.
+    async fn check_alerts(&self, out: &Variable, secrets: &[String]) {
+        let config = match &self.task.alerts {
+            Some(config) => config,
+            None => return,
+        };
+        let alerts = match self.db.read_alerts(self.task.id).await {
+            Ok(alerts) => alerts,
+            Err(err) => {
+                error!(
+                    "failed to read alerts of {} task: {}",
+                    self.task.info(),
+                    err
+                );
+                return;
+            }
+        };
+        for rule in &config.rules {
+            let last = alerts.iter().find(|alert| alert.rule == rule.name);
+            match rule.check(self.task.id, out, last, &self.registry, Utc::now()) {
+                Ok(Some((mut alert, notify))) => {
+                    alert.value = redact_value(alert.value, secrets);
+                    let _ = self.db.save_alert(&alert).await.log();
+                    if notify {
+                        for notifier in &config.notifiers {
+                            let _ = notifier.notify(&alert).await.log();
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => error!(
+                    "failed to check {} alert rule of {} task: {}",
+                    rule.name,
+                    self.task.info(),
+                    redact_error(err, secrets)
+                ),
+            }
+        }
+    }
+
+    /// Checks if output matches output of the last write, numbers can differ by task's tolerance.
+    async fn unchanged(&self, fingerprint: &Fingerprint) -> bool {
+        let tolerance = self
+            .task
+            .dedup
+            .as_ref()
+            .map_or(0.0, |dedup| dedup.tolerance);
+        match self.db.read_fingerprint(self.task.id).await {
+            Ok(last) => last.map_or(false, |last| last.matches(fingerprint, tolerance)),
+            Err(err) => {
+                error!(
+                    "failed to read fingerprint of {} task: {}",
+                    self.task.info(),
+                    err
+                );
+                false
+            }
+        }
+    }
+
+    /// Writes lines of a grid according to task's write mode, location of appended lines is saved after write.
+    /// Returns result of the write together with number of attempts made.
+    async fn write(&self, data: Grid, policy: &RetryPolicy) -> (Result<()>, u32) {
+        let last_place = self.db.get(&self.task.id).await.unwrap_or(0);
+        debug!("last_place: {}, data_len: {}", last_place, data.len());
+        let (data, offset) = match plan_write(&self.task, data, last_place) {
+            Ok(plan) => plan,
+            Err(err) => return (Err(err), 0),
+        };
+        match self.task.write_mode {
+            WriteMode::Append => self.append(data, offset, policy).await,
+            WriteMode::Overwrite => self.write_lines(data, offset, policy).await,
+            WriteMode::Upsert { key } => self.upsert(data, key, last_place, policy).await,
+        }
+    }
+
+    /// Writes lines after last_place previously written ones and saves new location if write succeeded.
+    async fn append(&self, data: Grid, last_place: u32, policy: &RetryPolicy) -> (Result<()>, u32) {
+        let data_len = data.len() as u32;
+        let (result, attempts) = self.write_lines(data, last_place, policy).await;
+        if result.is_ok() {
+            debug!("saving to db");
+            let _ = self
+                .db
+                .save(self.task.id, last_place + data_len)
+                .await
+                .log();
+        }
+        (result, attempts)
+    }
+
+    /// Replaces written lines that have the same key as lines of the grid, lines with new keys are appended.
+    async fn upsert(
+        &self,
+        data: Grid,
+        key: usize,
+        last_place: u32,
+        policy: &RetryPolicy,
+    ) -> (Result<()>, u32) {
+        // lines already have timestamp, written before values it shifts key cell.
+        let key = match self.task.timestamp(Utc::now()) {
+            Some(_) if self.task.timestamp_position == TimestampPosition::Before => key + 1,
+            _ => key,
+        };
+        let keys: Vec<String> = data
+            .iter()
+            .map(|line| line.get(key).map(Cell::to_string).unwrap_or_default())
+            .collect();
+
+        let mut attempts = 0;
+        let mut written = vec![];
+        if last_place > 0 {
+            let range = match self.key_range(key as u32, last_place) {
+                Ok(range) => range,
+                Err(err) => return (Err(err), 0),
+            };
+            let (rows, read_attempts) = policy
+                .run(RetryPhase::Write, || {
+                    self.api.read(&self.task.spreadsheet_id, &range)
+                })
+                .await;
+            attempts = read_attempts;
+            written = match (rows, self.task.direction) {
+                (Ok(rows), Direction::Vertical) => rows
+                    .into_iter()
+                    .map(|row| row.into_iter().next().unwrap_or_default())
+                    .collect(),
+                (Ok(rows), Direction::Horizontal) => rows.into_iter().next().unwrap_or_default(),
+                (Err(err), _) => return (Err(err), attempts),
+            };
+        }
+
+        // later line with the same key wins.
+        let lines: BTreeMap<u32, Vec<Cell>> = plan_upsert(&written, last_place, &keys)
+            .into_iter()
+            .zip(data)
+            .collect();
+        let mut appended = vec![];
+        for (index, line) in lines {
+            if index >= last_place {
+                appended.push(line);
+                continue;
+            }
+            let (result, write_attempts) = self.write_lines(vec![line], index, policy).await;
+            attempts = write_attempts;
+            if result.is_err() {
+                return (result, attempts);
+            }
+        }
+        if appended.is_empty() {
+            return (Ok(()), attempts);
+        }
+        self.append(appended, last_place, policy).await
+    }
+
+    /// Writes lines at offset lines from starting position, failed write is retried according to policy.
+    async fn write_lines(
+        &self,
+        data: Grid,
+        offset: u32,
+        policy: &RetryPolicy,
+    ) -> (Result<()>, u32) {
+        let range = match self
+            .task
+            .write_range(offset, data.len() as u32, data[0].len() as u32)
+        {
+            Ok(range) => range,
+            Err(err) => return (Err(err), 0),
+        };
+        let data = match self.task.direction {
+            Direction::Vertical => data,
+            Direction::Horizontal => transpose(data),
+        };
+        policy
+            .run(RetryPhase::Write, || {
+                self.api
+                    .write(data.clone(), &self.task.spreadsheet_id, &range)
+            })
+            .await
+    }
+
+    /// Validates input against process's input schema, rejected input is quarantined if process says so.
+    async fn validate(&self, input_data: &InputData) -> Result<()> {
+        let validated = self
+            .task
+            .process
+            .validate_input(&Variable::from(input_data.clone()));
+        if let Err(err) = &validated {
+            warn!("input rejected for task {}: {}", self.task.id, err);
+            if self.task.process.quarantine_rejected {
+                let quarantined =
+                    QuarantinedInput::new(self.task.id, input_data.clone(), err.to_string());
+                let _ = self.db.save_quarantined_input(&quarantined).await.log();
+            }
+        }
+        validated
+    }
+
+    /// Resolves secrets and params of task's process and evaluates it, imports are resolved on task's creation.
+    /// Returns evaluated data with values of used secrets, so they can be redacted.
+    async fn evaluate(
+        &self,
+        input_data: &InputData,
+    ) -> Result<(Variable, HashMap<String, String>)> {
+        let process = self.task.process.clone();
+        let secrets = self
+            .db
+            .resolve_secrets(&process.referenced_secrets())
+            .await?;
+        let params = process.resolve_params(&self.task.params)?;
+        let (data, _) = evaluate_data(
+            input_data.clone(),
+            process,
+            &self.registry,
+            secrets.clone(),
+            params,
+            false,
+        )
+        .map_err(|err| redact_error(err, &secrets.values().cloned().collect::<Vec<_>>()))?;
+        Ok((data, secrets))
+    }
+
+    /// Saves input which handling failed after all attempts, so it can be replayed later.
+    /// Dead letter with given id, e.g. replayed one, is replaced.
+    async fn dead_letter(
+        &self,
+        input_data: &InputData,
+        id: Option<Uuid>,
+        phase: RetryPhase,
+        err: &Error,
+        attempts: u32,
+    ) {
+        warn!(
+            "{:?} phase of {} task failed after {} attempts, saving dead letter",
+            phase,
+            self.task.info(),
+            attempts
+        );
+        let mut dead_letter = DeadLetter::new(
+            self.task.id,
+            input_data.clone(),
+            phase,
+            err.to_string(),
+            attempts,
+        );
+        if let Some(id) = id {
+            dead_letter.id = id;
+        }
+        let _ = self.db.save_dead_letter(&dead_letter).await.log();
+    }
+}
+
+/// Creates task's input buffer, durable buffer persists inputs in task's inbox.
+fn new_buffer(task: &TrackingTask, db: &Db) -> InputBuffer {
+    let config = task.buffer.clone().unwrap_or_default();
+    let durable = config.durable;
+    let buffer = InputBuffer::new(config);
+    if durable {
+        buffer.with_inbox(db.clone(), task.id)
+    } else {
+        buffer
+    }
+}
+
+/// Gets data with task's data function, failed fetches are retried according to task's retry policy.
+/// Data is not fetched outside of task's active window.
+async fn fetch_data(task: &TrackingTask) -> Option<InputData> {
+    if let Some(window) = &task.active_window {
+        if !window.is_active(Utc::now()) {
+            debug!(
+                "{} task is outside of active window, skipping fetch",
+                task.info()
+            );
+            return None;
+        }
+    }
+    let policy = task.retry_policy.clone().unwrap_or_default();
+    match policy.run(RetryPhase::Fetch, || task.data()).await {
+        (Ok(data), _) => Some(data),
+        (Err(err), attempts) => {
+            error!(
+                "failed to fetch data for {} task after {} attempts: {}",
+                task.info(),
+                attempts,
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Waits till active window opens, window is checked at least once an hour.
+async fn window_opens(window: Option<&ActiveWindow>) {
+    let hour = std::time::Duration::from_secs(60 * 60);
+    let wait = window
+        .and_then(|window| window.until_active(Utc::now()))
+        .map_or(hour, |wait| wait.min(hour));
+    tokio::time::sleep(wait).await;
+}
+
+/// Waits till task's end date, forever if task has none.
+async fn end_date(ends_at: Option<DateTime<Utc>>) {
+    match ends_at {
+        Some(ends_at) => {
+            let wait = (ends_at - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await
+        }
+        None => futures::future::pending().await,
+    }
 }
 
-/// Uses Engine utility to run task's process.
-fn evaluate_data(input_data: InputData, task_process: Process) -> Result<Variable> {
-    let mut engine = Engine::new(Variable::from(input_data), task_process)?;
+/// Uses Engine utility to run task's process. Evaluated steps are returned if tracing is enabled.
+pub(crate) fn evaluate_data(
+    input_data: InputData,
+    task_process: Process,
+    registry: &Registry,
+    secrets: HashMap<String, String>,
+    params: HashMap<String, Variable>,
+    tracing: bool,
+) -> Result<(Variable, Vec<TraceEntry>)> {
+    let mut builder = Engine::builder()
+        .with_registry(registry.clone())
+        .with_secrets(secrets)
+        .with_params(params);
+    if tracing {
+        builder = builder.with_tracing();
+    }
+    let mut engine = builder.build(Variable::from(input_data), task_process)?;
 
     engine.fire()?;
 
     let out = engine.get("OUT").context("OUT variable not found")?;
-    Ok(out.clone())
+    Ok((out.clone(), engine.trace().to_vec()))
+}
+
+/// Returns lines a write puts according to task's write mode, with offset from starting position they're written at.
+/// Overwrite pads lines to starting range if it's a range, so values written by previous runs are cleared,
+/// lines that don't fit into it are an error. Upsert lines are returned as appended, their keys are matched on write.
+pub(crate) fn plan_write(
+    task: &TrackingTask,
+    mut data: Grid,
+    last_place: u32,
+) -> Result<(Grid, u32)> {
+    if task.write_mode != WriteMode::Overwrite {
+        return Ok((data, last_place));
+    }
+    let start = task.starting_range()?;
+    if let Some(end) = start.end {
+        let columns = end.column.saturating_sub(start.start.column) + 1;
+        let rows = end.row.saturating_sub(start.start.row) + 1;
+        let (lines, width) = match task.direction {
+            Direction::Vertical => (rows, columns),
+            Direction::Horizontal => (columns, rows),
+        };
+        let data_width = data.first().map(Vec::len).unwrap_or(0);
+        if data.len() as u32 > lines || data_width as u32 > width {
+            return Err(Error::new_validation(
+                "TaskHandler",
+                format!(
+                    "{} lines of {} cells don't fit into {}",
+                    data.len(),
+                    data_width,
+                    task.starting_position
+                )
+                .as_str(),
+                "starting_position",
+            ));
+        }
+        data.resize(lines as usize, vec![]);
+        for line in data.iter_mut() {
+            line.resize(width as usize, Cell::Empty);
+        }
+    }
+    Ok((data, 0))
+}
+
+/// Job that pushes task's inputs into the buffer, see receive_input_data.
+struct ReceiverJob {
+    handle: JoinHandle<()>,
+    stop: Arc<Notify>,
+}
+
+impl ReceiverJob {
+    /// Signals job to stop, input that is being fetched or pushed is still pushed into the buffer.
+    fn stop(self) {
+        self.stop.notify_one();
+    }
+
+    fn abort(self) {
+        self.handle.abort();
+    }
 }
 
-fn receive_input_data(task: TrackingTask, sender: mpsc::Sender<InputData>) {
+/// Spawns tokio::task that pushes task's inputs into the buffer.
+/// Returned job should be stopped when handler stops.
+fn receive_input_data(task: TrackingTask, buffer: InputBuffer) -> ReceiverJob {
     assert!(task.kind.is_some());
-    tokio::task::spawn(async move {
+    let stop = Arc::new(Notify::new());
+    let stopped = stop.clone();
+    // stop is only checked between inputs, so held input is never dropped.
+    let handle = tokio::task::spawn(async move {
         match task.kind.as_ref().unwrap() {
-            TaskKind::Triggered { ch } => loop {
-                let id = ch.lock().await.recv().await.unwrap();
-                sender.send(id).await.unwrap();
+            TaskKind::Triggered { ch, ack } => loop {
+                tokio::select! {
+                    biased;
+                    _ = stopped.notified() => break,
+                    input_data = async { ch.lock().await.recv().await } => match input_data {
+                        Some(id) => {
+                            buffer.push(id).await;
+                            acknowledge(ack.as_ref());
+                        }
+                        None => {
+                            info!("input source of task {} closed", task.id);
+                            break;
+                        }
+                    }
+                }
             },
             TaskKind::Ticker { interval } => {
                 let mut timer = tokio::time::interval(*interval);
                 debug!("ticket starting with: {:?}", timer);
                 loop {
                     tokio::select! {
+                        biased;
+                        _ = stopped.notified() => break,
                         _ = timer.tick() => {
-                            sender.send(task.data().await.unwrap()).await.unwrap();
+                            if let Some(data) = fetch_data(&task).await {
+                                buffer.push(data).await;
+                            }
                         }
                     }
                 }
             }
             TaskKind::Clicked { ch } => loop {
-                ch.lock().await.recv().await.unwrap(); // wait for an click/call event.
-                sender.send(task.data().await.unwrap()).await.unwrap(); // return configured data.
+                // wait for an click/call event and return configured data.
+                tokio::select! {
+                    biased;
+                    _ = stopped.notified() => break,
+                    click = async { ch.lock().await.recv().await } => {
+                        if click.is_none() {
+                            info!("click channel of task {} closed", task.id);
+                            break;
+                        }
+                        if let Some(data) = fetch_data(&task).await {
+                            buffer.push(data).await;
+                        }
+                    }
+                }
             },
+            TaskKind::Cron { schedule } => {
+                while let Some(wait) = schedule.until_next(Utc::now()) {
+                    debug!("cron task {} fires in {:?}", task.id, wait);
+                    tokio::select! {
+                        biased;
+                        _ = stopped.notified() => return,
+                        _ = tokio::time::sleep(wait) => {
+                            if let Some(data) = fetch_data(&task).await {
+                                buffer.push(data).await;
+                            }
+                        }
+                    }
+                }
+                info!("cron schedule of task {} won't fire anymore", task.id);
+            }
         }
     });
+    ReceiverJob { handle, stop }
+}
+
+/// Lets input's source know that input is in the buffer, and in the inbox if buffer is durable.
+fn acknowledge(ack: Option<&Sender<()>>) {
+    if let Some(ack) = ack {
+        // source waits for every ack, so the channel is only full or closed if source is gone.
+        let _ = ack.try_send(());
+    }
 }
 
-// create_write_vec creates a vector of WriteData from a TrackedData.
-fn create_write_vec(_direction: Direction, data: Variable) -> Vec<Vec<String>> {
-    let mut write_vec: Vec<Vec<String>> = Vec::new();
-    write_vec.push(vec![format!("{:?}", data)]);
-    write_vec
+/// Adds task's timestamp to every row or column of data, before or after its values.
+pub(crate) fn add_timestamp(task: &TrackingTask, data: Grid, time: DateTime<Utc>) -> Grid {
+    let timestamp = match task.timestamp(time) {
+        Some(timestamp) => timestamp,
+        None => return data,
+    };
+    data.into_iter()
+        .map(|mut line| {
+            match task.timestamp_position {
+                TimestampPosition::Before => line.insert(0, Cell::Timestamp(timestamp.clone())),
+                TimestampPosition::After => line.push(Cell::Timestamp(timestamp.clone())),
+                TimestampPosition::None => {}
+            }
+            line
+        })
+        .collect()
 }
 
 fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
@@ -378,69 +1371,35 @@ fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
         .collect()
 }
 
-// create_range creates range from a starting position and a direction.
-fn create_range(
-    offset: u32, // last previously written place.
-    starting_position: &str,
-    sheet: &str,
-    direction: Direction,
-    data_len: u32,
-) -> String {
-    let character = &starting_position[..1];
-    assert!(
-        character.len() == 1,
-        "Starting position must be a single character."
-    );
-    let number = starting_position[1..].parse::<u32>().unwrap();
-
-    let mut range: String = match direction {
-        Direction::Vertical => {
-            format!(
-                "{}{}:{}{}",
-                character,
-                offset + number,
-                character,
-                offset + number + data_len
-            )
-        }
-        Direction::Horizontal => {
-            format!(
-                "{}{}:{}{}",
-                add_str(character, offset),
-                number,
-                add_str(character, offset + data_len),
-                number,
-            )
-        }
-    };
-    if !sheet.is_empty() {
-        range = format!("{}!{}", sheet, range);
-    }
-    debug!("range: {:?}", range);
-    range
-}
-
-// add_str increase ASCII code of a character by a number.
-fn add_str(s: &str, increment: u32) -> String {
-    s.chars()
-        .map(|c| std::char::from_u32(c as u32 + increment).unwrap_or(c))
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
-    use super::TaskHandler;
+    use super::{Report, TaskHandler};
+    use crate::core::alert::{AlertConfig, AlertRule, AlertStatus, Severity};
+    use crate::core::backfill::BackfillStatus;
+    use crate::core::buffer::{BatchConfig, BufferConfig, InputBuffer, OverflowPolicy};
     use crate::core::channels::ChannelsManager;
+    use crate::core::dead_letter::DeadLetter;
+    use crate::core::dedup::DedupConfig;
+    use crate::core::grid::Cell;
     use crate::core::handler::receive_input_data;
+    use crate::core::inbox::InboxEntry;
     use crate::core::manager::Command;
+    use crate::core::retry::RetryPhase;
+    use crate::core::schedule::{ActiveWindow, OutsideWindow};
+    use crate::core::secret::MasterKey;
     use crate::core::task::{BoxFnThatReturnsAFuture, InputData, TrackingTask};
-    use crate::core::types::{Direction, Hook, State, TaskKind};
+    use crate::core::types::{Direction, Hook, State, TaskKind, TimestampPosition};
+    use crate::core::write_mode::WriteMode;
     use crate::error::types::Result;
+    use crate::lang::input_schema::{InputSchema, SchemaType};
+    use crate::lang::process::{Definition, Process};
     use crate::persistance::in_memory::InMemoryPersistance;
     use crate::persistance::interface::Db;
-    use crate::server::task::TaskKindRequest;
+    use crate::server::task::{TaskKindRequest, TaskUpdateRequest};
     use crate::shutdown::Shutdown;
     use crate::wrap::TestAPI;
+    use chrono::Utc;
+    use serde_json::json;
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::select;
@@ -471,142 +1430,1161 @@ mod tests {
             TaskKindRequest::Ticker { interval_secs: 1 },
         );
         tt.init_channels(&ChannelsManager::default(), empty_shutdown())
-            .await;
+            .await
+            .unwrap();
 
-        let (sender, mut input_data_receiver) = mpsc::channel(1);
-        receive_input_data(tt, sender);
-        let id = input_data_receiver.recv().await.unwrap();
+        let buffer = InputBuffer::new(BufferConfig::default());
+        receive_input_data(tt, buffer.clone());
+        let id = buffer.pop().await;
         assert_eq!(id, InputData::String(String::from("test")))
     }
 
     #[tokio::test]
-    async fn test_run_signal_triggered() {
-        let (sender, receiver) = mpsc::channel(1);
-
-        let tt = TrackingTask::new(
+    async fn test_run_signal_cron() {
+        let mut tt = TrackingTask::new(
             "spreadsheet_id".to_string(),
             "".to_string(),
             "A1:B1".to_string(),
             Direction::Vertical,
-            None,
+            data_fn(),
+            TaskKindRequest::Cron {
+                expression: String::from("* * * * * *"),
+                timezone: String::from("Europe/Warsaw"),
+            },
+        );
+        tt.init_channels(&ChannelsManager::default(), empty_shutdown())
+            .await
+            .unwrap();
+
+        let buffer = InputBuffer::new(BufferConfig::default());
+        receive_input_data(tt, buffer.clone());
+        let id = buffer.pop().await;
+        assert_eq!(id, InputData::String(String::from("test")))
+    }
+
+    #[tokio::test]
+    async fn test_run_signal_triggered() {
+        let (sender, receiver) = mpsc::channel(1);
+        let (ack, mut acks) = mpsc::channel(1);
+
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1:B1".to_string(),
+            Direction::Vertical,
+            None,
             TaskKindRequest::Ticker { interval_secs: 1 },
         )
         .with_kind(TaskKind::Triggered {
             ch: Arc::new(Mutex::new(receiver)),
+            ack: Some(ack),
+        });
+        sender
+            .send(InputData::Vector(vec![InputData::String(String::from(
+                "triggered",
+            ))]))
+            .await
+            .unwrap();
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let buffer = InputBuffer::new(BufferConfig::default()).with_inbox(db.clone(), tt.id);
+        receive_input_data(tt.clone(), buffer.clone());
+        // input is acknowledged once it's saved in the inbox.
+        acks.recv().await.unwrap();
+        assert_eq!(db.read_inbox(tt.id).await.unwrap().len(), 1);
+        let id = buffer.pop().await;
+        assert_eq!(
+            id,
+            InputData::Vector(vec![InputData::String(String::from("triggered"))])
+        )
+    }
+
+    #[tokio::test]
+    async fn test_stop_receiving_input() {
+        let (sender, receiver) = mpsc::channel(1);
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_kind(TaskKind::Triggered {
+            ch: Arc::new(Mutex::new(receiver)),
+            ack: None,
+        });
+        let buffer = InputBuffer::new(BufferConfig {
+            capacity: 1,
+            overflow: OverflowPolicy::Block,
+            durable: false,
+        });
+        let job = receive_input_data(tt, buffer.clone());
+        for input in ["1", "2"] {
+            sender
+                .send(InputData::String(String::from(input)))
+                .await
+                .unwrap();
+        }
+        // second input waits for free space in the buffer while job is stopped.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        job.stop();
+
+        assert_eq!(buffer.pop().await, InputData::String(String::from("1")));
+        let held = tokio::time::timeout(Duration::from_secs(1), buffer.pop()).await;
+        assert_eq!(held.unwrap(), InputData::String(String::from("2")));
+    }
+
+    #[tokio::test]
+    async fn test_run_signal_clicked() {
+        let (sender, receiver) = mpsc::channel(1);
+
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1:B1".to_string(),
+            Direction::Vertical,
+            data_fn(),
+            TaskKindRequest::Ticker { interval_secs: 1 },
+        )
+        .with_kind(TaskKind::Clicked {
+            ch: Arc::new(Mutex::new(receiver)),
+        });
+        sender.send(()).await.unwrap();
+
+        let buffer = InputBuffer::new(BufferConfig::default());
+        receive_input_data(tt, buffer.clone());
+        let id = buffer.pop().await;
+        assert_eq!(id, InputData::String(String::from("test")));
+    }
+
+    #[tokio::test]
+    async fn test_handle_rejected_input() {
+        let mut tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Ticker { interval_secs: 1 },
+        );
+        tt.process = Process {
+            name: String::from("process"),
+            definitions: vec![Definition::new(vec!["DEFINE(OUT, GET(IN))"])],
+            input_schema: Some(InputSchema {
+                schema_type: Some(SchemaType::Object),
+                ..Default::default()
+            }),
+            quarantine_rejected: true,
+            ..Default::default()
+        };
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, _) = TestAPI::new();
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        let input = InputData::String(String::from("not an object"));
+        handler.handle(&input, None).await;
+
+        let reports = db.read_reports(tt.id).await.unwrap().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].failed);
+        assert!(reports[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("expected Object, got String"));
+
+        let quarantined = db.read_quarantined_inputs(tt.id).await.unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].input, input);
+    }
+
+    #[tokio::test]
+    async fn test_handle_upsert() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_process(Process::new(
+            "prices",
+            vec![Definition::new(vec!["DEFINE(OUT, VEC(GET(IN), INT(1)))"])],
+            None,
+        ))
+        .with_write_mode(WriteMode::Upsert { key: 0 });
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        db.save(tt.id, 2).await.unwrap();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, mut ch) = TestAPI::new();
+        let api = api.with_rows(vec![vec![String::from("BTC")], vec![String::from("ETH")]]);
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        // known key updates written line.
+        handler
+            .handle(&InputData::String(String::from("ETH")), None)
+            .await;
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![vec![Cell::String(String::from("ETH")), Cell::Int(1)]]
+        );
+        assert_eq!(db.get(&tt.id).await.unwrap(), 2);
+
+        // new key is appended.
+        handler
+            .handle(&InputData::String(String::from("SOL")), None)
+            .await;
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![vec![Cell::String(String::from("SOL")), Cell::Int(1)]]
+        );
+        assert_eq!(db.get(&tt.id).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_handle_dedup() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_process(Process::new(
+            "price",
+            vec![Definition::new(vec!["DEFINE(OUT, FLOAT(GET(IN)))"])],
+            None,
+        ))
+        .with_dedup(DedupConfig { tolerance: 0.5 });
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, mut ch) = TestAPI::new();
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        for price in ["1.5", "1.75", "2.5"] {
+            handler
+                .handle(&InputData::String(String::from(price)), None)
+                .await;
+        }
+        // 1.75 is within tolerance of 1.5.
+        assert_eq!(ch.recv().await.unwrap(), vec![vec![Cell::Float(1.5)]]);
+        assert_eq!(ch.recv().await.unwrap(), vec![vec![Cell::Float(2.5)]]);
+        assert_eq!(db.get(&tt.id).await.unwrap(), 2);
+
+        let reports = db.read_reports(tt.id).await.unwrap().unwrap();
+        assert_eq!(reports.len(), 3);
+        let skipped = reports
+            .into_iter()
+            .filter(|report| {
+                Report::from_model(report.clone())
+                    .phases
+                    .contains_key("SKIPPED")
+            })
+            .count();
+        assert_eq!(skipped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_handle_columns() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_process(Process::new(
+            "prices",
+            vec![Definition::new(vec!["DEFINE(OUT, GET(IN))"])],
+            None,
+        ));
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, mut ch) = TestAPI::new();
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        handler
+            .handle(&InputData::Json(json!({"c": 3, "a": 1})), None)
+            .await;
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![vec![Cell::Int(1), Cell::Int(3)]]
+        );
+
+        // new key is appended after known columns and missing one is left empty.
+        handler
+            .handle(&InputData::Json(json!({"b": 2, "c": 4})), None)
+            .await;
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![vec![Cell::Empty, Cell::Int(4), Cell::Int(2)]]
+        );
+        assert_eq!(
+            db.read_columns(tt.id).await.unwrap(),
+            Some(vec![
+                String::from("a"),
+                String::from("c"),
+                String::from("b")
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_backfill() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_process(Process::new(
+            "history",
+            vec![Definition::new(vec!["DEFINE(OUT, INT(GET(IN)))"])],
+            None,
+        ));
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, mut ch) = TestAPI::new();
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        let inputs = ["1", "x", "3"]
+            .into_iter()
+            .map(|input| InputData::String(String::from(input)))
+            .collect();
+        let backfill = tokio::spawn(async move {
+            handler.backfill(inputs).await;
+            handler
+        });
+        assert_eq!(ch.recv().await.unwrap(), vec![vec![Cell::Int(1)]]);
+        assert_eq!(ch.recv().await.unwrap(), vec![vec![Cell::Int(3)]]);
+        let mut handler = backfill.await.unwrap();
+
+        let progress = db.read_backfill(tt.id).await.unwrap().unwrap();
+        assert_eq!(progress.status, BackfillStatus::Finished);
+        assert_eq!((progress.processed, progress.failed), (3, 1));
+        let reports = db.read_reports(tt.id).await.unwrap().unwrap();
+        assert_eq!(reports.len(), 1);
+        let report = Report::from_model(reports[0].clone());
+        assert!(!report.success);
+        assert_eq!(report.items.len(), 3);
+        assert!(report.items[1].error.is_some());
+
+        // cancel command received before the first input.
+        cmd_sender.send(Command::CancelBackfill).await.unwrap();
+        handler
+            .backfill(vec![InputData::String(String::from("4"))])
+            .await;
+        let progress = db.read_backfill(tt.id).await.unwrap().unwrap();
+        assert_eq!(progress.status, BackfillStatus::Cancelled);
+        assert_eq!(progress.processed, 0);
+        assert!(ch.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_alerts() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_process(Process::new(
+            "price",
+            vec![Definition::new(vec!["DEFINE(OUT, FLOAT(GET(IN)))"])],
+            None,
+        ))
+        .with_alerts(AlertConfig {
+            rules: vec![AlertRule {
+                name: String::from("price"),
+                condition: String::from("GT(IN, INT(100))"),
+                resolve: None,
+                severity: Severity::Critical,
+                cooldown_secs: 0,
+            }],
+            notifiers: vec![],
+        });
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, _ch) = TestAPI::new();
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        handler
+            .handle(&InputData::String(String::from("90")), None)
+            .await;
+        assert!(db.read_alerts(tt.id).await.unwrap().is_empty());
+
+        handler
+            .handle(&InputData::String(String::from("120")), None)
+            .await;
+        let alerts = db.read_alerts(tt.id).await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].status, AlertStatus::Firing);
+        assert_eq!(alerts[0].value, serde_json::json!(120.0));
+        let fired_at = alerts[0].fired_at;
+
+        // alert keeps firing, nothing changes.
+        handler
+            .handle(&InputData::String(String::from("130")), None)
+            .await;
+        assert_eq!(db.read_alerts(tt.id).await.unwrap()[0].fired_at, fired_at);
+
+        handler
+            .handle(&InputData::String(String::from("80")), None)
+            .await;
+        let alerts = db.read_alerts(tt.id).await.unwrap();
+        assert_eq!(alerts[0].status, AlertStatus::Resolved);
+        assert!(alerts[0].resolved_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_handle_redacts_secrets() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_process(Process::new(
+            "token",
+            vec![Definition::new(vec!["DEFINE(OUT, SECRET(api_token))"])],
+            None,
+        ))
+        .with_alerts(AlertConfig {
+            rules: vec![AlertRule {
+                name: String::from("token"),
+                condition: String::from("NEQ(IN, INT(0))"),
+                resolve: None,
+                severity: Severity::Critical,
+                cooldown_secs: 0,
+            }],
+            notifiers: vec![],
         });
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()))
+            .with_master_key(MasterKey::from_bytes(&[1; 32]).unwrap());
+        db.save_secret("api_token", "s3cr3t").await.unwrap();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, _ch) = TestAPI::new();
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        let input = InputData::String(String::from("x"));
+        handler.handle(&input, None).await;
+        let alerts = db.read_alerts(tt.id).await.unwrap();
+        assert_eq!(alerts[0].value, serde_json::json!("***"));
+
+        // error of failed evaluation contains value of the secret.
+        handler.task.process = Process::new(
+            "token",
+            vec![Definition::new(vec![
+                "DEFINE(OUT, ADD(SECRET(api_token), INT(1)))",
+            ])],
+            None,
+        );
+        handler.handle(&input, None).await;
+        let reports = db.read_reports(tt.id).await.unwrap().unwrap();
+        let error = reports
+            .iter()
+            .find_map(|report| report.error.clone())
+            .unwrap();
+        assert!(error.contains("***") && !error.contains("s3cr3t"));
+        let dead_letters = db.read_dead_letters(tt.id).await.unwrap();
+        assert!(!dead_letters[0].error.contains("s3cr3t"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_overwrite() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1:B2".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_write_mode(WriteMode::Overwrite);
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, mut ch) = TestAPI::new();
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        handler
+            .handle(&InputData::String(String::from("x")), None)
+            .await;
+        // whole range is written, so values of previous runs are cleared.
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![
+                vec![Cell::String(String::from("x")), Cell::Empty],
+                vec![Cell::Empty, Cell::Empty]
+            ]
+        );
+        assert!(db.get(&tt.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_replay() {
+        let mut tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        );
+        tt.process = Process::new(
+            "replay",
+            vec![Definition::new(vec!["DEFINE(OUT, SECRET(missing))"])],
+            None,
+        );
+        tt.status = State::Stopped;
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (api, mut ch) = TestAPI::new();
+        let (_, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt.clone(),
+            db.clone(),
+            Shutdown::new(shutdown_sender, shutdown_receiver),
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+        let input = InputData::String(String::from("x"));
+        let dead_letter = DeadLetter::new(
+            tt.id,
+            input.clone(),
+            RetryPhase::Evaluate,
+            String::from("secret missing"),
+            1,
+        );
+        db.save_dead_letter(&dead_letter).await.unwrap();
+        let replay = || Command::Replay(dead_letter.id, input.clone());
+
+        // stopped task does not handle replayed input.
+        handler.apply(replay()).await.unwrap();
+        assert!(db.read_reports(tt.id).await.unwrap().unwrap().is_empty());
+        assert!(db.read_dead_letter(dead_letter.id).await.is_ok());
+
+        // failed replay replaces dead letter and is reported as failed.
+        handler.task.status = State::Running;
+        handler.apply(replay()).await.unwrap();
+        let reports = db.read_reports(tt.id).await.unwrap().unwrap();
+        assert!(reports[0].failed);
+        let dead_letters = db.read_dead_letters(tt.id).await.unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].id, dead_letter.id);
+
+        handler.task.process = Process::new(
+            "replay",
+            vec![Definition::new(vec!["DEFINE(OUT, GET(IN))"])],
+            None,
+        );
+        handler.apply(replay()).await.unwrap();
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![vec![Cell::String(String::from("x"))]]
+        );
+        assert!(db.read_dead_letter(dead_letter.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handler() {
+        env_logger::try_init().ok();
+
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            data_fn(),
+            TaskKindRequest::Ticker { interval_secs: 1 },
+        );
+
+        let db = InMemoryPersistance::new();
+        let channels_manager = ChannelsManager::default();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+        let (api, mut ch) = TestAPI::new();
+        let api = Arc::new(api);
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt,
+            Db::new(Box::new(db)),
+            shutdown,
+            api,
+            cmd_receiver,
+            channels_manager,
+        );
+
+        tokio::task::spawn(async move { handler.start().await });
+
+        loop {
+            select! {
+                result = ch.recv() => {
+                    match result {
+                        Some(result) => {
+                            assert_eq!(result, vec![vec![Cell::String(String::from("test"))]]);
+                            break;
+                        },
+                        None => (),
+                    }
+                }
+            }
+        }
+        drop(shutdown_sender);
+        drop(cmd_sender);
+    }
+
+    #[tokio::test]
+    async fn test_handler_triggered() {
+        env_logger::try_init().ok();
+
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            data_fn(),
+            TaskKindRequest::Triggered(Hook::None),
+        );
+        let id = tt.id;
+
+        let db = InMemoryPersistance::new();
+        let channels_manager = ChannelsManager::default();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+        let (api, mut ch) = TestAPI::new();
+        let api = Arc::new(api);
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt,
+            Db::new(Box::new(db)),
+            shutdown,
+            api,
+            cmd_receiver,
+            channels_manager.clone(),
+        );
+
+        tokio::task::spawn(async move { handler.start().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let sender = channels_manager
+            .triggered_channels
+            .lock()
+            .await
+            .get(&id)
+            .unwrap()
+            .clone();
+
+        sender
+            .send(InputData::Vector(vec![InputData::String(String::from(
+                "triggered",
+            ))]))
+            .await
+            .unwrap();
+
+        loop {
+            select! {
+                result = ch.recv() => {
+                    match result {
+                        Some(result) => {
+                            assert_eq!(result, vec![vec![Cell::String(String::from("triggered"))]]);
+                            break;
+                        },
+                        None => (),
+                    }
+                }
+            }
+        }
+        drop(shutdown_sender);
+        drop(cmd_sender);
+    }
+
+    #[tokio::test]
+    async fn test_handler_invocations() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            data_fn(),
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_invocations(1);
+        let id = tt.id;
+
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
+        let channels_manager = ChannelsManager::default();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+        let (api, mut ch) = TestAPI::new();
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt,
+            db.clone(),
+            shutdown,
+            Arc::new(api),
+            cmd_receiver,
+            channels_manager.clone(),
+        );
+
+        tokio::task::spawn(async move { handler.start().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let sender = channels_manager
+            .triggered_channels
+            .lock()
+            .await
+            .get(&id)
+            .unwrap()
+            .clone();
+        sender
+            .send(InputData::String(String::from("test")))
+            .await
+            .unwrap();
+        assert!(ch.recv().await.is_some());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let task = db.read_task(id).await.unwrap();
+        assert_eq!(task.status, State::Finished);
+        assert_eq!(task.invocations, Some(0));
+
+        drop(shutdown_sender);
+        drop(cmd_sender);
+    }
+
+    #[tokio::test]
+    async fn test_handler_ends_at() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            data_fn(),
+            TaskKindRequest::Clicked,
+        )
+        .with_ends_at(Utc::now() + chrono::Duration::milliseconds(200));
+        let id = tt.id;
+
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+        let (api, _ch) = TestAPI::new();
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt,
+            db.clone(),
+            shutdown,
+            Arc::new(api),
+            cmd_receiver,
+            ChannelsManager::default(),
+        );
+
+        // task is finished without being clicked.
+        let job = tokio::task::spawn(async move { handler.start().await });
+        tokio::time::timeout(Duration::from_secs(1), job)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(db.read_task(id).await.unwrap().status, State::Finished);
+
+        drop(shutdown_sender);
+        drop(cmd_sender);
+    }
+
+    #[tokio::test]
+    async fn test_handler_pipeline() {
+        let upstream = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::None),
+        );
+        let downstream = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Triggered(Hook::Task {
+                upstream_id: upstream.id,
+            }),
+        )
+        .with_process(Process::new(
+            "normalize",
+            vec![Definition::new(vec!["DEFINE(OUT, VEC(GET(IN), INT(1)))"])],
+            None,
+        ));
+        let upstream_id = upstream.id;
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let channels_manager = ChannelsManager::default();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let (upstream_api, _upstream_ch) = TestAPI::new();
+        let mut upstream_handler = TaskHandler::new(
+            upstream,
+            db.clone(),
+            Shutdown::new(shutdown_sender.clone(), shutdown_receiver),
+            Arc::new(upstream_api),
+            cmd_receiver,
+            channels_manager.clone(),
+        );
+        let (downstream_cmd_sender, downstream_cmd_receiver) = mpsc::channel(1);
+        let (downstream_api, mut downstream_ch) = TestAPI::new();
+        let mut downstream_handler = TaskHandler::new(
+            downstream,
+            db.clone(),
+            Shutdown::new(shutdown_sender.clone(), shutdown_sender.subscribe()),
+            Arc::new(downstream_api),
+            downstream_cmd_receiver,
+            channels_manager.clone(),
+        );
+
+        tokio::task::spawn(async move { upstream_handler.start().await });
+        tokio::task::spawn(async move { downstream_handler.start().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let sender = channels_manager
+            .triggered_channels
+            .lock()
+            .await
+            .get(&upstream_id)
+            .unwrap()
+            .clone();
         sender
-            .send(InputData::Vector(vec![InputData::String(String::from(
-                "triggered",
-            ))]))
+            .send(InputData::String(String::from("raw")))
             .await
             .unwrap();
 
-        let (sender, mut input_data_receiver) = mpsc::channel(1);
-        receive_input_data(tt, sender);
-        let id = input_data_receiver.recv().await.unwrap();
+        // downstream task gets evaluated OUT of upstream task.
         assert_eq!(
-            id,
-            InputData::Vector(vec![InputData::String(String::from("triggered"))])
-        )
+            downstream_ch.recv().await.unwrap(),
+            vec![vec![Cell::String(String::from("raw")), Cell::Int(1)]]
+        );
+
+        drop(shutdown_sender);
+        drop(cmd_sender);
+        drop(downstream_cmd_sender);
     }
 
     #[tokio::test]
-    async fn test_run_signal_clicked() {
-        let (sender, receiver) = mpsc::channel(1);
+    async fn test_handler_update() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            data_fn(),
+            TaskKindRequest::Triggered(Hook::None),
+        );
+        let id = tt.id;
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let channels_manager = ChannelsManager::default();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+        let (api, mut ch) = TestAPI::new();
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt,
+            db.clone(),
+            shutdown,
+            Arc::new(api),
+            cmd_receiver,
+            channels_manager.clone(),
+        );
+
+        tokio::task::spawn(async move { handler.start().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // process is swapped.
+        cmd_sender
+            .send(Command::Update(Box::new(TaskUpdateRequest {
+                process: Some(Process::new(
+                    "updated",
+                    vec![Definition::new(vec!["DEFINE(OUT, INT(1))"])],
+                    None,
+                )),
+                ..Default::default()
+            })))
+            .await
+            .unwrap();
+        let sender = channels_manager
+            .triggered_channels
+            .lock()
+            .await
+            .get(&id)
+            .unwrap()
+            .clone();
+        sender
+            .send(InputData::String(String::from("test")))
+            .await
+            .unwrap();
+        assert_eq!(ch.recv().await.unwrap(), vec![vec![Cell::Int(1)]]);
+
+        // task is now run by clicks.
+        cmd_sender
+            .send(Command::Update(Box::new(TaskUpdateRequest {
+                kind_request: Some(TaskKindRequest::Clicked),
+                ..Default::default()
+            })))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let clicked = channels_manager
+            .clicked_channels
+            .lock()
+            .await
+            .get(&id)
+            .unwrap()
+            .clone();
+        clicked.send(()).await.unwrap();
+        assert_eq!(ch.recv().await.unwrap(), vec![vec![Cell::Int(1)]]);
 
+        drop(shutdown_sender);
+        drop(cmd_sender);
+    }
+
+    #[tokio::test]
+    async fn test_handler_batch() {
         let tt = TrackingTask::new(
             "spreadsheet_id".to_string(),
             "".to_string(),
-            "A1:B1".to_string(),
+            "A1".to_string(),
             Direction::Vertical,
             data_fn(),
-            TaskKindRequest::Ticker { interval_secs: 1 },
+            TaskKindRequest::Triggered(Hook::None),
         )
-        .with_kind(TaskKind::Clicked {
-            ch: Arc::new(Mutex::new(receiver)),
+        .with_batch(BatchConfig {
+            max_items: 3,
+            max_wait_ms: 1000,
         });
-        sender.send(()).await.unwrap();
+        let id = tt.id;
 
-        let (sender, mut input_data_receiver) = mpsc::channel(1);
-        receive_input_data(tt, sender);
-        let id = input_data_receiver.recv().await.unwrap();
-        assert_eq!(id, InputData::String(String::from("test")));
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let channels_manager = ChannelsManager::default();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+        let (api, mut ch) = TestAPI::new();
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt,
+            db.clone(),
+            shutdown,
+            Arc::new(api),
+            cmd_receiver,
+            channels_manager.clone(),
+        );
+
+        tokio::task::spawn(async move { handler.start().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let sender = channels_manager
+            .triggered_channels
+            .lock()
+            .await
+            .get(&id)
+            .unwrap()
+            .clone();
+        for i in 0..3 {
+            sender.send(InputData::String(i.to_string())).await.unwrap();
+        }
+
+        // all inputs are written at once.
+        let written = ch.recv().await.unwrap();
+        assert_eq!(
+            written,
+            vec![
+                vec![Cell::String(String::from("0"))],
+                vec![Cell::String(String::from("1"))],
+                vec![Cell::String(String::from("2"))],
+            ]
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let reports = db.read_reports(id).await.unwrap().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(!reports[0].failed);
+        let report = Report::from_model(reports[0].clone());
+        assert_eq!(report.items.len(), 3);
+        assert!(report.items.iter().all(|item| item.error.is_none()));
+
+        drop(shutdown_sender);
+        drop(cmd_sender);
     }
 
     #[tokio::test]
-    async fn test_handler() {
-        env_logger::try_init().ok();
-
+    async fn test_handler_buffer_overflow() {
         let tt = TrackingTask::new(
             "spreadsheet_id".to_string(),
             "".to_string(),
             "A1".to_string(),
             Direction::Vertical,
             data_fn(),
-            TaskKindRequest::Ticker { interval_secs: 1 },
-        );
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_buffer(BufferConfig {
+            capacity: 1,
+            overflow: OverflowPolicy::DropNewest,
+            durable: false,
+        });
+        let id = tt.id;
 
-        let db = InMemoryPersistance::new();
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
         let channels_manager = ChannelsManager::default();
         let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
         let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
         let (api, mut ch) = TestAPI::new();
-        let api = Arc::new(api);
         let (cmd_sender, cmd_receiver) = mpsc::channel(1);
         let mut handler = TaskHandler::new(
             tt,
-            Db::new(Box::new(db)),
+            db.clone(),
             shutdown,
-            api,
+            Arc::new(api),
             cmd_receiver,
-            channels_manager,
+            channels_manager.clone(),
         );
 
         tokio::task::spawn(async move { handler.start().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
 
-        loop {
-            select! {
-                result = ch.recv() => {
-                    match result {
-                        Some(result) => {
-                            assert_eq!(result, vec![vec![String::from(r#"String("test")"#)]]);
-                            break;
-                        },
-                        None => (),
-                    }
-                }
-            }
+        let sender = channels_manager
+            .triggered_channels
+            .lock()
+            .await
+            .get(&id)
+            .unwrap()
+            .clone();
+        // api does not take writes, so handler gets stuck and buffer overflows.
+        for i in 0..5 {
+            sender.send(InputData::String(i.to_string())).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut written = 0;
+        while let Ok(Some(_)) = tokio::time::timeout(Duration::from_millis(200), ch.recv()).await {
+            written += 1;
         }
+
+        let task = db.read_task(id).await.unwrap();
+        assert!(task.dropped_inputs > 0);
+        assert_eq!(written + task.dropped_inputs, 5);
+
         drop(shutdown_sender);
         drop(cmd_sender);
     }
 
     #[tokio::test]
-    async fn test_handler_triggered() {
-        env_logger::try_init().ok();
-
+    async fn test_handler_timestamp() {
         let tt = TrackingTask::new(
             "spreadsheet_id".to_string(),
             "".to_string(),
             "A1".to_string(),
-            Direction::Vertical,
+            Direction::Horizontal,
             data_fn(),
             TaskKindRequest::Triggered(Hook::None),
-        );
+        )
+        .with_timestamp(true, TimestampPosition::Before)
+        .with_timestamp_format(String::from("%Y"), String::from("Europe/Warsaw"));
         let id = tt.id;
 
-        let db = InMemoryPersistance::new();
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
         let channels_manager = ChannelsManager::default();
         let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
         let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
         let (api, mut ch) = TestAPI::new();
-        let api = Arc::new(api);
         let (cmd_sender, cmd_receiver) = mpsc::channel(1);
         let mut handler = TaskHandler::new(
             tt,
-            Db::new(Box::new(db)),
+            db.clone(),
             shutdown,
-            api,
+            Arc::new(api),
             cmd_receiver,
             channels_manager.clone(),
         );
@@ -621,31 +2599,159 @@ mod tests {
             .get(&id)
             .unwrap()
             .clone();
+        sender
+            .send(InputData::String(String::from("0")))
+            .await
+            .unwrap();
+
+        // horizontal task writes a column with timestamp above the value.
+        let year = Utc::now().format("%Y").to_string();
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![
+                vec![Cell::Timestamp(year)],
+                vec![Cell::String(String::from("0"))]
+            ]
+        );
+
+        drop(shutdown_sender);
+        drop(cmd_sender);
+    }
+
+    #[tokio::test]
+    async fn test_handler_inbox() {
+        let tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            data_fn(),
+            TaskKindRequest::Triggered(Hook::None),
+        )
+        .with_buffer(BufferConfig {
+            durable: true,
+            ..Default::default()
+        });
+        let id = tt.id;
+
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        // input persisted before restart.
+        let entry = InboxEntry::new(id, InputData::String(String::from("0")));
+        db.save_inbox_entry(&entry).await.unwrap();
+
+        let channels_manager = ChannelsManager::default();
+        let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+        let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+        let (api, mut ch) = TestAPI::new();
+        let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+        let mut handler = TaskHandler::new(
+            tt,
+            db.clone(),
+            shutdown,
+            Arc::new(api),
+            cmd_receiver,
+            channels_manager.clone(),
+        )
+        .with_inbox(db.read_inbox(id).await.unwrap());
+
+        tokio::task::spawn(async move { handler.start().await });
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![vec![Cell::String(String::from("0"))]]
+        );
 
+        let sender = channels_manager
+            .triggered_channels
+            .lock()
+            .await
+            .get(&id)
+            .unwrap()
+            .clone();
         sender
-            .send(InputData::Vector(vec![InputData::String(String::from(
-                "triggered",
-            ))]))
+            .send(InputData::String(String::from("1")))
             .await
             .unwrap();
+        assert_eq!(
+            ch.recv().await.unwrap(),
+            vec![vec![Cell::String(String::from("1"))]]
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // entries are removed after handling.
+        assert!(db.read_inbox(id).await.unwrap().is_empty());
 
-        loop {
-            select! {
-                result = ch.recv() => {
-                    match result {
-                        Some(result) => {
-                            assert_eq!(result, vec![vec![String::from(r#"Vector([String("triggered")])"#)]]);
-                            break;
-                        },
-                        None => (),
-                    }
-                }
-            }
-        }
         drop(shutdown_sender);
         drop(cmd_sender);
     }
 
+    #[tokio::test]
+    async fn test_handler_active_window() {
+        for outside in [OutsideWindow::Drop, OutsideWindow::Defer] {
+            let today = Utc::now().date_naive();
+            // window is closed today and tomorrow.
+            let window = ActiveWindow {
+                blackout_dates: vec![today, today.succ_opt().unwrap()],
+                outside,
+                ..Default::default()
+            };
+            let tt = TrackingTask::new(
+                "spreadsheet_id".to_string(),
+                "".to_string(),
+                "A1".to_string(),
+                Direction::Vertical,
+                data_fn(),
+                TaskKindRequest::Triggered(Hook::None),
+            )
+            .with_active_window(window);
+            let id = tt.id;
+
+            let db = Db::new(Box::new(InMemoryPersistance::new()));
+            let channels_manager = ChannelsManager::default();
+            let (shutdown_sender, shutdown_receiver) = broadcast::channel(1);
+            let shutdown = Shutdown::new(shutdown_sender.clone(), shutdown_receiver);
+            let (api, mut ch) = TestAPI::new();
+            let (cmd_sender, cmd_receiver) = mpsc::channel(1);
+            let mut handler = TaskHandler::new(
+                tt,
+                db.clone(),
+                shutdown,
+                Arc::new(api),
+                cmd_receiver,
+                channels_manager.clone(),
+            );
+            let buffer = handler.buffer.clone();
+
+            tokio::task::spawn(async move { handler.start().await });
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            let sender = channels_manager
+                .triggered_channels
+                .lock()
+                .await
+                .get(&id)
+                .unwrap()
+                .clone();
+            sender
+                .send(InputData::String(String::from("input")))
+                .await
+                .unwrap();
+
+            assert!(
+                tokio::time::timeout(Duration::from_millis(200), ch.recv())
+                    .await
+                    .is_err(),
+                "nothing should be written outside of active window"
+            );
+            match outside {
+                OutsideWindow::Drop => assert!(buffer.is_empty()),
+                OutsideWindow::Defer => assert_eq!(buffer.len(), 1),
+            }
+
+            drop(shutdown_sender);
+            drop(cmd_sender);
+        }
+    }
+
     #[tokio::test]
     async fn test_handler_interval() {
         env_logger::try_init().ok();
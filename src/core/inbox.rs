@@ -0,0 +1,24 @@
+use super::task::InputData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Input persisted by durable buffer, kept until task's run with it is finished.
+pub struct InboxEntry {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub input: InputData,
+    pub created_at: DateTime<Utc>,
+}
+
+impl InboxEntry {
+    pub fn new(task_id: Uuid, input: InputData) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            task_id,
+            input,
+            created_at: Utc::now(),
+        }
+    }
+}
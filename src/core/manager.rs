@@ -1,13 +1,20 @@
+use super::task::InputData;
+use crate::error::types::Error;
+use crate::server::task::TaskUpdateRequest;
 use std::collections::HashMap;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use uuid::Uuid; // crate for async traits.
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 /// Command that can be run in Manager.
 pub enum Command {
-    Resume, // stars stopped task.
-    Stop,   // stops task.
-    Delete, // delete task.
+    Resume,                         // stars stopped task.
+    Stop,                           // stops task.
+    Delete,                         // delete task.
+    Replay(Uuid, InputData), // handles input of dead letter again, dead letter is removed on success.
+    Update(Box<TaskUpdateRequest>), // swaps changed fields of running task.
+    Backfill(Vec<InputData>), // handles historical inputs one by one.
+    CancelBackfill,          // stops running backfill after currently handled input.
 }
 
 impl Command {
@@ -22,7 +29,7 @@ impl Command {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 /// Contains tasks' uuid and command to be applied.
 pub struct TaskCommand {
     pub id: Uuid,
@@ -55,13 +62,21 @@ impl SenderManager {
         self.mapping.insert(uuid, send);
         receive
     }
-    pub async fn apply(&self, uuid: Uuid, cmd: Command) {
-        if let Err(err) = self.mapping.get(&uuid).unwrap().send(cmd).await {
-            error!(
-                "failed to send command to {} task, {:?}",
-                uuid.to_simple(),
-                err
+    /// Sends command to handler of a task, fails if task has no handler, e.g. it's finished.
+    pub async fn apply(&self, uuid: Uuid, cmd: Command) -> Result<(), Error> {
+        let sender = self.mapping.get(&uuid).ok_or_else(|| {
+            Error::new_internal(
+                String::from("SenderManager::apply"),
+                format!("{} task has no handler", uuid.to_simple()),
+                format!("{:?}", cmd),
             )
-        };
+        })?;
+        sender.send(cmd).await.map_err(|err| {
+            Error::new_internal(
+                String::from("SenderManager::apply"),
+                format!("failed to send command to {} task", uuid.to_simple()),
+                format!("{:?}", err),
+            )
+        })
     }
 }
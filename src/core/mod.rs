@@ -1,6 +1,22 @@
+pub mod alert;
+pub mod backfill;
+pub mod buffer;
 pub mod channels;
+pub mod dead_letter;
+pub mod dedup;
+pub mod dry_run;
+pub mod grid;
 pub mod handler;
+pub mod inbox;
 pub mod manager;
+pub mod notifier;
+pub mod pipeline;
+pub mod quarantine;
+pub mod range;
+pub mod retry;
+pub mod schedule;
+pub mod secret;
 pub mod task;
 pub mod tracker;
 pub mod types;
+pub mod write_mode;
@@ -0,0 +1,372 @@
+use super::alert::{Alert, AlertStatus, Severity};
+use crate::error::types::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// Notifiers are called by task's handler, so hanging destination can't stall the task longer than that.
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// Destination of alert events, every fired and resolved alert is sent to all notifiers of a task.
+pub enum Notifier {
+    Log, // alert is logged with level matching its severity.
+    Webhook {
+        url: String,
+    }, // alert is posted as json.
+    Email {
+        to: String,
+        from: String,
+        #[serde(default = "default_smtp_host")]
+        host: String,
+        #[serde(default = "default_smtp_port")]
+        port: u16,
+    }, // alert is sent through SMTP server, local one by default.
+}
+
+fn default_smtp_host() -> String {
+    String::from("localhost")
+}
+
+fn default_smtp_port() -> u16 {
+    25
+}
+
+impl Notifier {
+    /// Validates notifier, email addresses are sent in SMTP commands and headers as they are.
+    pub fn validate(&self) -> Result<()> {
+        if let Notifier::Email { to, from, .. } = self {
+            for address in [to, from] {
+                if !is_address(address) {
+                    return Err(Error::new_validation(
+                        "Notifier",
+                        format!("{} is not a single email address", address).as_str(),
+                        "notifiers",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn notify(&self, alert: &Alert) -> Result<()> {
+        self.notify_within(alert, NOTIFY_TIMEOUT).await
+    }
+
+    /// Sends alert, fails if it's not delivered within timeout.
+    async fn notify_within(&self, alert: &Alert, timeout: Duration) -> Result<()> {
+        tokio::time::timeout(timeout, self.send(alert, timeout))
+            .await
+            .map_err(|_| {
+                Error::new_internal(
+                    String::from("Notifier::notify"),
+                    format!("alert was not delivered within {:?}", timeout),
+                    format!("{:?}", self),
+                )
+            })?
+    }
+
+    async fn send(&self, alert: &Alert, timeout: Duration) -> Result<()> {
+        match self {
+            Notifier::Log => {
+                match alert.severity {
+                    Severity::Info => info!("{}", subject(alert)),
+                    Severity::Warning => warn!("{}", subject(alert)),
+                    Severity::Critical => error!("{}", subject(alert)),
+                }
+                Ok(())
+            }
+            Notifier::Webhook { url } => {
+                let webhook_err = |err: reqwest::Error| {
+                    Error::new_internal(
+                        String::from("Notifier::notify"),
+                        format!("failed to call {} webhook", url),
+                        err.to_string(),
+                    )
+                };
+                let client = reqwest::Client::builder()
+                    .timeout(timeout)
+                    .build()
+                    .map_err(webhook_err)?;
+                client
+                    .post(url)
+                    .json(alert)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map(|_| ())
+                    .map_err(webhook_err)
+            }
+            Notifier::Email {
+                to,
+                from,
+                host,
+                port,
+            } => {
+                let body = serde_json::to_string_pretty(alert).unwrap_or_default();
+                send_email(host, *port, from, to, &subject(alert), &body).await
+            }
+        }
+    }
+}
+
+/// Checks if text is a single plain address like alerts@example.com, without line breaks or separators.
+fn is_address(text: &str) -> bool {
+    match text.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !domain.contains('@')
+                && text
+                    .chars()
+                    .all(|ch| ch.is_ascii_graphic() && !"<>()[]\\,;:\"".contains(ch))
+        }
+        None => false,
+    }
+}
+
+fn subject(alert: &Alert) -> String {
+    let status = match alert.status {
+        AlertStatus::Firing => "firing",
+        AlertStatus::Resolved => "resolved",
+    };
+    format!(
+        "[{:?}] alert {} of task {} is {}",
+        alert.severity, alert.rule, alert.task_id, status
+    )
+}
+
+/// Sends plain text message with minimal SMTP dialog, server is expected to accept mail without authentication.
+async fn send_email(
+    host: &str,
+    port: u16,
+    from: &str,
+    to: &str,
+    subject: &str,
+    body: &str,
+) -> Result<()> {
+    let smtp_err = |err: std::io::Error| {
+        Error::new_internal(
+            String::from("send_email"),
+            format!("failed to talk to {}:{} SMTP server", host, port),
+            err.to_string(),
+        )
+    };
+    let stream = TcpStream::connect((host, port)).await.map_err(smtp_err)?;
+    let (read, mut write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    expect_reply(&mut reader, "220").await?;
+
+    // lines starting with a dot have to be escaped, lone dot ends message.
+    let body = body
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') {
+                format!(".{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\r\n");
+    let message = format!(
+        "From: <{}>\r\nTo: <{}>\r\nSubject: {}\r\n\r\n{}\r\n.\r\n",
+        from, to, subject, body
+    );
+    for (command, reply) in [
+        (String::from("HELO localhost\r\n"), "250"),
+        (format!("MAIL FROM:<{}>\r\n", from), "250"),
+        (format!("RCPT TO:<{}>\r\n", to), "250"),
+        (String::from("DATA\r\n"), "354"),
+        (message, "250"),
+        (String::from("QUIT\r\n"), "221"),
+    ] {
+        write
+            .write_all(command.as_bytes())
+            .await
+            .map_err(smtp_err)?;
+        expect_reply(&mut reader, reply).await?;
+    }
+    Ok(())
+}
+
+/// Reads SMTP reply and checks its code, lines of multiline reply have '-' right after the code.
+async fn expect_reply<R>(reader: &mut R, code: &str) -> Result<()>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line).await.map_err(|err| {
+            Error::new_internal(
+                String::from("expect_reply"),
+                String::from("failed to read SMTP reply"),
+                err.to_string(),
+            )
+        })?;
+        if read == 0 || !line.starts_with(code) {
+            return Err(Error::new_internal(
+                String::from("expect_reply"),
+                format!("wanted {} SMTP reply", code),
+                line.trim_end().to_string(),
+            ));
+        }
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Notifier;
+    use crate::core::alert::{Alert, AlertStatus, Severity};
+    use chrono::Utc;
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_notifier_json() {
+        let notifiers: Vec<Notifier> = serde_json::from_str(
+            r#"["log", {"webhook": {"url": "http://alerts"}}, {"email": {"to": "ops@localhost", "from": "tracker@localhost"}}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            notifiers,
+            vec![
+                Notifier::Log,
+                Notifier::Webhook {
+                    url: String::from("http://alerts")
+                },
+                Notifier::Email {
+                    to: String::from("ops@localhost"),
+                    from: String::from("tracker@localhost"),
+                    host: String::from("localhost"),
+                    port: 25,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_email() {
+        let email = |to: &str| Notifier::Email {
+            to: to.to_string(),
+            from: String::from("tracker@localhost"),
+            host: String::from("localhost"),
+            port: 25,
+        };
+        assert!(email("ops@localhost").validate().is_ok());
+        for invalid in [
+            "ops",
+            "ops@localhost\r\nRCPT TO:<other@localhost>",
+            "ops@localhost>\r\nBcc: other@localhost",
+            "ops@localhost, other@localhost",
+            "@localhost",
+        ] {
+            assert!(email(invalid).validate().is_err(), "{}", invalid);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_email() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // fake SMTP server that accepts every command and returns received message.
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut reader = BufReader::new(read);
+            write
+                .write_all(b"220-localhost\r\n220 ready\r\n")
+                .await
+                .unwrap();
+            let mut message = String::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        write.write_all(b"250 queued\r\n").await.unwrap();
+                    } else {
+                        message.push_str(&line);
+                    }
+                } else if line.starts_with("DATA") {
+                    in_data = true;
+                    write.write_all(b"354 go ahead\r\n").await.unwrap();
+                } else if line.starts_with("QUIT") {
+                    write.write_all(b"221 bye\r\n").await.unwrap();
+                    return message;
+                } else {
+                    write.write_all(b"250 ok\r\n").await.unwrap();
+                }
+            }
+        });
+
+        let alert = Alert {
+            task_id: Uuid::new_v4(),
+            rule: String::from("price"),
+            severity: Severity::Critical,
+            status: AlertStatus::Firing,
+            value: json!(120),
+            fired_at: Utc::now(),
+            notified_at: None,
+            resolved_at: None,
+        };
+        let notifier = Notifier::Email {
+            to: String::from("ops@localhost"),
+            from: String::from("tracker@localhost"),
+            host: String::from("127.0.0.1"),
+            port,
+        };
+        notifier.notify(&alert).await.unwrap();
+
+        let message = server.await.unwrap();
+        assert!(message.contains("To: <ops@localhost>"));
+        assert!(message.contains(&format!(
+            "Subject: [Critical] alert price of task {} is firing",
+            alert.task_id
+        )));
+        assert!(message.contains(r#""status": "firing""#));
+    }
+
+    #[tokio::test]
+    async fn test_notify_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // server accepts connection, but never greets.
+        let _server = tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+
+        let alert = Alert {
+            task_id: Uuid::new_v4(),
+            rule: String::from("price"),
+            severity: Severity::Critical,
+            status: AlertStatus::Firing,
+            value: json!(120),
+            fired_at: Utc::now(),
+            notified_at: None,
+            resolved_at: None,
+        };
+        let notifier = Notifier::Email {
+            to: String::from("ops@localhost"),
+            from: String::from("tracker@localhost"),
+            host: String::from("127.0.0.1"),
+            port,
+        };
+        assert!(notifier
+            .notify_within(&alert, Duration::from_millis(100))
+            .await
+            .is_err());
+    }
+}
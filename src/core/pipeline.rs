@@ -0,0 +1,160 @@
+use super::task::TrackingTask;
+use super::types::{Hook, State};
+use crate::error::types::{Error, Result};
+use crate::persistance::interface::Db;
+use crate::server::task::TaskKindRequest;
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PipelineNode {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PipelineEdge {
+    pub upstream: Uuid,
+    pub downstream: Uuid,
+}
+
+/// Dependency graph of tasks chained with Hook::Task.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub nodes: Vec<PipelineNode>,
+    pub edges: Vec<PipelineEdge>,
+}
+
+/// Returns id of upstream task if task is triggered by another task.
+pub fn upstream_of(kind_request: &TaskKindRequest) -> Option<Uuid> {
+    match kind_request {
+        TaskKindRequest::Triggered(Hook::Task { upstream_id }) => Some(*upstream_id),
+        _ => None,
+    }
+}
+
+impl Pipeline {
+    pub fn from_tasks(tasks: &[TrackingTask]) -> Self {
+        let mut pipeline = Self::default();
+        for task in tasks {
+            pipeline.nodes.push(PipelineNode {
+                id: task.id,
+                name: task.name.clone().unwrap_or_default(),
+            });
+            if let Some(upstream) = upstream_of(&task.kind_request) {
+                pipeline.edges.push(PipelineEdge {
+                    upstream,
+                    downstream: task.id,
+                });
+            }
+        }
+        pipeline
+    }
+
+    /// Builds graph of all persisted tasks.
+    pub async fn load(db: &Db) -> Result<Self> {
+        let tasks = db
+            .clone()
+            .get_tasks_by_status(&[
+                State::Created,
+                State::Running,
+                State::Stopped,
+                State::Quit,
+                State::Finished,
+            ])
+            .await?;
+        Ok(Self::from_tasks(&tasks))
+    }
+
+    /// Checks if task can be triggered by upstream task, upstream has to exist
+    /// and chaining can't create a cycle.
+    pub fn check(&self, task_id: Uuid, upstream_id: Uuid) -> Result<()> {
+        if !self.nodes.iter().any(|node| node.id == upstream_id) {
+            return Err(Error::new_validation(
+                "TaskKindRequest",
+                format!("upstream task {} does not exist", upstream_id).as_str(),
+                "upstream_id",
+            ));
+        }
+
+        let upstreams: HashMap<Uuid, Uuid> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.downstream != task_id)
+            .map(|edge| (edge.downstream, edge.upstream))
+            .collect();
+        let mut current = Some(upstream_id);
+        // every task has at most one upstream, so walking up can loop only through a cycle.
+        for _ in 0..=upstreams.len() {
+            match current {
+                Some(id) if id == task_id => {
+                    return Err(Error::new_validation(
+                        "TaskKindRequest",
+                        format!("task {} would create a cycle in pipeline", task_id).as_str(),
+                        "upstream_id",
+                    ))
+                }
+                Some(id) => current = upstreams.get(&id).copied(),
+                None => return Ok(()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pipeline, PipelineEdge, PipelineNode};
+    use crate::error::types::Error;
+    use uuid::Uuid;
+
+    fn pipeline(ids: &[Uuid]) -> Pipeline {
+        Pipeline {
+            nodes: ids
+                .iter()
+                .map(|id| PipelineNode {
+                    id: *id,
+                    name: String::default(),
+                })
+                .collect(),
+            // ids[0] -> ids[1] -> ids[2]
+            edges: ids
+                .windows(2)
+                .map(|pair| PipelineEdge {
+                    upstream: pair[0],
+                    downstream: pair[1],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let pipeline = pipeline(&ids);
+
+        assert!(pipeline.check(Uuid::new_v4(), ids[2]).is_ok());
+        // task can change its upstream.
+        assert!(pipeline.check(ids[2], ids[0]).is_ok());
+
+        let unknown = Uuid::new_v4();
+        assert_eq!(
+            pipeline.check(Uuid::new_v4(), unknown),
+            Err(Error::new_validation(
+                "TaskKindRequest",
+                format!("upstream task {} does not exist", unknown).as_str(),
+                "upstream_id"
+            ))
+        );
+        assert_eq!(
+            pipeline.check(ids[0], ids[2]),
+            Err(Error::new_validation(
+                "TaskKindRequest",
+                format!("task {} would create a cycle in pipeline", ids[0]).as_str(),
+                "upstream_id"
+            ))
+        );
+        assert!(pipeline.check(ids[0], ids[0]).is_err());
+    }
+}
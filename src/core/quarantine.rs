@@ -0,0 +1,27 @@
+use super::task::InputData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Input that was rejected by Process's input_schema and saved for later inspection.
+pub struct QuarantinedInput {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub input: InputData,
+    /// Validation error that caused rejection.
+    pub error: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl QuarantinedInput {
+    pub fn new(task_id: Uuid, input: InputData, error: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            task_id,
+            input,
+            error,
+            created_at: Utc::now(),
+        }
+    }
+}
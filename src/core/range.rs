@@ -0,0 +1,275 @@
+use super::types::Direction;
+use crate::error::types::{Error, Result};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Number of the last column of a sheet, XFD.
+pub const MAX_COLUMN: u32 = 16384;
+/// Number of the last row of a sheet.
+pub const MAX_ROW: u32 = 1048576;
+
+fn invalid(msg: String) -> Error {
+    Error::new_validation("A1Range", msg.as_str(), "starting_position")
+}
+
+/// Translates column letters to 1-based column number, e.g. A is 1 and AA is 27.
+pub fn column_number(letters: &str) -> Result<u32> {
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(invalid(format!("invalid column {}", letters)));
+    }
+    let mut number: u32 = 0;
+    for c in letters.to_ascii_uppercase().chars() {
+        number = number * 26 + (c as u32 - 'A' as u32 + 1);
+        if number > MAX_COLUMN {
+            return Err(invalid(format!("column {} is out of range", letters)));
+        }
+    }
+    Ok(number)
+}
+
+/// Translates 1-based column number to column letters, e.g. 28 is AB.
+pub fn column_letters(mut number: u32) -> String {
+    let mut letters = Vec::new();
+    while number > 0 {
+        let rem = (number - 1) % 26;
+        letters.push((b'A' + rem as u8) as char);
+        number = (number - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Single cell in A1 notation, e.g. B3 or $B$3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellRef {
+    pub column: u32, // 1-based.
+    pub row: u32,    // 1-based.
+    pub absolute_column: bool,
+    pub absolute_row: bool,
+}
+
+impl CellRef {
+    pub fn new(column: u32, row: u32) -> Self {
+        Self {
+            column,
+            row,
+            absolute_column: false,
+            absolute_row: false,
+        }
+    }
+
+    /// Returns cell moved right by given number of columns and down by given number of rows.
+    pub fn offset(&self, columns: u32, rows: u32) -> Result<Self> {
+        let column = self.column + columns;
+        let row = self.row + rows;
+        if column > MAX_COLUMN || row > MAX_ROW {
+            return Err(invalid(format!(
+                "{} moved by {} columns and {} rows is out of sheet",
+                self, columns, rows
+            )));
+        }
+        Ok(Self::new(column, row))
+    }
+}
+
+impl FromStr for CellRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (absolute_column, rest) = match s.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let split = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (letters, rest) = rest.split_at(split);
+        let (absolute_row, digits) = match rest.strip_prefix('$') {
+            Some(digits) => (true, digits),
+            None => (false, rest),
+        };
+        let column = column_number(letters).map_err(|_| invalid(format!("invalid cell {}", s)))?;
+        let row = digits
+            .parse::<u32>()
+            .ok()
+            .filter(|row| (1..=MAX_ROW).contains(row))
+            .ok_or_else(|| invalid(format!("invalid cell {}", s)))?;
+        Ok(Self {
+            column,
+            row,
+            absolute_column,
+            absolute_row,
+        })
+    }
+}
+
+impl Display for CellRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            if self.absolute_column { "$" } else { "" },
+            column_letters(self.column),
+            if self.absolute_row { "$" } else { "" },
+            self.row
+        )
+    }
+}
+
+/// Range in A1 notation with optional sheet name, e.g. A1, A1:C3 or 'My sheet'!A1:C3.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct A1Range {
+    pub sheet: Option<String>,
+    pub start: CellRef,
+    pub end: Option<CellRef>, // None if range is a single cell.
+}
+
+impl A1Range {
+    /// Returns range of a write that starts at given cell, after offset lines that were written before.
+    /// Lines are rows of vertical tasks and columns of horizontal ones, every line has width cells.
+    pub fn for_write(
+        start: CellRef,
+        sheet: Option<String>,
+        direction: Direction,
+        offset: u32,
+        lines: u32,
+        width: u32,
+    ) -> Result<Self> {
+        let lines = lines.max(1);
+        let width = width.max(1);
+        let (first, last) = match direction {
+            Direction::Vertical => (
+                start.offset(0, offset)?,
+                start.offset(width - 1, offset + lines - 1)?,
+            ),
+            Direction::Horizontal => (
+                start.offset(offset, 0)?,
+                start.offset(offset + lines - 1, width - 1)?,
+            ),
+        };
+        Ok(Self {
+            sheet,
+            start: first,
+            end: Some(last),
+        })
+    }
+}
+
+/// Sheet names other than plain words have to be quoted, quotes inside are doubled.
+fn format_sheet(sheet: &str) -> String {
+    if !sheet.is_empty() && sheet.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        sheet.to_string()
+    } else {
+        format!("'{}'", sheet.replace('\'', "''"))
+    }
+}
+
+/// Splits sheet name from the rest of the range, quoted name can contain any characters.
+fn split_sheet(s: &str) -> Result<(Option<String>, &str)> {
+    if let Some(quoted) = s.strip_prefix('\'') {
+        let mut sheet = String::new();
+        let mut chars = quoted.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c != '\'' {
+                sheet.push(c);
+                continue;
+            }
+            if let Some((_, '\'')) = chars.peek() {
+                sheet.push('\'');
+                chars.next();
+                continue;
+            }
+            return match quoted[i + 1..].strip_prefix('!') {
+                Some(rest) => Ok((Some(sheet), rest)),
+                None => Err(invalid(format!("expected ! after sheet name in {}", s))),
+            };
+        }
+        return Err(invalid(format!("unterminated sheet name in {}", s)));
+    }
+    Ok(match s.split_once('!') {
+        Some((sheet, rest)) => (Some(sheet.to_string()), rest),
+        None => (None, s),
+    })
+}
+
+impl FromStr for A1Range {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (sheet, cells) = split_sheet(s.trim())?;
+        let (start, end) = match cells.split_once(':') {
+            Some((start, end)) => (start.parse()?, Some(end.parse()?)),
+            None => (cells.parse()?, None),
+        };
+        Ok(Self { sheet, start, end })
+    }
+}
+
+impl Display for A1Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(sheet) = &self.sheet {
+            write!(f, "{}!", format_sheet(sheet))?;
+        }
+        write!(f, "{}", self.start)?;
+        if let Some(end) = &self.end {
+            write!(f, ":{}", end)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{column_letters, column_number, A1Range, CellRef};
+    use crate::core::types::Direction;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_columns() {
+        for (letters, number) in [("A", 1), ("Z", 26), ("AA", 27), ("AZ", 52), ("XFD", 16384)] {
+            assert_eq!(column_number(letters).unwrap(), number);
+            assert_eq!(column_letters(number), letters);
+        }
+        assert!(column_number("XFE").is_err());
+        assert!(column_number("A1").is_err());
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let cell = CellRef::from_str("$AA$10").unwrap();
+        assert_eq!((cell.column, cell.row), (27, 10));
+        assert!(cell.absolute_column && cell.absolute_row);
+        assert_eq!(cell.to_string(), "$AA$10");
+
+        for range in ["A1", "Sheet1!B2:C3", "'My sheet'!A1:B1", "'It''s'!A1"] {
+            assert_eq!(A1Range::from_str(range).unwrap().to_string(), range);
+        }
+        let range = A1Range::from_str("'It''s'!A1").unwrap();
+        assert_eq!(range.sheet, Some(String::from("It's")));
+
+        for invalid in ["", "1A", "A0", "A", "'sheet!A1", "'sheet'A1"] {
+            assert!(A1Range::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_for_write() {
+        let start = CellRef::from_str("Y10").unwrap();
+        let range = A1Range::for_write(start, None, Direction::Vertical, 2, 3, 4).unwrap();
+        assert_eq!(range.to_string(), "Y12:AB14");
+
+        // horizontal writes go past column Z.
+        let range = A1Range::for_write(
+            start,
+            Some(String::from("My sheet")),
+            Direction::Horizontal,
+            2,
+            1,
+            2,
+        )
+        .unwrap();
+        assert_eq!(range.to_string(), "'My sheet'!AA10:AA11");
+
+        let last = CellRef::from_str("XFD1").unwrap();
+        assert!(A1Range::for_write(last, None, Direction::Horizontal, 1, 1, 1).is_err());
+    }
+}
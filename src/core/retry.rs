@@ -0,0 +1,178 @@
+use crate::error::types::Result;
+use futures::Future;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// Phases of task handling that can be retried.
+pub enum RetryPhase {
+    Fetch,    // getting data with task's data function.
+    Evaluate, // running task's process.
+    Write,    // writing data to a spreadsheet.
+}
+
+fn default_max_attempts() -> u32 {
+    1
+}
+
+fn default_multiplier() -> f64 {
+    2.0
+}
+
+fn default_retryable() -> Vec<RetryPhase> {
+    vec![RetryPhase::Fetch, RetryPhase::Evaluate, RetryPhase::Write]
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+/// Describes how failed phases of task handling are retried.
+/// Delay before n-th retry is backoff_ms * multiplier^(n-1), capped by max_backoff_ms
+/// and randomly shifted by up to jitter fraction of it.
+pub struct RetryPolicy {
+    /// Number of attempts including the first one.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before first retry.
+    #[serde(default)]
+    pub backoff_ms: u64,
+
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+
+    pub max_backoff_ms: Option<u64>,
+
+    /// Fraction of delay, from 0 to 1, that is randomly added or subtracted.
+    #[serde(default)]
+    pub jitter: f64,
+
+    /// Phases that are retried, all by default.
+    #[serde(default = "default_retryable")]
+    pub retryable: Vec<RetryPhase>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_ms: 0,
+            multiplier: default_multiplier(),
+            max_backoff_ms: None,
+            jitter: 0.0,
+            retryable: default_retryable(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns delay before given retry, retries are numbered from 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let mut delay = self.backoff_ms as f64 * self.multiplier.powi(retry as i32 - 1);
+        if let Some(max_backoff_ms) = self.max_backoff_ms {
+            delay = delay.min(max_backoff_ms as f64);
+        }
+        if self.jitter > 0.0 {
+            let jitter = self.jitter.min(1.0);
+            delay *= rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter);
+        }
+        Duration::from_millis(delay as u64)
+    }
+
+    /// Runs func until it succeeds or attempts are exhausted, non-retryable phases are run once.
+    /// Returns result of last attempt together with number of attempts made.
+    pub async fn run<T, F, Fut>(&self, phase: RetryPhase, mut func: F) -> (Result<T>, u32)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let max_attempts = if self.retryable.contains(&phase) {
+            self.max_attempts.max(1)
+        } else {
+            1
+        };
+
+        let mut attempt = 1;
+        loop {
+            let result = func().await;
+            if result.is_ok() || attempt >= max_attempts {
+                return (result, attempt);
+            }
+            let delay = self.delay(attempt);
+            warn!(
+                "{:?} phase failed, retrying in {:?} (attempt {}/{})",
+                phase, delay, attempt, max_attempts
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RetryPhase, RetryPolicy};
+    use crate::error::types::Error;
+    use std::time::Duration;
+
+    fn failure() -> Error {
+        Error::new_internal(
+            String::from("test"),
+            String::from("failure"),
+            String::default(),
+        )
+    }
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy {
+            backoff_ms: 100,
+            max_backoff_ms: Some(300),
+            ..Default::default()
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(300));
+
+        let policy = RetryPolicy {
+            backoff_ms: 100,
+            jitter: 0.5,
+            ..Default::default()
+        };
+        let delay = policy.delay(1);
+        assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            retryable: vec![RetryPhase::Write],
+            ..Default::default()
+        };
+
+        let mut calls = 0;
+        let (result, attempts) = policy
+            .run(RetryPhase::Write, || {
+                calls += 1;
+                let result = if calls < 2 { Err(failure()) } else { Ok(calls) };
+                async move { result }
+            })
+            .await;
+        assert_eq!(result, Ok(2));
+        assert_eq!(attempts, 2);
+
+        let (result, attempts) = policy
+            .run(RetryPhase::Write, || async { Err::<(), _>(failure()) })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+
+        // evaluate phase is not retryable.
+        let (result, attempts) = policy
+            .run(RetryPhase::Evaluate, || async { Err::<(), _>(failure()) })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::error::types::{Error, Result};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Cron schedule evaluated in given timezone, used by TaskKind::Cron.
+///
+/// Expression can have standard 5 fields (minute, hour, day of month, month, day of week)
+/// or 6-7 fields with seconds and year. Numeric days of week follow standard cron, 0 or 7 is Sunday,
+/// and are translated to names, because cron crate counts them from 1 as Sunday.
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    schedule: Schedule,
+    timezone: Tz,
+}
+
+impl CronSchedule {
+    pub fn new(expression: &str, timezone: &str) -> Result<Self> {
+        let mut fields: Vec<String> = expression.split_whitespace().map(String::from).collect();
+        // seconds are not part of standard cron expression.
+        if fields.len() == 5 {
+            fields.insert(0, String::from("0"));
+        }
+        if let Some(days) = fields.get_mut(5) {
+            *days = day_of_week_names(days)?;
+        }
+        let normalized = fields.join(" ");
+        let schedule = Schedule::from_str(&normalized).map_err(|err| {
+            Error::new_validation(
+                "CronSchedule",
+                format!("invalid cron expression {}: {}", expression, err).as_str(),
+                "expression",
+            )
+        })?;
+        let timezone = Tz::from_str(timezone).map_err(|err| {
+            Error::new_validation(
+                "CronSchedule",
+                format!("invalid timezone {}: {}", timezone, err).as_str(),
+                "timezone",
+            )
+        })?;
+
+        Ok(Self { schedule, timezone })
+    }
+
+    /// Returns first fire time strictly after given time.
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule
+            .after(&time.with_timezone(&self.timezone))
+            .next()
+            .map(|next| next.with_timezone(&Utc))
+    }
+
+    /// Returns how long to wait from given time till next fire time, None if schedule won't fire again.
+    pub fn until_next(&self, time: DateTime<Utc>) -> Option<Duration> {
+        self.next_after(time)
+            .map(|next| (next - time).to_std().unwrap_or_default())
+    }
+}
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Rewrites numeric values of day of week field into names, e.g. 1-5 into Mon,Tue,Wed,Thu,Fri.
+/// Names and wildcards are left untouched.
+fn day_of_week_names(field: &str) -> Result<String> {
+    let invalid = || {
+        Error::new_validation(
+            "CronSchedule",
+            format!(
+                "invalid day of week {}, expected values 0-7 or names",
+                field
+            )
+            .as_str(),
+            "expression",
+        )
+    };
+    let day = |value: &str| match value.parse::<usize>() {
+        Ok(day) if day <= 7 => Ok(Some(day)),
+        Ok(_) => Err(invalid()),
+        Err(_) => Ok(None),
+    };
+
+    let mut parts = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<usize>().map_err(|_| invalid())?)),
+            None => (part, None),
+        };
+        let (first, last) = match range.split_once('-') {
+            Some((first, last)) => (day(first)?, day(last)?),
+            None if range == "*" || range == "?" => match step {
+                Some(_) => (Some(0), Some(6)),
+                None => (None, None),
+            },
+            None => {
+                let first = day(range)?;
+                (
+                    first,
+                    first.map(|first| if step.is_some() { 7 } else { first }),
+                )
+            }
+        };
+        match (first, last) {
+            (Some(first), Some(last)) if first <= last => {
+                for day in (first..=last).step_by(step.unwrap_or(1).max(1)) {
+                    let name = DAY_NAMES[day % 7];
+                    if !parts.contains(&name.to_string()) {
+                        parts.push(name.to_string());
+                    }
+                }
+            }
+            (None, None) => parts.push(part.to_string()),
+            // descending ranges and ranges mixing numbers with names.
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(parts.join(","))
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+/// Behavior of task when triggered input arrives outside of its ActiveWindow.
+pub enum OutsideWindow {
+    #[default]
+    Drop, // input is discarded.
+    Defer, // input waits in task's buffer until window opens.
+}
+
+fn all_days() -> Vec<Weekday> {
+    vec![
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ]
+}
+
+fn default_end_hour() -> u32 {
+    24
+}
+
+fn default_timezone() -> String {
+    String::from("UTC")
+}
+
+/// Period in which task handles its inputs, e.g. market hours. Task is active on given days
+/// between start_hour (inclusive) and end_hour (exclusive) of local time, except blackout dates.
+/// Tasks that poll for data don't fetch it outside of the window.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ActiveWindow {
+    #[serde(default = "all_days")]
+    pub days: Vec<Weekday>,
+
+    #[serde(default)]
+    pub start_hour: u32,
+
+    #[serde(default = "default_end_hour")]
+    pub end_hour: u32,
+
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+
+    #[serde(default)]
+    pub blackout_dates: Vec<NaiveDate>,
+
+    #[serde(default)]
+    pub outside: OutsideWindow,
+}
+
+impl Default for ActiveWindow {
+    fn default() -> Self {
+        Self {
+            days: all_days(),
+            start_hour: 0,
+            end_hour: default_end_hour(),
+            timezone: default_timezone(),
+            blackout_dates: Vec::new(),
+            outside: OutsideWindow::default(),
+        }
+    }
+}
+
+impl ActiveWindow {
+    pub fn validate(&self) -> Result<()> {
+        self.tz()?;
+        if self.start_hour >= self.end_hour || self.end_hour > 24 {
+            return Err(Error::new_validation(
+                "ActiveWindow",
+                format!(
+                    "invalid hours {}-{}, expected 0 <= start_hour < end_hour <= 24",
+                    self.start_hour, self.end_hour
+                )
+                .as_str(),
+                "start_hour",
+            ));
+        }
+        if self.days.is_empty() {
+            return Err(Error::new_validation(
+                "ActiveWindow",
+                "days cannot be empty",
+                "days",
+            ));
+        }
+        Ok(())
+    }
+
+    fn tz(&self) -> Result<Tz> {
+        Tz::from_str(&self.timezone).map_err(|err| {
+            Error::new_validation(
+                "ActiveWindow",
+                format!("invalid timezone {}: {}", self.timezone, err).as_str(),
+                "timezone",
+            )
+        })
+    }
+
+    /// Checks if window is open at given time.
+    pub fn is_active(&self, time: DateTime<Utc>) -> bool {
+        let local = time.with_timezone(&self.tz().unwrap_or(Tz::UTC));
+        self.days.contains(&local.weekday())
+            && (self.start_hour..self.end_hour).contains(&local.hour())
+            && !self.blackout_dates.contains(&local.date_naive())
+    }
+
+    /// Returns how long to wait from given time till window opens, zero if it's already open.
+    /// None if window doesn't open within a year.
+    pub fn until_active(&self, time: DateTime<Utc>) -> Option<Duration> {
+        if self.is_active(time) {
+            return Some(Duration::ZERO);
+        }
+        // window opens at a full hour, timezones with non-hour offsets are aligned by minutes.
+        let offset = ChronoDuration::minutes(i64::from(
+            time.with_timezone(&self.tz().unwrap_or(Tz::UTC)).minute(),
+        ));
+        let mut candidate = time.with_second(0)?.with_nanosecond(0)? - offset;
+        for _ in 0..366 * 24 {
+            candidate += ChronoDuration::hours(1);
+            if self.is_active(candidate) {
+                return (candidate - time).to_std().ok();
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{day_of_week_names, ActiveWindow, CronSchedule};
+    use chrono::{NaiveDate, TimeZone, Utc, Weekday};
+    use std::time::Duration;
+
+    #[test]
+    fn test_next_after() {
+        let schedule = CronSchedule::new("0 9 * * Mon-Fri", "Europe/Warsaw").unwrap();
+
+        // friday noon, next fire is on monday 09:00 CEST.
+        let friday = Utc.with_ymd_and_hms(2022, 10, 7, 12, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(friday),
+            Some(Utc.with_ymd_and_hms(2022, 10, 10, 7, 0, 0).unwrap())
+        );
+
+        // after change to CET the same local time is an hour later in UTC.
+        let friday = Utc.with_ymd_and_hms(2022, 11, 4, 12, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(friday),
+            Some(Utc.with_ymd_and_hms(2022, 11, 7, 8, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_until_next() {
+        let schedule = CronSchedule::new("*/30 * * * * *", "UTC").unwrap();
+        let time = Utc.with_ymd_and_hms(2022, 10, 7, 12, 0, 10).unwrap();
+        assert_eq!(schedule.until_next(time), Some(Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn test_invalid_schedule() {
+        assert!(CronSchedule::new("0 25 * * *", "UTC").is_err());
+        assert!(CronSchedule::new("0 9 * * *", "Mars/Olympus").is_err());
+        assert!(CronSchedule::new("0 9 * * 5-1", "UTC").is_err());
+        assert!(CronSchedule::new("0 9 * * 8", "UTC").is_err());
+        assert!(CronSchedule::new("0 9 * * Mon-5", "UTC").is_err());
+    }
+
+    #[test]
+    fn test_numeric_days_of_week() {
+        assert_eq!(day_of_week_names("1-5").unwrap(), "Mon,Tue,Wed,Thu,Fri");
+        assert_eq!(day_of_week_names("0,6").unwrap(), "Sun,Sat");
+        assert_eq!(day_of_week_names("5-7").unwrap(), "Fri,Sat,Sun");
+        assert_eq!(day_of_week_names("*/2").unwrap(), "Sun,Tue,Thu,Sat");
+        assert_eq!(day_of_week_names("Mon-Fri").unwrap(), "Mon-Fri");
+        assert_eq!(day_of_week_names("*").unwrap(), "*");
+
+        // 1-5 are weekdays like in standard cron, friday noon fires next on monday.
+        let schedule = CronSchedule::new("0 9 * * 1-5", "UTC").unwrap();
+        let friday = Utc.with_ymd_and_hms(2022, 10, 7, 12, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(friday),
+            Some(Utc.with_ymd_and_hms(2022, 10, 10, 9, 0, 0).unwrap())
+        );
+    }
+
+    fn market_hours() -> ActiveWindow {
+        ActiveWindow {
+            days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            start_hour: 9,
+            end_hour: 17,
+            timezone: String::from("America/New_York"),
+            blackout_dates: vec![NaiveDate::from_ymd_opt(2022, 10, 10).unwrap()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_active() {
+        let window = market_hours();
+        // friday 10:00 EDT.
+        assert!(window.is_active(Utc.with_ymd_and_hms(2022, 10, 7, 14, 0, 0).unwrap()));
+        // friday 17:00 EDT, end hour is exclusive.
+        assert!(!window.is_active(Utc.with_ymd_and_hms(2022, 10, 7, 21, 0, 0).unwrap()));
+        // saturday.
+        assert!(!window.is_active(Utc.with_ymd_and_hms(2022, 10, 8, 14, 0, 0).unwrap()));
+        // blackout monday.
+        assert!(!window.is_active(Utc.with_ymd_and_hms(2022, 10, 10, 14, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_until_active() {
+        let window = market_hours();
+        let open = Utc.with_ymd_and_hms(2022, 10, 7, 14, 0, 0).unwrap();
+        assert_eq!(window.until_active(open), Some(Duration::ZERO));
+
+        // friday 17:30 EDT, monday is blackout so window opens on tuesday 09:00 EDT.
+        let friday = Utc.with_ymd_and_hms(2022, 10, 7, 21, 30, 0).unwrap();
+        let tuesday = Utc.with_ymd_and_hms(2022, 10, 11, 13, 0, 0).unwrap();
+        assert_eq!(
+            window.until_active(friday),
+            Some((tuesday - friday).to_std().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_window() {
+        assert!(market_hours().validate().is_ok());
+        let window = ActiveWindow {
+            start_hour: 17,
+            end_hour: 9,
+            ..Default::default()
+        };
+        assert!(window.validate().is_err());
+        let window = ActiveWindow {
+            timezone: String::from("Mars/Olympus"),
+            ..Default::default()
+        };
+        assert!(window.validate().is_err());
+    }
+}
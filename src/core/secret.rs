@@ -0,0 +1,342 @@
+use crate::error::types::{Error, EvalError, PersistanceError, Result};
+use crate::models::secret::SecretModel;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{self, Debug};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+
+/// Placeholder used instead of secret values in logs and responses.
+pub const REDACTED: &str = "***";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+/// Sensitive value, like a password, used by task inputs and hooks.
+/// Can be given in plain text ("pass") or as a reference to stored secret ({"secret": "psql_password"}).
+/// Secret references have to be resolved with Db::resolve_credential before being used.
+pub enum Credential {
+    Plain(String),
+    Secret {
+        secret: String,
+        #[serde(skip)]
+        resolved: Option<String>,
+    },
+}
+
+impl Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Credential::Plain(_) => write!(f, "Plain({REDACTED})"),
+            Credential::Secret { secret, .. } => write!(f, "Secret({secret})"),
+        }
+    }
+}
+
+impl From<&str> for Credential {
+    fn from(value: &str) -> Self {
+        Credential::Plain(value.to_string())
+    }
+}
+
+impl Credential {
+    /// Creates reference to stored secret.
+    pub fn secret<S: Into<String>>(name: S) -> Self {
+        Credential::Secret {
+            secret: name.into(),
+            resolved: None,
+        }
+    }
+
+    /// Returns value of a credential, fails if secret reference was not resolved.
+    pub fn expose(&self) -> Result<&str> {
+        match self {
+            Credential::Plain(value) => Ok(value),
+            Credential::Secret {
+                resolved: Some(value),
+                ..
+            } => Ok(value),
+            Credential::Secret { secret, .. } => Err(Error::new_internal(
+                String::from("Credential::expose"),
+                format!("secret {secret} was not resolved"),
+                String::default(),
+            )),
+        }
+    }
+
+    /// Checks if credential references stored secret, plain value would be persisted with the task as it is.
+    pub fn ensure_secret(&self) -> Result<()> {
+        match self {
+            Credential::Plain(_) => Err(Error::new_validation(
+                "Credential",
+                "Plain passwords are not stored, reference a stored secret with {\"secret\": name}",
+                "password",
+            )),
+            Credential::Secret { .. } => Ok(()),
+        }
+    }
+
+    /// Returns copy of credential that is safe to be shown, plain values are replaced.
+    pub fn redacted(&self) -> Self {
+        match self {
+            Credential::Plain(_) => Credential::Plain(REDACTED.to_string()),
+            Credential::Secret { secret, .. } => Credential::secret(secret.clone()),
+        }
+    }
+}
+
+/// Key used for encryption of secrets at rest. Kept locally, never stored in persistance.
+pub struct MasterKey {
+    cipher: Aes256Gcm,
+}
+
+impl Debug for MasterKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MasterKey({REDACTED})")
+    }
+}
+
+impl MasterKey {
+    /// Creates MasterKey from 32 raw bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 32 {
+            return Err(Error::new_validation(
+                "MasterKey",
+                "Master key must be 32 bytes long",
+                "key",
+            ));
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(bytes)),
+        })
+    }
+
+    /// Reads base64 encoded key from file, generates and saves new one if file does not exist.
+    /// Key file is readable only by its owner, file accessible by group or others is rejected.
+    pub fn load_or_create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            info!("generating new master key in {}", path.display());
+            let key = Aes256Gcm::generate_key(&mut OsRng);
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            options
+                .open(path)?
+                .write_all(base64::encode(key).as_bytes())?;
+        }
+
+        #[cfg(unix)]
+        if fs::metadata(path)?.permissions().mode() & 0o077 != 0 {
+            return Err(Error::new_validation(
+                "MasterKey",
+                format!(
+                    "{} can be accessed by group or others, its mode has to be 600",
+                    path.display()
+                )
+                .as_str(),
+                "path",
+            ));
+        }
+
+        let encoded = fs::read_to_string(path)?;
+        let bytes = base64::decode(encoded.trim()).map_err(|err| {
+            Error::new_internal(
+                String::from("MasterKey::load_or_create"),
+                String::from("failed to decode master key"),
+                err.to_string(),
+            )
+        })?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Encrypts value of a secret with random nonce.
+    pub fn encrypt(&self, name: &str, value: &str) -> Result<SecretModel> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, value.as_bytes())
+            .map_err(|err| {
+                Error::new_internal(
+                    String::from("MasterKey::encrypt"),
+                    format!("failed to encrypt {name} secret"),
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(SecretModel {
+            name: name.to_string(),
+            nonce: base64::encode(nonce),
+            ciphertext: base64::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts stored secret, fails if it was encrypted with other key.
+    pub fn decrypt(&self, model: &SecretModel) -> Result<String> {
+        let decrypt_err = |err: String| {
+            Error::new_internal(
+                String::from("MasterKey::decrypt"),
+                format!("failed to decrypt {} secret", model.name),
+                err,
+            )
+        };
+
+        let nonce = base64::decode(&model.nonce).map_err(|err| decrypt_err(err.to_string()))?;
+        let ciphertext =
+            base64::decode(&model.ciphertext).map_err(|err| decrypt_err(err.to_string()))?;
+        if nonce.len() != 12 {
+            return Err(decrypt_err(String::from("invalid nonce")));
+        }
+
+        let value = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|err| decrypt_err(err.to_string()))?;
+        String::from_utf8(value).map_err(|err| decrypt_err(err.to_string()))
+    }
+}
+
+/// Replaces every occurrence of secret values in text, used before anything is logged.
+pub fn redact<'a>(text: &str, secrets: impl IntoIterator<Item = &'a String>) -> String {
+    secrets
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .fold(text.to_string(), |text, secret| {
+            text.replace(secret.as_str(), REDACTED)
+        })
+}
+
+/// Redacts every message of an error, used before errors are logged or saved in reports and dead letters.
+pub fn redact_error(err: Error, secrets: &[String]) -> Error {
+    let clean = |text: String| redact(&text, secrets);
+    match err {
+        Error::Eval(EvalError::InvalidType {
+            operation,
+            t,
+            wanted,
+        }) => Error::new_eval_invalid_type(clean(operation), clean(t), clean(wanted)),
+        Error::Eval(EvalError::Internal { operation, msg }) => {
+            Error::new_eval_internal(clean(operation), clean(msg))
+        }
+        Error::Persistance(PersistanceError::Internal { msg, err }) => {
+            Error::new_persistance_internal(clean(msg), clean(err))
+        }
+        Error::Persistance(PersistanceError::Parsing { msg, err, field }) => {
+            Error::new_persistance_parsing(clean(msg), clean(err), clean(field))
+        }
+        Error::Internal { place, msg, err } => {
+            Error::new_internal(clean(place), clean(msg), clean(err))
+        }
+        Error::Validation { entity, msg, field } => {
+            Error::new_validation(clean(entity), clean(msg), clean(field))
+        }
+    }
+}
+
+/// Redacts every string of json value, including keys of objects, used before values are returned in responses.
+pub fn redact_value(value: Value, secrets: &[String]) -> Value {
+    match value {
+        Value::String(text) => Value::String(redact(&text, secrets)),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| redact_value(item, secrets))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (redact(&k, secrets), redact_value(v, secrets)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact, redact_error, redact_value, Credential, MasterKey};
+    use crate::error::types::Error;
+    use serde_json::json;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let key = MasterKey::from_bytes(&[7; 32]).unwrap();
+        let model = key.encrypt("psql_password", "pass").unwrap();
+        assert!(!model.ciphertext.contains("pass"));
+        assert_eq!(key.decrypt(&model).unwrap(), "pass");
+
+        let other_key = MasterKey::from_bytes(&[8; 32]).unwrap();
+        assert!(other_key.decrypt(&model).is_err());
+        assert!(MasterKey::from_bytes(&[7; 16]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_or_create() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = "test_master.key";
+        let _ = fs::remove_file(path);
+        let key = MasterKey::load_or_create(path).unwrap();
+        assert_eq!(
+            fs::metadata(path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        let model = key.encrypt("psql_password", "pass").unwrap();
+        let loaded = MasterKey::load_or_create(path).unwrap();
+        assert_eq!(loaded.decrypt(&model).unwrap(), "pass");
+
+        fs::set_permissions(path, fs::Permissions::from_mode(0o644)).unwrap();
+        let loaded = MasterKey::load_or_create(path);
+        fs::remove_file(path).unwrap();
+        assert!(loaded.is_err());
+    }
+
+    #[test]
+    fn test_credential() {
+        let plain: Credential = serde_json::from_str(r#""pass""#).unwrap();
+        assert_eq!(plain, Credential::from("pass"));
+        assert_eq!(plain.expose().unwrap(), "pass");
+        assert_eq!(format!("{:?}", plain), "Plain(***)");
+
+        let secret: Credential = serde_json::from_str(r#"{"secret": "psql_password"}"#).unwrap();
+        assert_eq!(secret, Credential::secret("psql_password"));
+        assert!(secret.expose().is_err());
+        assert_eq!(
+            serde_json::to_string(&secret).unwrap(),
+            r#"{"secret":"psql_password"}"#
+        );
+    }
+
+    #[test]
+    fn test_redact() {
+        let secrets = vec![String::from("s3cr3t"), String::default()];
+        assert_eq!(
+            redact("String(\"token s3cr3t\")", &secrets),
+            "String(\"token ***\")"
+        );
+        assert_eq!(
+            redact_value(json!({"token": ["s3cr3t", 1], "s3cr3t": null}), &secrets),
+            json!({"token": ["***", 1], "***": null})
+        );
+        assert_eq!(
+            redact_error(
+                Error::new_eval_internal("GET", "invalid url https://s3cr3t@host"),
+                &secrets
+            ),
+            Error::new_eval_internal("GET", "invalid url https://***@host")
+        );
+    }
+
+    #[test]
+    fn test_ensure_secret() {
+        assert!(Credential::from("pass").ensure_secret().is_err());
+        assert!(Credential::secret("psql_password").ensure_secret().is_ok());
+    }
+}
@@ -1,17 +1,32 @@
+use super::alert::AlertConfig;
+use super::buffer::{BatchConfig, BufferConfig};
 use super::channels::ChannelsManager;
+use super::dedup::DedupConfig;
+use super::range::A1Range;
+use super::retry::RetryPolicy;
+use super::schedule::ActiveWindow;
+use super::schedule::CronSchedule;
+use super::secret::Credential;
 use super::types::*;
+use super::write_mode::WriteMode;
 use crate::connector::factory::getter_from_task_input;
 use crate::connector::kafka::{consume_topic, KafkaConfig};
 use crate::connector::psql::{monitor_changes, PSQLConfig};
 use crate::error::types::{Error, Result};
 use crate::lang::process::Process;
+use crate::lang::registry::Registry;
 use crate::models::task::TaskModel;
-use crate::server::task::{TaskCreateRequest, TaskKindRequest};
+use crate::persistance::interface::Db;
+use crate::server::task::{TaskCreateRequest, TaskKindRequest, TaskUpdateRequest};
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::future::Future;
 use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use std::vec::Vec;
@@ -34,7 +49,7 @@ pub enum TaskInput {
         host: String,
         port: u16,
         user: String,
-        password: String,
+        password: Credential,
         query: String,
         db: String,
     },
@@ -54,6 +69,23 @@ impl TaskInput {
     pub fn to_json(&self) -> String {
         serde_json::json!(self).to_string()
     }
+
+    /// Validates input, passwords have to reference stored secrets.
+    pub fn validate(&self) -> Result<()> {
+        if let TaskInput::PSQL { password, .. } = self {
+            password.ensure_secret()?;
+        }
+        Ok(())
+    }
+
+    /// Returns copy of TaskInput that is safe to be shown, credentials are redacted.
+    pub fn redacted(&self) -> Self {
+        let mut input = self.clone();
+        if let TaskInput::PSQL { password, .. } = &mut input {
+            *password = password.redacted();
+        }
+        input
+    }
 }
 
 impl Default for TaskInput {
@@ -63,7 +95,7 @@ impl Default for TaskInput {
 }
 
 /// Enum for user's input data.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum InputData {
     String(String),
     Json(Value),
@@ -119,8 +151,19 @@ pub struct TrackingTask {
     pub direction: Direction,
     pub with_timestamp: bool, // whether to write timestamp.
     pub timestamp_position: TimestampPosition,
-    pub invocations: Option<i32>, // number of invocations.
-    pub process: Process,         // definition of handling data.
+    pub timestamp_format: String,       // strftime format of timestamp.
+    pub timestamp_timezone: String,     // timezone of timestamp.
+    pub invocations: Option<i32>,       // number of invocations left, None if unlimited.
+    pub ends_at: Option<DateTime<Utc>>, // task is finished after that date.
+    pub retry_policy: Option<RetryPolicy>, // how failed phases are retried, no retries if None.
+    pub buffer: Option<BufferConfig>, // size and overflow policy of input buffer, default if None.
+    pub dropped_inputs: i64,          // number of inputs dropped on buffer overflow.
+    pub batch: Option<BatchConfig>,   // inputs are written one by one if None.
+    pub active_window: Option<ActiveWindow>, // period in which task handles inputs, always if None.
+    pub write_mode: WriteMode,        // where data of every run is written.
+    pub dedup: Option<DedupConfig>,   // writes of unchanged output are skipped, never if None.
+    pub alerts: Option<AlertConfig>,  // rules checked on every output, no alerting if None.
+    pub process: Process,             // definition of handling data.
     pub status: State,
     pub input: Option<TaskInput>,
 
@@ -134,6 +177,7 @@ pub struct TrackingTask {
     #[derivative(PartialEq = "ignore")]
     pub kind: Option<TaskKind>, // None if not initialized.
     pub kind_request: TaskKindRequest, // request from API, needed for persistance.
+    pub params: HashMap<String, Value>, // values of params declared by process.
 }
 
 impl TrackingTask {
@@ -165,22 +209,35 @@ impl TrackingTask {
             callbacks: None,
             with_timestamp: false,
             timestamp_position: TimestampPosition::None,
+            timestamp_format: String::from(DEFAULT_TIMESTAMP_FORMAT),
+            timestamp_timezone: String::from(DEFAULT_TIMESTAMP_TIMEZONE),
             invocations: None,
+            ends_at: None,
+            retry_policy: None,
+            buffer: None,
+            dropped_inputs: 0,
+            batch: None,
+            active_window: None,
+            write_mode: WriteMode::default(),
+            dedup: None,
+            alerts: None,
             process: Process::default(),
             status: State::Created,
             input: None,
             kind: None,
             kind_request,
+            params: HashMap::new(),
         }
     }
 
     /// Sets TaskKind for TrackingTask, create channels and spawns needed tokio::task for needed types.
     /// Method returns Option<JoinHandle> in order to allow graceful shutdown in scope where this method was called.
+    /// Fails if persisted cron expression of the task can't be parsed.
     pub async fn init_channels(
         &mut self,
         channels_manager: &ChannelsManager,
         mut shutdown: broadcast::Receiver<()>,
-    ) -> Option<JoinHandle<()>> {
+    ) -> Result<Option<JoinHandle<()>>> {
         let mut join = None;
         self.kind = Some(match self.kind_request.clone() {
             TaskKindRequest::Triggered(hook) => match hook {
@@ -189,6 +246,16 @@ impl TrackingTask {
                     channels_manager.add_triggered(self.id, sender).await;
                     TaskKind::Triggered {
                         ch: Arc::new(Mutex::new(receiver)),
+                        ack: None,
+                    }
+                }
+                Hook::Task { upstream_id } => {
+                    let (sender, receiver) = create_channel(1);
+                    channels_manager.add_triggered(self.id, sender).await;
+                    channels_manager.add_downstream(upstream_id, self.id).await;
+                    TaskKind::Triggered {
+                        ch: Arc::new(Mutex::new(receiver)),
+                        ack: None,
                     }
                 }
                 Hook::PSQL {
@@ -210,6 +277,7 @@ impl TrackingTask {
                     }));
                     TaskKind::Triggered {
                         ch: Arc::new(Mutex::new(receiver)),
+                        ack: None,
                     }
                 }
                 Hook::Kafka {
@@ -218,6 +286,7 @@ impl TrackingTask {
                     brokers,
                 } => {
                     let (sender, receiver) = create_channel(1);
+                    let (ack, acks) = create_channel(1);
                     join = Some(tokio::task::spawn(async move {
                         consume_topic(
                             KafkaConfig {
@@ -226,12 +295,14 @@ impl TrackingTask {
                                 brokers,
                             },
                             sender,
+                            acks,
                             &mut shutdown,
                         )
                         .await;
                     }));
                     TaskKind::Triggered {
                         ch: Arc::new(Mutex::new(receiver)),
+                        ack: Some(ack),
                     }
                 }
             },
@@ -245,8 +316,28 @@ impl TrackingTask {
             TaskKindRequest::Ticker { interval_secs } => TaskKind::Ticker {
                 interval: Duration::from_secs(interval_secs),
             },
+            TaskKindRequest::Cron {
+                expression,
+                timezone,
+            } => TaskKind::Cron {
+                schedule: Arc::new(CronSchedule::new(&expression, &timezone)?),
+            },
         });
-        join
+        Ok(join)
+    }
+
+    /// Resolves secrets referenced by task's input and hook and recreates data function with them.
+    pub async fn resolve_secrets(&mut self, db: &Db) -> Result<()> {
+        if let Some(input) = &mut self.input {
+            if let TaskInput::PSQL { password, .. } = input {
+                db.resolve_credential(password).await?;
+            }
+            self.data_fn = getter_from_task_input(input).map(Arc::new);
+        }
+        if let TaskKindRequest::Triggered(Hook::PSQL { password, .. }) = &mut self.kind_request {
+            db.resolve_credential(password).await?;
+        }
+        Ok(())
     }
 
     // sets task name.
@@ -285,12 +376,121 @@ impl TrackingTask {
         self
     }
 
+    // with_timestamp_format sets strftime format and timezone of timestamp.
+    pub fn with_timestamp_format(mut self, format: String, timezone: String) -> TrackingTask {
+        self.timestamp_format = format;
+        self.timestamp_timezone = timezone;
+        self
+    }
+
+    /// Returns timestamp of given time formatted for the task, None if task doesn't write timestamps.
+    pub fn timestamp(&self, time: DateTime<Utc>) -> Option<String> {
+        if !self.with_timestamp || self.timestamp_position == TimestampPosition::None {
+            return None;
+        }
+        let timezone = Tz::from_str(&self.timestamp_timezone).unwrap_or(Tz::UTC);
+        Some(
+            time.with_timezone(&timezone)
+                .format(&self.timestamp_format)
+                .to_string(),
+        )
+    }
+
     // with_invocations sets number of invocations.
     pub fn with_invocations(mut self, invocations: i32) -> TrackingTask {
         self.invocations = Some(invocations);
         self
     }
 
+    /// Sets date after which task won't be run anymore.
+    pub fn with_ends_at(mut self, ends_at: DateTime<Utc>) -> TrackingTask {
+        self.ends_at = Some(ends_at);
+        self
+    }
+
+    /// Sets policy of retrying failed phases.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> TrackingTask {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Sets size and overflow policy of input buffer.
+    pub fn with_buffer(mut self, buffer: BufferConfig) -> TrackingTask {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /// Enables micro-batching of inputs.
+    pub fn with_batch(mut self, batch: BatchConfig) -> TrackingTask {
+        self.batch = Some(batch);
+        self
+    }
+
+    /// Limits handling of inputs to given window.
+    pub fn with_active_window(mut self, window: ActiveWindow) -> TrackingTask {
+        self.active_window = Some(window);
+        self
+    }
+
+    /// Sets where data of every run is written.
+    pub fn with_write_mode(mut self, write_mode: WriteMode) -> TrackingTask {
+        self.write_mode = write_mode;
+        self
+    }
+
+    /// Enables skipping writes of output that did not change.
+    pub fn with_dedup(mut self, dedup: DedupConfig) -> TrackingTask {
+        self.dedup = Some(dedup);
+        self
+    }
+
+    /// Enables alerting on task's output.
+    pub fn with_alerts(mut self, alerts: AlertConfig) -> TrackingTask {
+        self.alerts = Some(alerts);
+        self
+    }
+
+    /// Returns starting range of the task. Sheet of the task takes precedence over sheet given in starting position.
+    pub fn starting_range(&self) -> Result<A1Range> {
+        let mut start = A1Range::from_str(&self.starting_position)?;
+        if !self.sheet.is_empty() {
+            start.sheet = Some(self.sheet.clone());
+        }
+        Ok(start)
+    }
+
+    /// Returns A1 range of a write of lines with width cells each, placed after last_place previously written lines.
+    pub fn write_range(&self, last_place: u32, lines: u32, width: u32) -> Result<String> {
+        let start = self.starting_range()?;
+        let range = A1Range::for_write(
+            start.start,
+            start.sheet,
+            self.direction,
+            last_place,
+            lines,
+            width,
+        )?;
+        Ok(range.to_string())
+    }
+
+    /// Fails if task is not in one of allowed states, e.g. finished task has no handler to receive commands.
+    pub fn ensure_status(&self, allowed: &[State]) -> Result<()> {
+        if allowed.contains(&self.status) {
+            return Ok(());
+        }
+        Err(Error::new_validation(
+            "TrackingTask",
+            format!("{} task is {}", self.info(), self.status).as_str(),
+            "status",
+        ))
+    }
+
+    /// Checks if task reached its invocations limit or end date.
+    pub fn is_exhausted(&self, now: DateTime<Utc>) -> bool {
+        self.invocations.filter(|i| *i <= 0).is_some()
+            || self.ends_at.filter(|ends_at| *ends_at <= now).is_some()
+    }
+
     /// Sets process field.
     pub fn with_process(mut self, process: Process) -> Self {
         self.process = process;
@@ -305,6 +505,12 @@ impl TrackingTask {
         self
     }
 
+    /// Sets values of process params, they are validated with Process::resolve_params.
+    pub fn with_params(mut self, params: HashMap<String, Value>) -> Self {
+        self.params = params;
+        self
+    }
+
     pub fn with_kind(mut self, kind: TaskKind) -> TrackingTask {
         self.kind = Some(kind);
         self
@@ -340,7 +546,89 @@ impl TrackingTask {
         }
     }
 
-    pub fn from_task_create_request(tcr: TaskCreateRequest) -> Result<Self> {
+    /// Applies changes of the task. Changes are validated first, so task is left untouched if any of them is invalid.
+    pub fn apply_update(&mut self, update: TaskUpdateRequest) -> Result<()> {
+        if let Some(process) = &update.process {
+            process.resolve_params(&self.params)?;
+            process.validate_parallel()?;
+        }
+        if let Some(kind_request) = &update.kind_request {
+            kind_request.validate()?;
+        }
+        if update.spreadsheet_id.as_deref() == Some("") {
+            return Err(Error::new_validation(
+                "TaskUpdateRequest",
+                "spreadsheet_id cannot be empty",
+                "spreadsheet_id",
+            ));
+        }
+
+        if let Some(name) = update.name {
+            self.name = Some(name);
+        }
+        if let Some(process) = update.process {
+            self.process = process;
+        }
+        if let Some(kind_request) = update.kind_request {
+            self.kind_request = kind_request;
+        }
+        if let Some(spreadsheet_id) = update.spreadsheet_id {
+            self.spreadsheet_id = spreadsheet_id;
+        }
+        if let Some(sheet) = update.sheet {
+            self.sheet = sheet;
+        }
+        Ok(())
+    }
+
+    /// Validates request and creates task from it, alert rules are parsed with functions of given Registry.
+    pub fn from_task_create_request(tcr: TaskCreateRequest, registry: &Registry) -> Result<Self> {
+        // fail fast on missing, undeclared or mistyped params.
+        tcr.process.resolve_params(&tcr.params)?;
+        tcr.process.validate_parallel()?;
+        tcr.kind_request.validate()?;
+        if let Some(input) = &tcr.input {
+            input.validate()?;
+        }
+        if let Some(window) = &tcr.active_window {
+            window.validate()?;
+        }
+        if let Some(timestamp) = &tcr.timestamp {
+            timestamp.validate()?;
+        }
+        if let Some(alerts) = &tcr.alerts {
+            alerts.validate(registry)?;
+        }
+        if let Some(dedup) = &tcr.dedup {
+            dedup.validate()?;
+            if tcr.batch.is_some() {
+                return Err(Error::new_validation(
+                    "TaskCreateRequest",
+                    "dedup cannot be used with batch",
+                    "dedup",
+                ));
+            }
+        }
+        A1Range::from_str(&tcr.starting_position)?;
+        if tcr.invocations.filter(|i| *i <= 0).is_some() {
+            return Err(Error::new_validation(
+                "TaskCreateRequest",
+                "invocations has to be positive",
+                "invocations",
+            ));
+        }
+        if tcr
+            .ends_at
+            .filter(|ends_at| *ends_at <= Utc::now())
+            .is_some()
+        {
+            return Err(Error::new_validation(
+                "TaskCreateRequest",
+                "ends_at cannot be in the past",
+                "ends_at",
+            ));
+        }
+
         Ok(TrackingTask {
             id: Uuid::new_v4(),
             name: Some(tcr.name),
@@ -350,9 +638,29 @@ impl TrackingTask {
             starting_position: tcr.starting_position,
             direction: tcr.direction,
             callbacks: None,
-            with_timestamp: false,
-            timestamp_position: TimestampPosition::None,
-            invocations: None,
+            with_timestamp: tcr.timestamp.is_some(),
+            timestamp_position: tcr
+                .timestamp
+                .as_ref()
+                .map_or(TimestampPosition::None, |timestamp| timestamp.position),
+            timestamp_format: tcr.timestamp.as_ref().map_or_else(
+                || String::from(DEFAULT_TIMESTAMP_FORMAT),
+                |timestamp| timestamp.format.clone(),
+            ),
+            timestamp_timezone: tcr.timestamp.as_ref().map_or_else(
+                || String::from(DEFAULT_TIMESTAMP_TIMEZONE),
+                |timestamp| timestamp.timezone.clone(),
+            ),
+            invocations: tcr.invocations,
+            ends_at: tcr.ends_at,
+            retry_policy: tcr.retry_policy,
+            buffer: tcr.buffer,
+            dropped_inputs: 0,
+            batch: tcr.batch,
+            active_window: tcr.active_window,
+            write_mode: tcr.write_mode,
+            dedup: tcr.dedup,
+            alerts: tcr.alerts,
             process: tcr.process,
             data_fn: tcr
                 .input
@@ -362,6 +670,7 @@ impl TrackingTask {
             input: tcr.input,
             kind: None,
             kind_request: tcr.kind_request,
+            params: tcr.params,
         })
     }
 }
@@ -383,6 +692,78 @@ impl TryFrom<TaskModel> for TrackingTask {
             .as_ref()
             .map(|i| TaskInput::from_json(i).unwrap());
         let kind_request = TaskKindRequest::from_json(&task_model.kind)?;
+        let params = match &task_model.params {
+            Some(params) => serde_json::from_str(params).map_err(|err| {
+                Error::new_internal(
+                    String::from("from_task_model"),
+                    String::from("failed to deserialize params"),
+                    err.to_string(),
+                )
+            })?,
+            None => HashMap::new(),
+        };
+        let retry_policy = match &task_model.retry_policy {
+            Some(retry_policy) => Some(serde_json::from_str(retry_policy).map_err(|err| {
+                Error::new_internal(
+                    String::from("from_task_model"),
+                    String::from("failed to deserialize retry policy"),
+                    err.to_string(),
+                )
+            })?),
+            None => None,
+        };
+        let buffer = match &task_model.buffer {
+            Some(buffer) => Some(serde_json::from_str(buffer).map_err(|err| {
+                Error::new_internal(
+                    String::from("from_task_model"),
+                    String::from("failed to deserialize buffer config"),
+                    err.to_string(),
+                )
+            })?),
+            None => None,
+        };
+        let batch = match &task_model.batch {
+            Some(batch) => Some(serde_json::from_str(batch).map_err(|err| {
+                Error::new_internal(
+                    String::from("from_task_model"),
+                    String::from("failed to deserialize batch config"),
+                    err.to_string(),
+                )
+            })?),
+            None => None,
+        };
+        let active_window = match &task_model.active_window {
+            Some(window) => Some(serde_json::from_str(window).map_err(|err| {
+                Error::new_internal(
+                    String::from("from_task_model"),
+                    String::from("failed to deserialize active window"),
+                    err.to_string(),
+                )
+            })?),
+            None => None,
+        };
+
+        let write_mode = WriteMode::from_json(&task_model.write_mode)?;
+        let dedup = match &task_model.dedup {
+            Some(dedup) => Some(serde_json::from_str(dedup).map_err(|err| {
+                Error::new_internal(
+                    String::from("from_task_model"),
+                    String::from("failed to deserialize dedup config"),
+                    err.to_string(),
+                )
+            })?),
+            None => None,
+        };
+        let alerts = match &task_model.alerts {
+            Some(alerts) => Some(serde_json::from_str(alerts).map_err(|err| {
+                Error::new_internal(
+                    String::from("from_task_model"),
+                    String::from("failed to deserialize alert config"),
+                    err.to_string(),
+                )
+            })?),
+            None => None,
+        };
 
         Ok(TrackingTask {
             id,
@@ -395,21 +776,36 @@ impl TryFrom<TaskModel> for TrackingTask {
             starting_position: task_model.position,
             sheet: task_model.sheet,
             direction: task_model.direction,
-            with_timestamp: true,
+            with_timestamp: task_model.with_timestamp,
             timestamp_position: task_model.timestamp_position,
-            invocations: None,
+            timestamp_format: task_model.timestamp_format,
+            timestamp_timezone: task_model.timestamp_timezone,
+            invocations: task_model.invocations,
+            ends_at: task_model
+                .ends_at
+                .map(|ends_at| Utc.from_utc_datetime(&ends_at)),
+            retry_policy,
+            buffer,
+            dropped_inputs: task_model.dropped_inputs,
+            batch,
+            active_window,
+            write_mode,
+            dedup,
+            alerts,
             process: Process::try_from(task_model.process)?,
             status: task_model.status,
             callbacks: None,
             input,
             kind: None,
             kind_request,
+            params,
         })
     }
 }
 
 mod test {
     #![allow(unused_imports)]
+    use crate::core::secret::Credential;
     use crate::core::task::{Direction, InputData, TaskInput, TrackingTask};
     use crate::error::types::Result;
     use crate::server::task::TaskKindRequest;
@@ -470,7 +866,7 @@ mod test {
             host: String::from("host"),
             port: 5432,
             user: String::from("user"),
-            password: String::from("pass"),
+            password: Credential::from("pass"),
             query: String::from("SELECT 1"),
             db: String::from("test"),
         };
@@ -1,9 +1,11 @@
 use super::channels::ChannelsManager;
 use super::handler::TaskHandler;
+use super::inbox::InboxEntry;
 use super::manager::{SenderManager, TaskCommand};
 use super::task::TrackingTask;
 use super::types::State;
-use crate::error::types::Result;
+use crate::error::types::{LogExt, Result};
+use crate::lang::registry::Registry;
 use crate::persistance::interface::Db;
 use crate::shutdown::Shutdown;
 use crate::wrap::API;
@@ -45,6 +47,9 @@ where
     /// Broadcasts a shutdown signal to all active task handlers..
     notify_shutdown: broadcast::Sender<()>,
     manager: SenderManager,
+
+    /// Native functions available in every handled task's process.
+    registry: Registry,
 }
 
 impl<A> Tracker<A>
@@ -70,9 +75,16 @@ where
             shutdown: Shutdown::new(notify_shutdown.clone(), shutdown_channel),
             notify_shutdown,
             manager: SenderManager::default(),
+            registry: Registry::default(),
         }
     }
 
+    /// Sets Registry of native functions that will be passed to every TaskHandler.
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = registry;
+        self
+    }
+
     pub async fn start(&mut self) {
         info!("Starting Tracker.");
         let mut spawned: HashMap<Uuid, JoinHandle<()>> = HashMap::default();
@@ -89,10 +101,10 @@ where
                     break
                 }
                 Some(task) = self.task_channel.recv() => {
-                    spawned.insert(task.id, self.start_handler_for_task(&task).await);
+                    spawned.insert(task.id, self.start_handler_for_task(&task, Vec::new()).await);
                 }
                 Some(task_cmd) = self.task_command_channel.recv() => {
-                    self.manager.apply(task_cmd.id, task_cmd.cmd).await;
+                    let _ = self.manager.apply(task_cmd.id, task_cmd.cmd).await.log();
                 }
             }
         }
@@ -105,7 +117,12 @@ where
     }
 
     /// Creates new TaskHandler for given task and pushes it to vector of handlers.
-    async fn start_handler_for_task(&mut self, task: &TrackingTask) -> JoinHandle<()> {
+    /// Inputs from task's inbox are handled first.
+    async fn start_handler_for_task(
+        &mut self,
+        task: &TrackingTask,
+        inbox: Vec<InboxEntry>,
+    ) -> JoinHandle<()> {
         info!(
             "start_handler_for_task - for {}:{} task",
             task.id, task.status
@@ -120,7 +137,9 @@ where
             self.api.clone(),
             self.manager.add_new_mapping(task.id),
             self.channels_manager.clone(),
-        );
+        )
+        .with_registry(self.registry.clone())
+        .with_inbox(inbox);
         tokio::task::spawn(async move { handler.start().await })
     }
 
@@ -133,7 +152,15 @@ where
         info!("{} tasks loaded from db", tasks.len());
 
         for tt in &tasks {
-            spawned.insert(tt.id, self.start_handler_for_task(tt).await);
+            // replay inputs that were not handled before shutdown or crash,
+            // task is started without them if its inbox can't be read.
+            let inbox = match &tt.buffer {
+                Some(buffer) if buffer.durable => {
+                    self.db.read_inbox(tt.id).await.log().unwrap_or_default()
+                }
+                _ => Vec::new(),
+            };
+            spawned.insert(tt.id, self.start_handler_for_task(tt, inbox).await);
         }
         Ok(())
     }
@@ -142,6 +169,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::core::channels::ChannelsManager;
+    use crate::core::grid::{Cell, Grid};
     use crate::core::task::*;
     use crate::core::tracker::Tracker;
     use crate::error::types::{Error, Result};
@@ -155,14 +183,14 @@ mod tests {
 
     #[derive(Clone)]
     struct TestAPI {
-        check: fn(Vec<Vec<String>>, &str, &str),
+        check: fn(Grid, &str, &str),
         fail: bool,
         fail_msg: &'static str,
     }
 
     #[async_trait]
     impl API for TestAPI {
-        async fn write(&self, v: Vec<Vec<String>>, s: &str, r: &str) -> Result<()> {
+        async fn write(&self, v: Grid, s: &str, r: &str) -> Result<()> {
             (self.check)(v, s, r);
             if self.fail {
                 return Err(Error::new_internal(
@@ -173,6 +201,10 @@ mod tests {
             }
             Ok(())
         }
+
+        async fn read(&self, _s: &str, _r: &str) -> Result<Vec<Vec<String>>> {
+            Ok(vec![])
+        }
     }
 
     use crate::core::tracker::TaskCommand;
@@ -192,10 +224,18 @@ mod tests {
         use tokio::sync::oneshot;
         let (tx, rx) = oneshot::channel::<bool>();
 
-        fn check_cases(v: Vec<Vec<String>>, s: &str, r: &str) {
+        fn check_cases(v: Grid, s: &str, r: &str) {
             let cases = vec![
-                (vec![vec!["test".to_string()]], "spreadsheet4", "A4:A5"),
-                (vec![vec!["test".to_string()]], "spreadsheet5", "A5:A6"),
+                (
+                    vec![vec![Cell::String("test".to_string())]],
+                    "spreadsheet4",
+                    "A4:A4",
+                ),
+                (
+                    vec![vec![Cell::String("test".to_string())]],
+                    "spreadsheet5",
+                    "A5:A5",
+                ),
             ];
             info!("{:?} {} {}", cases, s, r);
             for (i, c) in cases.iter().enumerate() {
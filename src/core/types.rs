@@ -1,5 +1,10 @@
 use super::manager::Command;
+use super::schedule::CronSchedule;
+use super::secret::Credential;
 use super::task::InputData;
+use crate::error::types::{Error, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono_tz::Tz;
 use diesel::backend::Backend;
 use diesel::deserialize;
 use diesel::serialize::{self, Output};
@@ -8,10 +13,12 @@ use diesel::types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 use std::io;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc::Receiver;
+use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::Mutex;
+use uuid::Uuid;
 
 /// Supported types for task's input data.
 /// Should match with InputData.
@@ -71,14 +78,20 @@ pub enum State {
     Running,
     Stopped,
     Quit,
+    Finished, // task reached its invocations limit or end date.
 }
 
 impl State {
-    pub fn from_cmd(cmd: Command) -> Self {
+    /// Returns State that task should be in after Command, None if Command does not change State.
+    pub fn from_cmd(cmd: &Command) -> Option<Self> {
         match cmd {
-            Command::Resume => State::Running,
-            Command::Stop => State::Stopped,
-            Command::Delete => State::Quit,
+            Command::Resume => Some(State::Running),
+            Command::Stop => Some(State::Stopped),
+            Command::Delete => Some(State::Quit),
+            Command::Replay(..)
+            | Command::Update(_)
+            | Command::Backfill(_)
+            | Command::CancelBackfill => None,
         }
     }
 }
@@ -90,6 +103,7 @@ impl Display for State {
             Self::Running => "running",
             Self::Stopped => "stopped",
             Self::Quit => "quit",
+            Self::Finished => "finished",
         };
         write!(f, "{:?}", v)
     }
@@ -114,6 +128,7 @@ where
             Self::Running => "running",
             Self::Stopped => "stopped",
             Self::Quit => "quit",
+            Self::Finished => "finished",
         });
         v.to_sql(out)
     }
@@ -130,6 +145,7 @@ where
             "running" => Self::Running,
             "stopped" => Self::Stopped,
             "quit" => Self::Quit,
+            "finished" => Self::Finished,
             _ => return Err("replace me with a real error".into()),
         })
     }
@@ -185,12 +201,63 @@ where
 }
 
 // TimestampPosition indicates position of timestamp in the data.
-#[derive(AsExpression, FromSqlRow, Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(AsExpression, FromSqlRow, Clone, Debug, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 #[sql_type = "Text"]
 pub enum TimestampPosition {
-    None, // timestamp will not be written.
-    Before,
-    After,
+    None,   // timestamp will not be written.
+    Before, // timestamp is the first cell of written row or column.
+    After,  // timestamp is the last cell of written row or column.
+}
+
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+pub const DEFAULT_TIMESTAMP_TIMEZONE: &str = "UTC";
+
+fn default_timestamp_format() -> String {
+    String::from(DEFAULT_TIMESTAMP_FORMAT)
+}
+
+fn default_timestamp_timezone() -> String {
+    String::from(DEFAULT_TIMESTAMP_TIMEZONE)
+}
+
+/// Describes timestamp written next to task's data. Format uses strftime syntax.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TimestampConfig {
+    pub position: TimestampPosition,
+
+    #[serde(default = "default_timestamp_format")]
+    pub format: String,
+
+    #[serde(default = "default_timestamp_timezone")]
+    pub timezone: String,
+}
+
+impl TimestampConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.position == TimestampPosition::None {
+            return Err(Error::new_validation(
+                "TimestampConfig",
+                "timestamp position cannot be none",
+                "position",
+            ));
+        }
+        if StrftimeItems::new(&self.format).any(|item| matches!(item, Item::Error)) {
+            return Err(Error::new_validation(
+                "TimestampConfig",
+                format!("invalid timestamp format {}", self.format).as_str(),
+                "format",
+            ));
+        }
+        Tz::from_str(&self.timezone).map_err(|err| {
+            Error::new_validation(
+                "TimestampConfig",
+                format!("invalid timezone {}: {}", self.timezone, err).as_str(),
+                "timezone",
+            )
+        })?;
+        Ok(())
+    }
 }
 
 impl Display for TimestampPosition {
@@ -239,9 +306,20 @@ where
 #[derive(Debug, Derivative, Clone)]
 /// Describes how TrackingTask is being run.
 pub enum TaskKind {
-    Ticker { interval: Duration },
-    Triggered { ch: Arc<Mutex<Receiver<InputData>>> },
-    Clicked { ch: Arc<Mutex<Receiver<()>>> }, // Clicked that will be triggered by e.g. api call or clicked button.
+    Ticker {
+        interval: Duration,
+    },
+    Triggered {
+        ch: Arc<Mutex<Receiver<InputData>>>,
+        /// Notified after every received input is pushed into task's buffer, if the source waits for it.
+        ack: Option<Sender<()>>,
+    },
+    Clicked {
+        ch: Arc<Mutex<Receiver<()>>>,
+    }, // Clicked that will be triggered by e.g. api call or clicked button.
+    Cron {
+        schedule: Arc<CronSchedule>,
+    }, // Ticker that fires at times given by cron expression.
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -251,7 +329,7 @@ pub enum Hook {
         host: String,
         port: u16,
         user: String,
-        password: String,
+        password: Credential,
         db: String,
         channel: String, // psql channel that will be listened.
     },
@@ -260,4 +338,7 @@ pub enum Hook {
         group_id: String,
         brokers: String,
     },
+    Task {
+        upstream_id: Uuid, // task which evaluated OUT is an input of this task.
+    },
 }
@@ -0,0 +1,102 @@
+use crate::error::types::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// Describes where data of every run is written.
+pub enum WriteMode {
+    Append,    // lines are written after previously written ones.
+    Overwrite, // every run writes at starting position, e.g. dashboards.
+    Upsert {
+        key: usize, // 0-based index of key cell in a written line, timestamp excluded.
+    }, // line with known key replaces written one, line with new key is appended.
+}
+
+impl Default for WriteMode {
+    fn default() -> Self {
+        Self::Append
+    }
+}
+
+impl WriteMode {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|err| {
+            Error::new_internal(
+                String::from("WriteMode::from_json"),
+                String::from("failed to deserialize write mode"),
+                err.to_string(),
+            )
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::json!(self).to_string()
+    }
+}
+
+/// Returns index of a line where every line of an upsert write goes. Lines are matched by keys with
+/// lines written before, last_place of them. Lines with new keys get indexes after last_place,
+/// repeated keys get the same index so the last line wins. Empty keys are never matched.
+pub fn plan_upsert(written: &[String], last_place: u32, keys: &[String]) -> Vec<u32> {
+    let mut indexes: HashMap<&str, u32> = written
+        .iter()
+        .take(last_place as usize)
+        .enumerate()
+        .filter(|(_, key)| !key.is_empty())
+        .map(|(i, key)| (key.as_str(), i as u32))
+        .collect();
+    let mut next = last_place;
+    keys.iter()
+        .map(|key| {
+            if let Some(index) = indexes.get(key.as_str()) {
+                return *index;
+            }
+            let index = next;
+            next += 1;
+            if !key.is_empty() {
+                indexes.insert(key.as_str(), index);
+            }
+            index
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan_upsert, WriteMode};
+
+    #[test]
+    fn test_write_mode_json() {
+        for (json, mode) in [
+            (r#""append""#, WriteMode::Append),
+            (r#""overwrite""#, WriteMode::Overwrite),
+            (r#"{"upsert":{"key":1}}"#, WriteMode::Upsert { key: 1 }),
+        ] {
+            assert_eq!(WriteMode::from_json(json).unwrap(), mode);
+            assert_eq!(mode.to_json(), json);
+        }
+    }
+
+    #[test]
+    fn test_plan_upsert() {
+        let written = vec![String::from("BTC"), String::default(), String::from("ETH")];
+        let keys = vec![
+            String::from("ETH"),
+            String::from("SOL"),
+            String::default(),
+            String::from("SOL"),
+        ];
+        assert_eq!(plan_upsert(&written, 3, &keys), vec![2, 3, 4, 3]);
+
+        // sheet can return less values than written lines, e.g. trailing empty cells.
+        assert_eq!(
+            plan_upsert(
+                &written[..1],
+                3,
+                &[String::from("BTC"), String::from("ETH")]
+            ),
+            vec![0, 3]
+        );
+    }
+}
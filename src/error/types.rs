@@ -114,9 +114,14 @@ impl From<std::io::Error> for Error {
 #[rocket::async_trait]
 impl<'r> Responder<'r, 'static> for Error {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        // validation errors are caused by request, e.g. invalid field or state of a task.
+        let status = match self {
+            Error::Validation { .. } => Status::BadRequest,
+            _ => Status::InternalServerError,
+        };
         let value = serde_json::json!(self);
         Response::build_from(value.respond_to(req).unwrap())
-            .status(Status::InternalServerError)
+            .status(status)
             .header(ContentType::JSON)
             .ok()
     }
@@ -2,15 +2,19 @@ use super::{
     eval::EvalForest,
     node::{EvalMetadata, SharedState},
     process::{MountOption, MountType, Process},
+    registry::{NativeFunction, Registry},
     variable::Variable,
 };
-use crate::error::types::Result;
+use crate::error::types::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs::File,
     io::{BufReader, Read},
     rc::Rc,
+    thread,
 };
 
 pub struct Engine {
@@ -22,27 +26,94 @@ pub struct Engine {
 
     // set of mounted readers.
     mounted: HashMap<String, Rc<RefCell<dyn Read>>>,
+
+    // native functions available in steps.
+    registry: Registry,
+
+    // values of secrets available with SECRET keyword.
+    secrets: HashMap<String, String>,
+
+    // values of task's params available with PARAM keyword.
+    params: HashMap<String, Variable>,
+
+    // whether every evaluated step is recorded in trace.
+    tracing: bool,
+
+    // steps evaluated by the last fire, empty unless tracing is enabled.
+    trace: Vec<TraceEntry>,
 }
 
-impl Engine {
-    pub fn default() -> Self {
-        Engine {
-            variables: HashMap::new(),
-            eval_forests: vec![],
-            mounted: HashMap::new(),
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Result of a single evaluated step, together with variables it changed.
+pub struct TraceEntry {
+    pub step: String,
+    pub result: Value,
+    pub changed: BTreeMap<String, Value>,
+}
+
+impl TraceEntry {
+    fn new(
+        step: String,
+        result: Variable,
+        before: &HashMap<String, Variable>,
+        after: &HashMap<String, Variable>,
+    ) -> Self {
+        let changed = after
+            .iter()
+            .filter(|(name, value)| before.get(*name) != Some(value))
+            .map(|(name, value)| (name.clone(), Value::from(value.clone())))
+            .collect();
+        Self {
+            step,
+            result: Value::from(result),
+            changed,
         }
     }
+}
 
-    /// Creates new instance of Engine with IN and OUT variables set.
-    ///
-    /// IN variable is a entry variable that is being set
-    /// at the very begging, it's a input-like for whole engine.
-    ///
-    /// OUT variable is a variable that will be a result as
-    /// a evaluation that will happen in engine. User should
-    /// write wanted data to OUT at last as this variable will
-    /// be taken out from Engine after all.
-    pub fn new(in_var: Variable, process: Process) -> Result<Self> {
+#[derive(Debug, Default)]
+/// Builds Engine with native functions registered.
+pub struct EngineBuilder {
+    registry: Registry,
+    secrets: HashMap<String, String>,
+    params: HashMap<String, Variable>,
+    tracing: bool,
+}
+
+impl EngineBuilder {
+    /// Registers NativeFunction that will be available in steps.
+    pub fn register<F: NativeFunction + 'static>(mut self, function: F) -> Result<Self> {
+        self.registry.register(function)?;
+        Ok(self)
+    }
+
+    /// Replaces whole Registry, handy when the same functions are shared by many Engines.
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Sets values of secrets that can be read with SECRET keyword.
+    pub fn with_secrets(mut self, secrets: HashMap<String, String>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Sets values of task's params that can be read with PARAM keyword, see Process::resolve_params.
+    pub fn with_params(mut self, params: HashMap<String, Variable>) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Records every evaluated step, see Engine::trace.
+    pub fn with_tracing(mut self) -> Self {
+        self.tracing = true;
+        self
+    }
+
+    /// Creates Engine, see Engine::new for more info.
+    pub fn build(self, in_var: Variable, process: Process) -> Result<Engine> {
+        process.validate_parallel()?;
         let mounted = mount_options(&process.mounts.unwrap_or_default())?;
 
         let mut variables = HashMap::new();
@@ -53,50 +124,203 @@ impl Engine {
         // parse Process into Vec of EvalForest.
 
         for def in process.definitions {
-            eval_forests.push(EvalForest::from(def));
+            eval_forests.push(EvalForest::new(def, &self.registry)?);
         }
 
-        Ok(Self {
+        Ok(Engine {
             variables,
             eval_forests,
             mounted,
+            registry: self.registry,
+            secrets: self.secrets,
+            params: self.params,
+            tracing: self.tracing,
+            trace: vec![],
         })
     }
+}
+
+impl Engine {
+    pub fn default() -> Self {
+        Engine {
+            variables: HashMap::new(),
+            eval_forests: vec![],
+            mounted: HashMap::new(),
+            registry: Registry::default(),
+            secrets: HashMap::new(),
+            params: HashMap::new(),
+            tracing: false,
+            trace: vec![],
+        }
+    }
+
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::default()
+    }
+
+    /// Creates new instance of Engine with IN and OUT variables set.
+    ///
+    /// IN variable is a entry variable that is being set
+    /// at the very begging, it's a input-like for whole engine.
+    ///
+    /// OUT variable is a variable that will be a result as
+    /// a evaluation that will happen in engine. User should
+    /// write wanted data to OUT at last as this variable will
+    /// be taken out from Engine after all.
+    pub fn new(in_var: Variable, process: Process) -> Result<Self> {
+        Self::builder().build(in_var, process)
+    }
 
     pub fn get(&self, key: &str) -> Option<&Variable> {
         self.variables.get(key)
     }
 
+    /// Sets variable before Engine is fired, e.g. to prepare state for a test case.
+    pub fn set<S: Into<String>>(&mut self, key: S, value: Variable) {
+        self.variables.insert(key.into(), value);
+    }
+
+    /// Returns steps evaluated by the last fire in order of definitions, empty if Engine
+    /// was not built with tracing. Implicit subtrees are not traced.
+    pub fn trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
     /// Takes set of eval forest and runs them one by one.
+    ///
+    /// Consecutive forests marked as parallel are run concurrently, each on its own
+    /// copy of variables. After all of them finish, their declared outputs are merged
+    /// into shared state in order of definitions.
     pub fn fire(&mut self) -> Result<()> {
         let mut shared_state = SharedState::new_with_mounted(
             self.variables.clone(),
             HashMap::new(),
             self.mounted.clone(),
         );
-
-        for ef in self.eval_forests.clone() {
-            for root in ef.clone() {
-                shared_state.subtress = ef.subtrees.clone();
-                root.start_evaluation(&mut shared_state)?;
+        shared_state.registry = self.registry.clone();
+        shared_state.secrets = self.secrets.clone();
+        shared_state.params = self.params.clone();
+        let mut trace = self.tracing.then(Vec::new);
+
+        let mut forests = self.eval_forests.clone().into_iter().peekable();
+        while let Some(ef) = forests.next() {
+            if !ef.parallel {
+                run_forest(ef, &mut shared_state, trace.as_mut())?;
+                continue;
             }
 
-            // for now we only support 1 level of nesting.
-            for (subtree_name, roots) in &ef.implicit_subtrees {
-                debug!("implicitly running {subtree_name} subtree");
-                for root in roots {
-                    root.start_evaluation(&mut shared_state)?;
-                }
+            let mut group = vec![ef];
+            while let Some(ef) = forests.next_if(|ef| ef.parallel) {
+                group.push(ef);
             }
+            run_parallel(group, &mut shared_state, trace.as_mut())?;
         }
 
         // rewrite variables from tree execution.
         self.variables = shared_state.variables;
+        self.trace = trace.unwrap_or_default();
 
         Ok(())
     }
 }
 
+/// Runs all roots of a forest and then its implicit subtrees, roots are recorded in trace if given.
+fn run_forest(
+    ef: EvalForest,
+    shared_state: &mut SharedState,
+    mut trace: Option<&mut Vec<TraceEntry>>,
+) -> Result<()> {
+    shared_state.subtress = ef.subtrees.clone();
+    for (i, root) in ef.roots.into_iter().enumerate() {
+        let trace = match trace.as_mut() {
+            Some(trace) => trace,
+            None => {
+                root.start_evaluation(shared_state)?;
+                continue;
+            }
+        };
+        let before = shared_state.variables.clone();
+        let result = root.start_evaluation(shared_state)?;
+        let step = ef.steps.get(i).cloned().unwrap_or_default();
+        trace.push(TraceEntry::new(
+            step,
+            result,
+            &before,
+            &shared_state.variables,
+        ));
+    }
+
+    // for now we only support 1 level of nesting.
+    for (subtree_name, roots) in ef.implicit_subtrees {
+        debug!("implicitly running {subtree_name} subtree");
+        for root in roots {
+            root.start_evaluation(shared_state)?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs group of forests concurrently on isolated variable scopes
+/// and merges their outputs back into shared state.
+fn run_parallel(
+    group: Vec<EvalForest>,
+    shared_state: &mut SharedState,
+    mut trace: Option<&mut Vec<TraceEntry>>,
+) -> Result<()> {
+    debug!("running {} definitions in parallel", group.len());
+    let tracing = trace.is_some();
+
+    let results = thread::scope(|s| {
+        let handles: Vec<_> = group
+            .into_iter()
+            .map(|ef| {
+                let variables = shared_state.variables.clone();
+                let registry = shared_state.registry.clone();
+                let secrets = shared_state.secrets.clone();
+                let params = shared_state.params.clone();
+                let outputs = ef.outputs.clone();
+                let handle = s.spawn(move || {
+                    let mut scope = SharedState::new(variables, HashMap::new());
+                    scope.registry = registry;
+                    scope.secrets = secrets;
+                    scope.params = params;
+                    let mut scope_trace = tracing.then(Vec::new);
+                    run_forest(ef, &mut scope, scope_trace.as_mut())
+                        .map(|_| (scope.variables, scope_trace.unwrap_or_default()))
+                });
+                (outputs, handle)
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|(outputs, handle)| {
+                let result = handle.join().unwrap_or_else(|_| {
+                    Err(Error::new_eval_internal(
+                        String::from("parallel"),
+                        String::from("parallel definition panicked"),
+                    ))
+                });
+                (outputs, result)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // merge in order of definitions, so the result does not depend on scheduling.
+    for (outputs, result) in results {
+        let (mut variables, scope_trace) = result?;
+        if let Some(trace) = trace.as_mut() {
+            trace.extend(scope_trace);
+        }
+        for output in outputs {
+            if let Some(value) = variables.remove(&output) {
+                shared_state.variables.insert(output, value);
+            }
+        }
+    }
+    Ok(())
+}
+
 fn mount_options(options: &[MountOption]) -> Result<HashMap<String, Rc<RefCell<dyn Read>>>> {
     let mut mounted: HashMap<String, Rc<RefCell<dyn Read>>> = HashMap::new();
 
@@ -118,9 +342,11 @@ fn mount_options(options: &[MountOption]) -> Result<HashMap<String, Rc<RefCell<d
 mod tests {
     use super::Engine;
     use crate::error::types::Result;
-    use crate::lang::process::Process;
+    use crate::lang::process::{Definition, Process};
     use crate::lang::variable::Variable;
     use anyhow::Context;
+    use serde_json::json;
+    use std::collections::HashMap;
     use std::fs::File;
     use std::io::BufReader;
     use std::path::Path;
@@ -198,4 +424,133 @@ mod tests {
 
         assert_eq!(engine.get("OUT").unwrap(), &Variable::Int(13));
     }
+
+    #[test]
+    fn test_process4_parallel() {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("src")
+            .join("lang")
+            .join("test_data")
+            .join("process4.json");
+
+        let process = process_file_to_struct(path).unwrap();
+        let mut engine = Engine::new(Variable::None, process).unwrap();
+        engine.fire().unwrap();
+
+        // OUT written in parallel definition is not declared as output, so it's not merged.
+        assert_eq!(engine.get("OUT").unwrap(), &Variable::Int(33));
+        assert_eq!(engine.get("left").unwrap(), &Variable::Int(11));
+        assert_eq!(engine.get("right").unwrap(), &Variable::Int(21));
+    }
+
+    #[test]
+    fn test_parallel_outputs_merge_order() {
+        let mut left = Definition::new(vec!["DEFINE(OUT, INT(1))"]);
+        left.parallel = true;
+        left.outputs = Some(vec![String::from("OUT")]);
+        let mut right = Definition::new(vec!["DEFINE(OUT, INT(2))"]);
+        right.parallel = true;
+        right.outputs = Some(vec![String::from("OUT")]);
+
+        let process = Process::new("parallel", vec![left, right], None);
+        let mut engine = Engine::new(Variable::None, process).unwrap();
+        engine.fire().unwrap();
+
+        // later definition wins regardless of which thread finished first.
+        assert_eq!(engine.get("OUT").unwrap(), &Variable::Int(2));
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut parallel = Definition::new(vec!["DEFINE(right, INT(2))"]);
+        parallel.parallel = true;
+        parallel.outputs = Some(vec![String::from("right")]);
+        let process = Process::new(
+            "trace",
+            vec![
+                Definition::new(vec!["DEFINE(left, INT(1))", "ADD(GET(left), INT(1))"]),
+                parallel,
+            ],
+            None,
+        );
+
+        let mut engine = Engine::new(Variable::None, process.clone()).unwrap();
+        engine.fire().unwrap();
+        assert!(engine.trace().is_empty());
+
+        let mut engine = Engine::builder()
+            .with_tracing()
+            .build(Variable::None, process)
+            .unwrap();
+        engine.fire().unwrap();
+        let trace = engine.trace();
+        assert_eq!(trace.len(), 3);
+        assert_eq!(trace[0].step, "DEFINE(left, INT(1))");
+        assert_eq!(trace[0].changed.get("left"), Some(&json!(1)));
+        assert_eq!(trace[1].result, json!(2));
+        assert!(trace[1].changed.is_empty());
+        assert_eq!(trace[2].step, "DEFINE(right, INT(2))");
+        assert_eq!(trace[2].changed.get("right"), Some(&json!(2)));
+    }
+
+    #[test]
+    fn test_secret() {
+        let process = Process::new(
+            "secret",
+            vec![Definition::new(vec![
+                "DEFINE(psql_password, 'shadowed')",
+                "DEFINE(OUT, SECRET(psql_password))",
+            ])],
+            None,
+        );
+        let secrets = HashMap::from([(String::from("psql_password"), String::from("pass"))]);
+
+        let mut engine = Engine::builder()
+            .with_secrets(secrets)
+            .build(Variable::None, process)
+            .unwrap();
+        engine.fire().unwrap();
+        assert_eq!(
+            engine.get("OUT").unwrap(),
+            &Variable::String(String::from("pass"))
+        );
+
+        let process = Process::new(
+            "secret",
+            vec![Definition::new(vec!["DEFINE(OUT, SECRET(missing))"])],
+            None,
+        );
+        let mut engine = Engine::new(Variable::None, process).unwrap();
+        assert!(engine.fire().is_err());
+    }
+
+    #[test]
+    fn test_param() {
+        let process = Process::new(
+            "param",
+            vec![Definition::new(vec![
+                "DEFINE(symbol, 'shadowed')",
+                "DEFINE(OUT, VEC(PARAM(symbol), GET(symbol)))",
+            ])],
+            None,
+        );
+        let params = HashMap::from([(
+            String::from("symbol"),
+            Variable::String(String::from("BTC")),
+        )]);
+
+        let mut engine = Engine::builder()
+            .with_params(params)
+            .build(Variable::None, process)
+            .unwrap();
+        engine.fire().unwrap();
+        assert_eq!(
+            engine.get("OUT").unwrap(),
+            &Variable::Vector(vec![
+                Variable::String(String::from("BTC")),
+                Variable::String(String::from("shadowed"))
+            ])
+        );
+    }
 }
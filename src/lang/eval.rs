@@ -1,5 +1,6 @@
 use super::node::Node;
 use super::process::Definition;
+use super::registry::Registry;
 use crate::error::types::{Error, Result};
 use crate::lang::lexer::{Lexer, Parser};
 use serde::{Deserialize, Serialize};
@@ -13,6 +14,16 @@ pub(super) struct EvalForest {
 
     // subtress that will be run implicitly, cannot be called from Definition's steps.
     pub implicit_subtrees: HashMap<String, Vec<Node>>,
+
+    // whether forest can be run concurrently with neighbouring parallel forests.
+    pub parallel: bool,
+
+    // variables merged back into shared state after parallel run.
+    pub outputs: Vec<String>,
+
+    // source of roots, in the same order, used to describe evaluation trace.
+    #[serde(default)]
+    pub steps: Vec<String>,
 }
 
 impl EvalForest {
@@ -23,13 +34,26 @@ impl EvalForest {
     }
 }
 
-impl From<Definition> for EvalForest {
-    fn from(def: Definition) -> Self {
+impl TryFrom<Definition> for EvalForest {
+    type Error = Error;
+
+    fn try_from(def: Definition) -> Result<Self> {
+        Self::new(def, &Registry::default())
+    }
+}
+
+impl EvalForest {
+    /// Parses Definition into EvalForest, resolving native functions against given Registry.
+    /// Fails if any of the steps cannot be parsed.
+    pub fn new(def: Definition, registry: &Registry) -> Result<Self> {
+        let parse = |step: &str| {
+            Parser::new(Lexer::new_with_registry(step, registry).make_tokens()?).parse()
+        };
         let mut roots = vec![];
 
         // parse base steps in Definition.
         for step in &def.steps {
-            roots.push(Parser::new(Lexer::new(step).make_tokens()).parse().unwrap());
+            roots.push(parse(step)?);
         }
 
         let mut subtrees = HashMap::default();
@@ -41,7 +65,7 @@ impl From<Definition> for EvalForest {
 
             // parse base steps in Definition.
             for step in &subtree.definition.steps {
-                roots.push(Parser::new(Lexer::new(step).make_tokens()).parse().unwrap());
+                roots.push(parse(step)?);
             }
 
             // check if subtree is marked as implicit - if so, do not parse it as it'll be run separatelly.
@@ -57,11 +81,14 @@ impl From<Definition> for EvalForest {
             }
         }
 
-        EvalForest {
+        Ok(EvalForest {
             roots,
             subtrees,
             implicit_subtrees,
-        }
+            parallel: def.parallel,
+            outputs: def.outputs.unwrap_or_default(),
+            steps: def.steps,
+        })
     }
 }
 
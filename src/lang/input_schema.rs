@@ -0,0 +1,197 @@
+use super::process::Process;
+use super::variable::Variable;
+use crate::error::types::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// Types supported by InputSchema, named the same way as in JSON Schema.
+pub enum SchemaType {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl SchemaType {
+    pub(crate) fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Boolean,
+            Value::Number(n) if n.is_f64() => Self::Number,
+            Value::Number(_) => Self::Integer,
+            Value::String(_) => Self::String,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+        }
+    }
+
+    pub(crate) fn matches(&self, value: &Value) -> bool {
+        match (self, Self::of(value)) {
+            // every integer is a number as well.
+            (Self::Number, Self::Integer) => true,
+            (wanted, got) => *wanted == got,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+/// JSON-Schema-like description of IN variable. Supports subset of JSON Schema:
+/// `type`, `properties`, `required` and `items`. Every keyword is optional.
+pub struct InputSchema {
+    #[serde(rename = "type")]
+    pub schema_type: Option<SchemaType>,
+
+    /// Schemas of object's fields, checked only if field is present.
+    pub properties: Option<BTreeMap<String, InputSchema>>,
+
+    /// Fields that object must contain.
+    pub required: Option<Vec<String>>,
+
+    /// Schema of every array's element.
+    pub items: Option<Box<InputSchema>>,
+}
+
+impl InputSchema {
+    /// Validates value against schema, path is used to point to invalid part of value, e.g. IN.user.id.
+    pub fn validate(&self, value: &Value, path: &str) -> Result<()> {
+        let invalid = |msg: String| Error::new_validation("InputSchema", msg.as_str(), path);
+
+        if let Some(schema_type) = self.schema_type {
+            if !schema_type.matches(value) {
+                return Err(invalid(format!(
+                    "expected {:?}, got {:?}",
+                    schema_type,
+                    SchemaType::of(value)
+                )));
+            }
+        }
+
+        match value {
+            Value::Object(object) => {
+                for field in self.required.as_ref().unwrap_or(&vec![]) {
+                    if !object.contains_key(field) {
+                        return Err(invalid(format!("missing required field {}", field)));
+                    }
+                }
+                for (field, schema) in self.properties.iter().flatten() {
+                    if let Some(value) = object.get(field) {
+                        schema.validate(value, &format!("{}.{}", path, field))?;
+                    }
+                }
+            }
+            Value::Array(array) => {
+                if let Some(items) = &self.items {
+                    for (i, value) in array.iter().enumerate() {
+                        items.validate(value, &format!("{}[{}]", path, i))?;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Process {
+    /// Checks IN variable against Process's input_schema, if there's any.
+    pub fn validate_input(&self, input: &Variable) -> Result<()> {
+        match &self.input_schema {
+            Some(schema) => schema.validate(&Value::from(input.clone()), "IN"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputSchema;
+    use crate::core::task::InputData;
+    use crate::error::types::Error;
+    use crate::lang::process::{Definition, Process};
+    use crate::lang::variable::Variable;
+    use serde_json::json;
+
+    fn schema() -> InputSchema {
+        serde_json::from_value(json!({
+            "type": "object",
+            "required": ["user"],
+            "properties": {
+                "user": {
+                    "type": "object",
+                    "required": ["id"],
+                    "properties": {
+                        "id": {"type": "integer"},
+                        "tags": {"type": "array", "items": {"type": "string"}}
+                    }
+                },
+                "score": {"type": "number"}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let schema = schema();
+
+        assert!(schema
+            .validate(
+                &json!({"user": {"id": 1, "tags": ["a", "b"]}, "score": 1}),
+                "IN"
+            )
+            .is_ok());
+        assert_eq!(
+            schema.validate(&json!({"user_renamed": {"id": 1}}), "IN"),
+            Err(Error::new_validation(
+                "InputSchema",
+                "missing required field user",
+                "IN"
+            ))
+        );
+        assert_eq!(
+            schema.validate(&json!({"user": {"id": "1"}}), "IN"),
+            Err(Error::new_validation(
+                "InputSchema",
+                "expected Integer, got String",
+                "IN.user.id"
+            ))
+        );
+        assert_eq!(
+            schema.validate(&json!({"user": {"id": 1, "tags": ["a", 2]}}), "IN"),
+            Err(Error::new_validation(
+                "InputSchema",
+                "expected String, got Integer",
+                "IN.user.tags[1]"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_process_validate_input() {
+        let process = Process {
+            name: String::from("process"),
+            definitions: vec![Definition::new(vec!["DEFINE(OUT, GET(IN))"])],
+            input_schema: Some(schema()),
+            ..Default::default()
+        };
+
+        let input = Variable::from(InputData::Json(json!({"user": {"id": 1}})));
+        assert!(process.validate_input(&input).is_ok());
+
+        let input = Variable::from(InputData::String(String::from("user")));
+        assert_eq!(
+            process.validate_input(&input),
+            Err(Error::new_validation(
+                "InputSchema",
+                "expected Object, got String",
+                "IN"
+            ))
+        );
+    }
+}
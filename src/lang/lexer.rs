@@ -1,7 +1,7 @@
-use super::node::{Node, NodeEnum};
+use super::node::Node;
+use super::registry::Registry;
 use super::variable::Variable;
 use crate::error::types::{Error, Result};
-use core::panic;
 use serde::{Deserialize, Serialize};
 
 /// All supported keyword that can be used in steps declarations.
@@ -49,6 +49,15 @@ pub enum Keyword {
     /// Can be chained like that: Eq(Eq(INT(1), INT(1)), Eq(FLOAT(2.5), FLOAT(2.5))).
     Eq,
     Neq,
+    /// Compares two numbers, Int, Float, Json numbers and numeric Strings are supported.
+    /// Returns true if first number is greater than second one: GT(GET(price), FLOAT(100.0)).
+    Gt,
+    /// Greater than or equal: GTE(GET(price), INT(100)).
+    Gte,
+    /// Less than: LT(GET(price), INT(100)).
+    Lt,
+    /// Less than or equal: LTE(GET(price), INT(100)).
+    Lte,
 
     Map,
     /// Can be used for vector/object values mapping: MAP(VEC(1,2,3), ADD(x, INT(4)))
@@ -73,10 +82,38 @@ pub enum Keyword {
     ///
     /// Fails if no transaction was started.
     Rollback,
+
+    /// Encodes Variable::String using standard base64 alphabet: BASE64(text).
+    Base64,
+    /// Decodes base64 encoded Variable::String: BASE64_DECODE('dGV4dA==').
+    Base64Decode,
+    /// Encodes Variable::String as lowercase hex: HEX(text).
+    Hex,
+    /// Decodes hex encoded Variable::String: HEX_DECODE(74657874).
+    HexDecode,
+    /// Percent-encodes Variable::String so it can be used in urls: URL_ENCODE('a b').
+    UrlEncode,
+    /// Decodes percent-encoded Variable::String: URL_DECODE('a%20b').
+    UrlDecode,
+    /// Returns hex encoded SHA-256 digest of Variable::String: SHA256(text).
+    Sha256,
+    /// Returns hex encoded HMAC of a message, supported algorithms are sha256 and sha512.
+    /// Algorithm has to be quoted so it won't be parsed as a keyword: HMAC('sha256', key, message).
+    Hmac,
+    /// Takes no arguments, returns random (v4) uuid: UUID().
+    Uuid,
+
+    /// Returns value of stored secret, secret's value is redacted from logs: SECRET(psql_password).
+    Secret,
+    /// Returns value of task's param declared by Process, params are read-only: PARAM(symbol).
+    Param,
+
+    /// Function registered by embedder in Registry, holds lowercased function name.
+    Native(String),
 }
 
 impl Keyword {
-    fn from_string(s: &str) -> Option<Self> {
+    pub(crate) fn from_string(s: &str) -> Option<Self> {
         let s = match s.to_lowercase().as_str() {
             "define" => Self::Define,
             "get" => Self::Get,
@@ -99,6 +136,10 @@ impl Keyword {
             "if" => Self::If,
             "eq" => Self::Eq,
             "neq" => Self::Neq,
+            "gt" => Self::Gt,
+            "gte" => Self::Gte,
+            "lt" => Self::Lt,
+            "lte" => Self::Lte,
             "map" => Self::Map,
             "mapinplace" => Self::MapInPlace,
             "filter" => Self::Filter,
@@ -106,6 +147,17 @@ impl Keyword {
             "begin" => Self::Begin,
             "commit" => Self::Commit,
             "rollback" => Self::Rollback,
+            "base64" => Self::Base64,
+            "base64_decode" | "base64decode" => Self::Base64Decode,
+            "hex" => Self::Hex,
+            "hex_decode" | "hexdecode" => Self::HexDecode,
+            "url_encode" | "urlencode" => Self::UrlEncode,
+            "url_decode" | "urldecode" => Self::UrlDecode,
+            "sha256" => Self::Sha256,
+            "hmac" => Self::Hmac,
+            "uuid" => Self::Uuid,
+            "secret" => Self::Secret,
+            "param" => Self::Param,
             _ => Self::None,
         };
         if s == Self::None {
@@ -116,12 +168,18 @@ impl Keyword {
 
     /// Returns error if there's invalid number of arguments for given Keyword.
     pub fn check_arguments_count(&self, nodes: &[Variable]) -> Result<()> {
+        self.check_arity(nodes.len())
+    }
+
+    /// Checks if keyword can be called with given number of arguments.
+    pub fn check_arity(&self, count: usize) -> Result<()> {
         let wanted = match self {
             Keyword::None
             | Keyword::Break
             | Keyword::Begin
             | Keyword::Commit
-            | Keyword::Rollback => 0,
+            | Keyword::Rollback
+            | Keyword::Uuid => 0,
             Keyword::Get
             | Keyword::Json
             | Keyword::Object
@@ -131,7 +189,16 @@ impl Keyword {
             | Keyword::HTTP
             | Keyword::Log
             | Keyword::RunSubtree
-            | Keyword::ReadMountedToString => 1,
+            | Keyword::ReadMountedToString
+            | Keyword::Base64
+            | Keyword::Base64Decode
+            | Keyword::Hex
+            | Keyword::HexDecode
+            | Keyword::UrlEncode
+            | Keyword::UrlDecode
+            | Keyword::Sha256
+            | Keyword::Secret
+            | Keyword::Param => 1,
             Keyword::Define
             | Keyword::Add
             | Keyword::Sub
@@ -140,39 +207,43 @@ impl Keyword {
             | Keyword::If
             | Keyword::Eq
             | Keyword::Neq
+            | Keyword::Gt
+            | Keyword::Gte
+            | Keyword::Lt
+            | Keyword::Lte
             | Keyword::Map
             | Keyword::MapInPlace
             | Keyword::Filter
             | Keyword::Append => 2,
+            Keyword::Hmac => 3,
+            // arity of native functions is checked by Registry.
+            Keyword::Native(_) => count,
             Keyword::Vec => {
-                if nodes.is_empty() {
+                if count == 0 {
                     return Err(Error::new_eval_internal(
                         String::from("Keyword::check_arguments_count"),
                         format!(
                             "keyword: {:?} - wanted at least 1 argument, got {}",
-                            self,
-                            nodes.len()
+                            self, count
                         ),
                     ));
                 }
-                nodes.len()
+                count
             }
             Keyword::Extract => {
-                if nodes.len() > 3 && nodes.len() < 2 {
+                if count > 3 && count < 2 {
                     return Err(Error::new_eval_internal(
                         String::from("Keyword::check_arguments_count"),
                         format!(
                             "keyword: {:?} - wanted 2 or 3 arguments, got {}",
-                            self,
-                            nodes.len()
+                            self, count
                         ),
                     ));
                 }
-                nodes.len()
+                count
             }
         };
-        nodes
-            .len()
+        count
             .eq(&wanted)
             .then_some(0)
             .ok_or_else(|| {
@@ -180,9 +251,7 @@ impl Keyword {
                     String::from("Keyword::check_arguments_count"),
                     format!(
                         "keyword: {:?} - wanted {} arguments, got {}",
-                        self,
-                        wanted,
-                        nodes.len()
+                        self, wanted, count
                     ),
                 )
             })
@@ -192,7 +261,7 @@ impl Keyword {
     /// Determines if enum variant's first argument is l_value or not.
     /// l_value won't be automatically converted into initialized variable with the same name.
     fn contain_l_value(&self) -> bool {
-        matches!(self, Self::Define | Self::Get)
+        matches!(self, Self::Define | Self::Get | Self::Secret | Self::Param)
     }
 }
 
@@ -222,6 +291,9 @@ pub struct Lexer<'a> {
     /// Contains previously parsed token, handy for determining if something is l_value or not.
     /// Check Keyword::contain_l_value method for more info.
     previous: Option<Token>,
+
+    /// Native functions that unknown identifiers are resolved against.
+    registry: Option<&'a Registry>,
 }
 
 impl<'a> Lexer<'a> {
@@ -229,18 +301,26 @@ impl<'a> Lexer<'a> {
         Lexer {
             text,
             pos: 0,
-            current_char: text.chars().next().unwrap(),
-            done: false,
+            current_char: text.chars().next().unwrap_or_default(),
+            done: text.is_empty(),
             previous: None,
+            registry: None,
+        }
+    }
+
+    /// Creates Lexer that resolves identifiers against given Registry before treating them as variables.
+    pub fn new_with_registry(text: &'a str, registry: &'a Registry) -> Self {
+        Lexer {
+            registry: Some(registry),
+            ..Lexer::new(text)
         }
     }
 
     fn advance(&mut self) {
         self.pos += 1;
-        if self.pos < self.text.len() {
-            self.current_char = self.text.chars().nth(self.pos).unwrap();
-        } else {
-            self.done = true
+        match self.text.chars().nth(self.pos) {
+            Some(ch) => self.current_char = ch,
+            None => self.done = true,
         }
     }
 
@@ -248,8 +328,8 @@ impl<'a> Lexer<'a> {
         ch.is_whitespace() || ch == '"' || ch == '{' || ch == '}' || ch == ':'
     }
 
-    /// Create tokens from wanted declaration.
-    pub fn make_tokens(&mut self) -> Vec<Token> {
+    /// Create tokens from wanted declaration, fails on unexpected characters and unclosed strings.
+    pub fn make_tokens(&mut self) -> Result<Vec<Token>> {
         let mut tokens: Vec<Token> = vec![];
 
         while !self.done {
@@ -266,13 +346,13 @@ impl<'a> Lexer<'a> {
                 tokens.push(Token::Comma);
                 self.advance()
             } else {
-                tokens.push(self.make_word())
+                tokens.push(self.make_word()?)
             }
         }
-        tokens
+        Ok(tokens)
     }
 
-    fn make_word(&mut self) -> Token {
+    fn make_word(&mut self) -> Result<Token> {
         let mut word = String::new();
         let mut apostrophe_found = false; // if so, we will have to find another end of whole string;
         let mut ending_apostrophe_missing = true;
@@ -290,7 +370,10 @@ impl<'a> Lexer<'a> {
                     break;
                 }
             }
-            if (self.current_char.is_alphanumeric() || self.current_char == '.') || apostrophe_found
+            if (self.current_char.is_alphanumeric()
+                || self.current_char == '.'
+                || self.current_char == '_')
+                || apostrophe_found
             {
                 word.push(self.current_char);
                 self.advance();
@@ -299,25 +382,41 @@ impl<'a> Lexer<'a> {
             }
         }
         if apostrophe_found && ending_apostrophe_missing {
-            panic!("string with start but without the end")
+            return Err(Error::new_eval_internal(
+                String::from("Lexer::make_tokens"),
+                format!("string '{} is not closed", word),
+            ));
+        }
+        if word.is_empty() && !apostrophe_found {
+            return Err(Error::new_eval_internal(
+                String::from("Lexer::make_tokens"),
+                format!("unexpected character {}", self.current_char),
+            ));
         }
 
+        let l_value = match &self.previous {
+            Some(Token::Keyword(k)) => k.contain_l_value() || apostrophe_found,
+            _ => false,
+        };
         let mut token = Token::Var {
             value: word.clone(),
             l_value: false,
         };
-        if let Some(f) = Keyword::from_string(&word) {
+        // quoted words are always treated as plain strings, even if they match a keyword.
+        if let Some(f) = Keyword::from_string(&word).filter(|_| !apostrophe_found) {
             token = Token::Keyword(f)
-        } else if let Some(Token::Keyword(k)) = &self.previous {
-            if k.contain_l_value() || apostrophe_found {
-                token = Token::Var {
-                    value: word,
-                    l_value: true,
-                };
-            }
+        } else if !apostrophe_found && !l_value && self.registry.is_some_and(|r| r.contains(&word))
+        {
+            // names of variables, e.g. DEFINE(double, ...), are never resolved to native functions.
+            token = Token::Keyword(Keyword::Native(word.to_lowercase()))
+        } else if l_value {
+            token = Token::Var {
+                value: word,
+                l_value: true,
+            };
         };
         self.previous = Some(token.clone());
-        token
+        Ok(token)
     }
 }
 
@@ -325,63 +424,82 @@ impl<'a> Lexer<'a> {
 pub struct Parser {
     tokens: Vec<Token>,
     token_inx: usize,
-    current_token: Token,
-    done: bool,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        let t = tokens.first().unwrap().clone();
         Parser {
             tokens,
             token_inx: 0,
-            current_token: t,
-            done: false,
         }
     }
 
-    fn advance(&mut self) {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.token_inx)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.token_inx).cloned();
         self.token_inx += 1;
-        if self.token_inx < self.tokens.len() {
-            self.current_token = self.tokens.get(self.token_inx).unwrap().clone();
-        } else {
-            self.done = true
-        }
+        token
     }
 
-    pub fn parse(&mut self) -> Option<Node> {
-        if self.done {
-            return None;
+    /// Parses tokens of a single step, fails if they are not a single keyword call.
+    pub fn parse(&mut self) -> Result<Node> {
+        let node = self.parse_keyword()?;
+        match self.peek() {
+            Some(token) => Err(parse_error(format!(
+                "unexpected {:?} after the end of expression",
+                token
+            ))),
+            None => Ok(node),
         }
-        let mut pt: Node;
-        if let Token::Keyword(s) = &self.current_token {
-            pt = Node::new_keyword(s.clone());
-        } else {
-            panic!("first token is not keyword")
+    }
+
+    /// Parses keyword with its arguments. Keyword without arguments can be used without brackets: BREAK.
+    fn parse_keyword(&mut self) -> Result<Node> {
+        let mut node = match self.advance() {
+            Some(Token::Keyword(keyword)) => Node::new_keyword(keyword),
+            Some(token) => return Err(parse_error(format!("wanted keyword, got {:?}", token))),
+            None => return Err(parse_error(String::from("wanted keyword, got nothing"))),
+        };
+        if self.peek() != Some(&Token::LeftBracket) {
+            return Ok(node);
         }
-        while !self.done {
-            self.advance();
-            match &self.current_token {
-                Token::RightBracket => {
-                    break;
+        self.advance();
+        loop {
+            match self.peek().cloned() {
+                Some(Token::RightBracket) => {
+                    self.advance();
+                    return Ok(node);
                 }
-                Token::Keyword(_) => {
-                    if let Some(parsed) = self.parse() {
-                        pt.push(parsed)
+                Some(Token::Keyword(_)) => {
+                    let parsed = self.parse_keyword()?;
+                    node.push(parsed);
+                }
+                Some(Token::Var { value, l_value }) => {
+                    self.advance();
+                    if self.peek() == Some(&Token::LeftBracket) {
+                        return Err(parse_error(format!("unknown function {}", value)));
                     }
+                    node.push(Node::new_var(value, l_value));
+                }
+                Some(Token::Comma) => {
+                    self.advance();
+                }
+                Some(Token::LeftBracket) => {
+                    return Err(parse_error(String::from("unexpected LeftBracket")));
                 }
-                Token::Var { value, l_value } => pt.push(Node::new_var(value.clone(), *l_value)),
-                _ => (),
+                None => return Err(parse_error(String::from("bracket is not closed"))),
             }
         }
-        if pt.value == NodeEnum::None {
-            None
-        } else {
-            Some(pt)
-        }
     }
 }
 
+fn parse_error(msg: String) -> Error {
+    Error::new_eval_internal(String::from("Parser::parse"), msg)
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Lexer, Result};
@@ -390,8 +508,8 @@ mod tests {
         lang::{
             engine::Engine,
             eval::EvalForest,
-            lexer::{Keyword, Node, NodeEnum, Parser, Token},
-            node::{EvalMetadata, SharedState},
+            lexer::{Keyword, Node, Parser, Token},
+            node::{EvalMetadata, NodeEnum, SharedState},
             process::{Definition, Process, SubTree},
             variable::Variable,
         },
@@ -429,7 +547,7 @@ mod tests {
     #[test]
     fn test_simple_lexer() {
         let mut lexer = Lexer::new("DEFINE)");
-        let tokens = lexer.make_tokens();
+        let tokens = lexer.make_tokens().unwrap();
         let wanted: Vec<Token> = vec![Token::Keyword(Keyword::Define), Token::RightBracket];
         assert_eq!(tokens, wanted);
     }
@@ -446,7 +564,7 @@ mod tests {
         }"#;
         let t = format!("OBJECT('{}')", map_str);
         let mut lexer = Lexer::new(&t);
-        let tokens = lexer.make_tokens();
+        let tokens = lexer.make_tokens().unwrap();
         let wanted: Vec<Token> = vec![
             Token::Keyword(Keyword::Object),
             Token::LeftBracket,
@@ -463,7 +581,7 @@ mod tests {
     fn test_lexer() {
         let mut lexer = Lexer::new("DEFINE(var, VEC(1,BOOL(2),3,FLOAT(4.0)))");
 
-        let tokens = lexer.make_tokens();
+        let tokens = lexer.make_tokens().unwrap();
         let wanted: Vec<Token> = vec![
             Token::Keyword(Keyword::Define),
             Token::LeftBracket,
@@ -526,7 +644,7 @@ mod tests {
     #[test]
     fn test_lexer_v2() {
         let mut lexer = Lexer::new("DEFINE(var3, EXTRACT(var, use))");
-        let tokens = lexer.make_tokens();
+        let tokens = lexer.make_tokens().unwrap();
 
         let mut parser = Parser::new(tokens);
         let got = parser.parse().unwrap();
@@ -543,7 +661,7 @@ mod tests {
 
     #[test]
     fn test_lexer_v3() {
-        let tokens = Lexer::new("DEFINE(var3, qwdqw)").make_tokens();
+        let tokens = Lexer::new("DEFINE(var3, qwdqw)").make_tokens().unwrap();
         let got = Parser::new(tokens).parse().unwrap();
 
         let r = Node::new_keyword(Keyword::Define)
@@ -689,7 +807,7 @@ mod tests {
         let mut state = SharedState::default();
 
         let mut lexer = Lexer::new("VEC(1,BOOL(true),3,FLOAT(4.0))");
-        let tokens = lexer.make_tokens();
+        let tokens = lexer.make_tokens().unwrap();
         let mut parser = Parser::new(tokens);
         let got = parser.parse().unwrap();
         println!("{:?}", got);
@@ -706,7 +824,7 @@ mod tests {
 
     fn fire_for_test(def: Definition, state: &mut SharedState) -> Result<()> {
         for step in def {
-            let root = Parser::new(Lexer::new(&step).make_tokens())
+            let root = Parser::new(Lexer::new(&step).make_tokens().unwrap())
                 .parse()
                 .unwrap();
             root.start_evaluation(state)?;
@@ -958,7 +1076,8 @@ mod tests {
         let tokens = Lexer::new(
             format!("DEFINE(var, VEC(1, INT(2), FLOAT(3.2), JSON('{}')))", data).as_str(),
         )
-        .make_tokens();
+        .make_tokens()
+        .unwrap();
         let root = Parser::new(tokens).parse().unwrap();
 
         let serialized = serde_json::to_string(&root).unwrap();
@@ -980,8 +1099,10 @@ mod tests {
             }]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(None, &eval_forest).expect("could not evaluate");
         assert_eq!(out, Variable::Int(30));
 
@@ -999,13 +1120,17 @@ mod tests {
                         subtrees: None,
                         name: None,
                         implicit_subtrees: None,
+                        parallel: false,
+                        outputs: None,
                     },
                 },
             ]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(None, &eval_forest).expect("could not evaluate");
         assert_eq!(out, Variable::Int(400));
     }
@@ -1157,13 +1282,17 @@ mod tests {
                     subtrees: None,
                     name: None,
                     implicit_subtrees: None,
+                    parallel: false,
+                    outputs: None,
                 },
             }]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(Some(Variable::Int(125)), &eval_forest).expect("could not evaluate");
         assert_eq!(out, Variable::Int(135));
 
@@ -1177,13 +1306,17 @@ mod tests {
                     subtrees: None,
                     name: None,
                     implicit_subtrees: None,
+                    parallel: false,
+                    outputs: None,
                 },
             }]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(Some(Variable::Int(125)), &eval_forest).expect("could not evaluate");
         assert_eq!(out, Variable::Int(125));
 
@@ -1197,6 +1330,8 @@ mod tests {
                         subtrees: None,
                         name: None,
                         implicit_subtrees: None,
+                        parallel: false,
+                        outputs: None,
                     },
                 },
                 SubTree {
@@ -1207,14 +1342,18 @@ mod tests {
                         subtrees: None,
                         name: None,
                         implicit_subtrees: None,
+                        parallel: false,
+                        outputs: None,
                     },
                 },
             ]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(Some(Variable::Int(1)), &eval_forest).expect("could not evaluate");
         assert_eq!(out, Variable::Int(155));
 
@@ -1229,6 +1368,8 @@ mod tests {
             subtrees: None,
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
         let process = Process::new("test", vec![definition], None);
@@ -1242,6 +1383,8 @@ mod tests {
             subtrees: None,
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
         let process = Process::new("test", vec![definition], None);
@@ -1255,6 +1398,8 @@ mod tests {
             subtrees: None,
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
         let process = Process::new("test", vec![definition], None);
@@ -1266,11 +1411,37 @@ mod tests {
 
     #[test]
     fn test_break_parse() {
-        let root = Parser::new(Lexer::new("BREAK").make_tokens())
+        let root = Parser::new(Lexer::new("BREAK").make_tokens().unwrap())
             .parse()
             .unwrap();
         assert_eq!(root.value, NodeEnum::Keyword(Keyword::Break));
         assert_eq!(root.nodes.len(), 0);
+
+        // keyword without brackets does not close its parent.
+        let root = Parser::new(Lexer::new("IF(GET(IN), BREAK)").make_tokens().unwrap())
+            .parse()
+            .unwrap();
+        assert_eq!(root.nodes.len(), 2);
+        assert_eq!(root.nodes[1].value, NodeEnum::Keyword(Keyword::Break));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for invalid in [
+            "",
+            "var",
+            "DEFINE(var, 'open)",
+            "DEFINE(var, INT(-1))",
+            "DEFINE(var, INT(1)",
+            "DEFINE(var, INT(1)))",
+            "DEFINE(var, unknown(1))",
+            "DEFINE(var, INT(1)) DEFINE(var, INT(2))",
+        ] {
+            let parsed = Lexer::new(invalid)
+                .make_tokens()
+                .and_then(|tokens| Parser::new(tokens).parse());
+            assert!(parsed.is_err(), "{}", invalid);
+        }
     }
 
     #[test]
@@ -1289,6 +1460,8 @@ mod tests {
                         subtrees: None,
                         name: None,
                         implicit_subtrees: None,
+                        parallel: false,
+                        outputs: None,
                     },
                 },
                 SubTree {
@@ -1298,14 +1471,18 @@ mod tests {
                         subtrees: None,
                         name: None,
                         implicit_subtrees: None,
+                        parallel: false,
+                        outputs: None,
                     },
                 },
             ]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(Some(Variable::Int(2)), &eval_forest).expect("could not evaluate");
         assert_eq!(out, Variable::Int(2));
 
@@ -1323,13 +1500,17 @@ mod tests {
                     subtrees: None,
                     name: None,
                     implicit_subtrees: None,
+                    parallel: false,
+                    outputs: None,
                 },
             }]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(Some(Variable::Int(5)), &eval_forest).expect("could not evaluate");
         assert_eq!(out, Variable::Int(1));
 
@@ -1345,13 +1526,17 @@ mod tests {
                     subtrees: None,
                     name: None,
                     implicit_subtrees: None,
+                    parallel: false,
+                    outputs: None,
                 },
             }]),
             name: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
         };
 
-        let eval_forest = EvalForest::from(definition);
+        let eval_forest = EvalForest::try_from(definition).unwrap();
         let out = evaluate(Some(Variable::Int(5)), &eval_forest)
             .expect_err("should be stack overflow error");
         assert_eq!(
@@ -1368,6 +1553,25 @@ mod tests {
         test(def, String::from("OUT"), Variable::Bool(true));
     }
 
+    #[test]
+    fn comparison_test() {
+        for (expr, want) in [
+            ("GT(INT(2), INT(1))", true),
+            ("GT(INT(1), INT(1))", false),
+            ("GTE(INT(1), FLOAT(1.0))", true),
+            ("LT(FLOAT(0.5), INT(1))", true),
+            ("LTE('2.5', FLOAT(2.5))", true),
+            ("LT(JSON('3'), INT(3))", false),
+        ] {
+            let def = Definition::new(vec![format!("DEFINE(OUT, {})", expr)]);
+            test(def, String::from("OUT"), Variable::Bool(want));
+        }
+
+        let def = Definition::new(vec!["DEFINE(OUT, GT(abc, INT(1)))"]);
+        let eval_forest = EvalForest::try_from(def).unwrap();
+        assert!(evaluate(None, &eval_forest).is_err());
+    }
+
     #[test]
     fn map_test() {
         let def = Definition::new(vec![String::from(
@@ -1491,4 +1695,82 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_encoding_keywords() {
+        let def = Definition::new(vec!["DEFINE(OUT, BASE64(hello))"]);
+        test(
+            def,
+            String::from("OUT"),
+            Variable::String("aGVsbG8=".into()),
+        );
+
+        let def = Definition::new(vec!["DEFINE(OUT, BASE64_DECODE('aGVsbG8='))"]);
+        test(def, String::from("OUT"), Variable::String("hello".into()));
+
+        let def = Definition::new(vec!["DEFINE(OUT, HEX(hello))"]);
+        test(
+            def,
+            String::from("OUT"),
+            Variable::String("68656c6c6f".into()),
+        );
+
+        let def = Definition::new(vec!["DEFINE(OUT, HEX_DECODE(68656c6c6f))"]);
+        test(def, String::from("OUT"), Variable::String("hello".into()));
+
+        let def = Definition::new(vec!["DEFINE(OUT, URL_ENCODE('a b&c'))"]);
+        test(
+            def,
+            String::from("OUT"),
+            Variable::String("a%20b%26c".into()),
+        );
+
+        let def = Definition::new(vec!["DEFINE(OUT, URL_DECODE('a%20b%26c'))"]);
+        test(def, String::from("OUT"), Variable::String("a b&c".into()));
+
+        let def = Definition::new(vec!["DEFINE(OUT, BASE64_DECODE(BASE64(roundtrip)))"]);
+        test(
+            def,
+            String::from("OUT"),
+            Variable::String("roundtrip".into()),
+        );
+    }
+
+    #[test]
+    fn test_hashing_keywords() {
+        let def = Definition::new(vec!["DEFINE(OUT, SHA256(hello))"]);
+        test(
+            def,
+            String::from("OUT"),
+            Variable::String(
+                "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".into(),
+            ),
+        );
+
+        let def = Definition::new(vec![
+            "DEFINE(secret_key, key)",
+            "DEFINE(OUT, HMAC('sha256', secret_key, 'The quick brown fox jumps over the lazy dog'))",
+        ]);
+        test(
+            def,
+            String::from("OUT"),
+            Variable::String(
+                "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8".into(),
+            ),
+        );
+
+        let def = Definition::new(vec!["DEFINE(OUT, HMAC('md5', key, message))"]);
+        assert_eq!(
+            fire_for_test(def, &mut SharedState::default()),
+            Err(Error::new_eval_internal(
+                "hmac",
+                "unsupported algorithm: md5, wanted sha256 or sha512"
+            ))
+        );
+
+        let mut state = SharedState::default();
+        fire_for_test(Definition::new(vec!["DEFINE(OUT, UUID())"]), &mut state).unwrap();
+        let out = state.variables.get("OUT").unwrap().to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(out).is_ok());
+    }
 }
@@ -0,0 +1,227 @@
+use super::process::{Definition, Process, SubTree};
+use crate::error::types::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+/// Named and versioned Definition stored in persistance that can be shared across many Processes.
+/// Imported LibraryDefinition is available in every Definition of a Process as a SubTree
+/// with the same name as LibraryDefinition: RunSubtree(lib_name).
+pub struct LibraryDefinition {
+    pub name: String,
+    pub version: i32,
+    pub definition: Definition,
+}
+
+impl LibraryDefinition {
+    pub fn new<S: Into<String>>(name: S, version: i32, definition: Definition) -> Self {
+        Self {
+            name: name.into(),
+            version,
+            definition,
+        }
+    }
+
+    /// Validates if LibraryDefinition fields are valid.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name.contains('@') {
+            return Err(Error::new_validation(
+                "LibraryDefinition",
+                "Name cannot be empty or contain '@'",
+                "name",
+            ));
+        }
+        self.definition.validate()
+    }
+
+    /// Translates LibraryDefinition into SubTree that can be injected into Definition.
+    fn to_subtree(&self) -> SubTree {
+        SubTree {
+            name: self.name.clone(),
+            definition: self.definition.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Reference to LibraryDefinition used in Process's imports.
+/// Written as `name@v2` (or `name@2`) for pinned version or just `name` for the latest one.
+pub struct Import {
+    pub name: String,
+    pub version: Option<i32>,
+}
+
+impl FromStr for Import {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            Error::new_validation(
+                "Import",
+                format!("Invalid import: {}", s).as_str(),
+                "imports",
+            )
+        };
+
+        let (name, version) = match s.split_once('@') {
+            Some((name, version)) => {
+                let version = version
+                    .trim_start_matches('v')
+                    .parse::<i32>()
+                    .map_err(|_| invalid())?;
+                (name, Some(version))
+            }
+            None => (s, None),
+        };
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            version,
+        })
+    }
+}
+
+impl Process {
+    /// Returns parsed imports of a Process.
+    pub fn parsed_imports(&self) -> Result<Vec<Import>> {
+        self.imports
+            .as_ref()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|i| Import::from_str(i))
+            .collect()
+    }
+
+    /// Injects imported LibraryDefinitions as SubTrees into every Definition of a Process.
+    /// Resolved Process has no imports left, so it can be safely resolved again.
+    pub fn resolve_imports(mut self, imported: &[LibraryDefinition]) -> Result<Self> {
+        for import in self.parsed_imports()? {
+            let library_definition = imported
+                .iter()
+                .filter(|ld| {
+                    ld.name == import.name
+                        && (import.version.is_none() || import.version == Some(ld.version))
+                })
+                .max_by_key(|ld| ld.version)
+                .ok_or_else(|| {
+                    Error::new_validation(
+                        "Process",
+                        format!("Import {} was not resolved", import.name).as_str(),
+                        "imports",
+                    )
+                })?;
+
+            for definition in self.definitions.iter_mut() {
+                inject_subtree(definition, library_definition.to_subtree())?;
+            }
+        }
+        self.imports = None;
+        Ok(self)
+    }
+}
+
+/// Adds SubTree to Definition, fails if SubTree with the same name already exists.
+fn inject_subtree(definition: &mut Definition, subtree: SubTree) -> Result<()> {
+    let subtrees = definition.subtrees.get_or_insert_with(Vec::new);
+    if subtrees.iter().any(|st| st.name == subtree.name) {
+        return Err(Error::new_validation(
+            "Process",
+            format!("Imported {} conflicts with existing subtree", subtree.name).as_str(),
+            "imports",
+        ));
+    }
+    subtrees.push(subtree);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Import, LibraryDefinition};
+    use crate::error::types::Error;
+    use crate::lang::engine::Engine;
+    use crate::lang::process::{Definition, Process};
+    use crate::lang::variable::Variable;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_import() {
+        assert_eq!(
+            Import::from_str("normalize@v2").unwrap(),
+            Import {
+                name: String::from("normalize"),
+                version: Some(2),
+            }
+        );
+        assert_eq!(
+            Import::from_str("normalize@3").unwrap(),
+            Import {
+                name: String::from("normalize"),
+                version: Some(3),
+            }
+        );
+        assert_eq!(
+            Import::from_str("normalize").unwrap(),
+            Import {
+                name: String::from("normalize"),
+                version: None,
+            }
+        );
+        assert!(Import::from_str("normalize@latest").is_err());
+        assert!(Import::from_str("@v1").is_err());
+    }
+
+    #[test]
+    fn test_resolve_imports() {
+        let imported = vec![
+            LibraryDefinition::new(
+                "increment",
+                1,
+                Definition::new(vec!["DEFINE(OUT, ADD(GET(OUT), INT(1)))"]),
+            ),
+            LibraryDefinition::new(
+                "increment",
+                2,
+                Definition::new(vec!["DEFINE(OUT, ADD(GET(OUT), INT(2)))"]),
+            ),
+        ];
+
+        let mut process = Process::new(
+            "process with imports",
+            vec![Definition::new(vec![
+                "DEFINE(OUT, INT(1))",
+                "RunSubtree(increment)",
+            ])],
+            None,
+        );
+        process.imports = Some(vec![String::from("increment@v2")]);
+
+        let resolved = process.resolve_imports(&imported).unwrap();
+        assert!(resolved.imports.is_none());
+
+        let mut engine = Engine::new(Variable::None, resolved).unwrap();
+        engine.fire().unwrap();
+        assert_eq!(engine.get("OUT").unwrap(), &Variable::Int(3));
+    }
+
+    #[test]
+    fn test_resolve_imports_missing() {
+        let mut process = Process::new(
+            "process with imports",
+            vec![Definition::new(vec!["RunSubtree(increment)"])],
+            None,
+        );
+        process.imports = Some(vec![String::from("increment@v3")]);
+
+        assert_eq!(
+            process.resolve_imports(&[]),
+            Err(Error::new_validation(
+                "Process",
+                "Import increment was not resolved",
+                "imports"
+            ))
+        );
+    }
+}
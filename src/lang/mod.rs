@@ -1,6 +1,11 @@
 pub mod engine;
 pub mod eval;
+pub mod input_schema;
 pub mod lexer;
+pub mod library;
 pub mod node;
+pub mod params;
 pub mod process;
+pub mod registry;
+pub mod testing;
 pub mod variable;
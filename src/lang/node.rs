@@ -1,11 +1,14 @@
 use super::lexer::Keyword;
+use super::registry::Registry;
 use super::variable::Variable;
+use crate::core::secret::redact;
 use crate::error::types::{Error, Result};
 use crate::lang::variable::value_object_to_variable_object;
 use anyhow::{bail, Context};
-use core::panic;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::io::Read;
@@ -70,6 +73,15 @@ pub struct SharedState {
 
     pub eval_metadata: EvalMetadata,
 
+    /// Holds native functions registered by embedder.
+    pub registry: Registry,
+
+    /// Holds values of secrets available with SECRET keyword.
+    pub secrets: HashMap<String, String>,
+
+    /// Holds values of task's params available with PARAM keyword.
+    pub params: HashMap<String, Variable>,
+
     /// Holds variables during lifetime of started transaction.
     ///
     /// After transaction commit this variables will overwrite `variables`.
@@ -96,6 +108,9 @@ impl SharedState {
             subtress,
             mounted,
             eval_metadata: EvalMetadata::default(),
+            registry: Registry::default(),
+            secrets: HashMap::new(),
+            params: HashMap::new(),
             transactions_variables: None,
         }
     }
@@ -242,11 +257,15 @@ impl Node {
                     Keyword::Json => json(&nodes),
                     Keyword::Object => object(&nodes),
                     Keyword::HTTP => http(&nodes),
-                    Keyword::Log => log(&nodes),
+                    Keyword::Log => log(&nodes, state),
                     Keyword::RunSubtree => run_subtree(&nodes, state, stack),
                     Keyword::If => if_return(&nodes),
                     Keyword::Eq => eq(&nodes),
                     Keyword::Neq => neq(&nodes),
+                    Keyword::Gt => compare(&nodes, |a, b| a > b),
+                    Keyword::Gte => compare(&nodes, |a, b| a >= b),
+                    Keyword::Lt => compare(&nodes, |a, b| a < b),
+                    Keyword::Lte => compare(&nodes, |a, b| a <= b),
                     Keyword::Break => {
                         break_function(stack);
                         Ok(Variable::None)
@@ -256,7 +275,22 @@ impl Node {
                     Keyword::Begin => begin(state),
                     Keyword::Commit => commit(state),
                     Keyword::Rollback => rollback(state),
-                    _ => panic!("should not be reached"),
+                    Keyword::Base64 => base64_encode(&nodes),
+                    Keyword::Base64Decode => base64_decode(&nodes),
+                    Keyword::Hex => hex_encode(&nodes),
+                    Keyword::HexDecode => hex_decode(&nodes),
+                    Keyword::UrlEncode => url_encode(&nodes),
+                    Keyword::UrlDecode => url_decode(&nodes),
+                    Keyword::Sha256 => sha256(&nodes),
+                    Keyword::Hmac => hmac(&nodes),
+                    Keyword::Uuid => Ok(Variable::String(uuid::Uuid::new_v4().to_string())),
+                    Keyword::Secret => secret(&nodes, state),
+                    Keyword::Param => param(&nodes, state),
+                    Keyword::Native(name) => state.registry.evaluate(name, &nodes),
+                    _ => Err(Error::new_eval_internal(
+                        String::from("eval"),
+                        format!("keyword: {:?} cannot be evaluated", keyword),
+                    )),
                 }
             }
             NodeEnum::Var { ref value, l_value } => {
@@ -510,11 +544,42 @@ fn http(nodes: &[Variable]) -> Result<Variable> {
     Ok(Variable::Json(body))
 }
 
-fn log(nodes: &[Variable]) -> Result<Variable> {
-    info!("value of nods: {:?}", nodes);
+fn log(nodes: &[Variable], state: &SharedState) -> Result<Variable> {
+    info!(
+        "value of nods: {}",
+        redact(&format!("{:?}", nodes), state.secrets.values())
+    );
     Ok(Variable::None)
 }
 
+/// Returns value of a secret preloaded to SharedState.
+fn secret(nodes: &[Variable], state: &SharedState) -> Result<Variable> {
+    let name = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("secret"), err.to_string()))?;
+    state
+        .secrets
+        .get(&name)
+        .map(|value| Variable::String(value.clone()))
+        .ok_or_else(|| {
+            Error::new_eval_internal(
+                String::from("secret"),
+                format!("secret {} is not available", name),
+            )
+        })
+}
+
+/// Returns value of task's param preloaded to SharedState.
+fn param(nodes: &[Variable], state: &SharedState) -> Result<Variable> {
+    let name = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("param"), err.to_string()))?;
+    state.params.get(&name).cloned().ok_or_else(|| {
+        Error::new_eval_internal(
+            String::from("param"),
+            format!("param {} is not declared", name),
+        )
+    })
+}
+
 /// Max times when 'RunSubtree' can be called in a loop.
 const MAX_SUBTREE_STACK: usize = 100;
 
@@ -577,6 +642,30 @@ fn neq(nodes: &[Variable]) -> Result<Variable> {
     Ok(Variable::Bool(!nodes[0].equals(&nodes[1])))
 }
 
+fn compare(nodes: &[Variable], cmp: fn(f64, f64) -> bool) -> Result<Variable> {
+    Ok(Variable::Bool(cmp(
+        to_number(&nodes[0])?,
+        to_number(&nodes[1])?,
+    )))
+}
+
+/// Converts Variable into a number so it can be compared, numeric Strings are parsed.
+fn to_number(v: &Variable) -> Result<f64> {
+    let number = match v {
+        Variable::Int(i) => Some(*i as f64),
+        Variable::Float(f) => Some(*f as f64),
+        Variable::Json(Value::Number(n)) => n.as_f64(),
+        Variable::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    number.ok_or_else(|| {
+        Error::new_eval_internal(
+            String::from("compare"),
+            format!("{:?} cannot be compared as a number", v),
+        )
+    })
+}
+
 fn map_function(nodes: &[Node], state: &mut SharedState) -> Result<Variable> {
     assert_eq!(nodes.len(), 2);
     let mapped_variable = match nodes[0].start_evaluation(state) {
@@ -753,6 +842,99 @@ fn rollback(state: &mut SharedState) -> Result<Variable> {
     ))
 }
 
+/// Encodes Variable::String to base64.
+fn base64_encode(nodes: &[Variable]) -> Result<Variable> {
+    let v = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("base64"), err.to_string()))?;
+    Ok(Variable::String(base64::encode(v)))
+}
+
+/// Decodes base64 Variable::String, decoded bytes must be valid utf-8.
+fn base64_decode(nodes: &[Variable]) -> Result<Variable> {
+    let v = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("base64_decode"), err.to_string()))?;
+    let decoded = base64::decode(v)
+        .map_err(|err| Error::new_eval_internal(String::from("base64_decode"), err.to_string()))?;
+    bytes_to_variable("base64_decode", decoded)
+}
+
+/// Encodes Variable::String to lowercase hex.
+fn hex_encode(nodes: &[Variable]) -> Result<Variable> {
+    let v = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("hex"), err.to_string()))?;
+    Ok(Variable::String(hex::encode(v)))
+}
+
+/// Decodes hex Variable::String, decoded bytes must be valid utf-8.
+fn hex_decode(nodes: &[Variable]) -> Result<Variable> {
+    let v = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("hex_decode"), err.to_string()))?;
+    let decoded = hex::decode(v)
+        .map_err(|err| Error::new_eval_internal(String::from("hex_decode"), err.to_string()))?;
+    bytes_to_variable("hex_decode", decoded)
+}
+
+/// Percent-encodes Variable::String, so it can be used in url.
+fn url_encode(nodes: &[Variable]) -> Result<Variable> {
+    let v = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("url_encode"), err.to_string()))?;
+    Ok(Variable::String(urlencoding::encode(&v).into_owned()))
+}
+
+/// Decodes percent-encoded Variable::String, decoded bytes must be valid utf-8.
+fn url_decode(nodes: &[Variable]) -> Result<Variable> {
+    let v = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("url_decode"), err.to_string()))?;
+    let decoded = urlencoding::decode(&v)
+        .map_err(|err| Error::new_eval_internal(String::from("url_decode"), err.to_string()))?;
+    Ok(Variable::String(decoded.into_owned()))
+}
+
+/// Returns hex encoded SHA-256 digest of Variable::String.
+fn sha256(nodes: &[Variable]) -> Result<Variable> {
+    let v = parse_single_param::<String>(nodes)
+        .map_err(|err| Error::new_eval_internal(String::from("sha256"), err.to_string()))?;
+    Ok(Variable::String(hex::encode(Sha256::digest(v.as_bytes()))))
+}
+
+/// Signs message with a key using wanted algorithm, returns hex encoded signature.
+/// Arguments: algorithm name (sha256 or sha512), key, message.
+fn hmac(nodes: &[Variable]) -> Result<Variable> {
+    let mut iter = nodes.iter();
+    let alg = iter.next().unwrap().to_str()?.to_lowercase();
+    let key = iter.next().unwrap().to_str()?;
+    let message = iter.next().unwrap().to_str()?;
+
+    let signature = match alg.as_str() {
+        "sha256" => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                .map_err(|err| Error::new_eval_internal(String::from("hmac"), err.to_string()))?;
+            mac.update(message.as_bytes());
+            hex::encode(mac.finalize().into_bytes())
+        }
+        "sha512" => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(key.as_bytes())
+                .map_err(|err| Error::new_eval_internal(String::from("hmac"), err.to_string()))?;
+            mac.update(message.as_bytes());
+            hex::encode(mac.finalize().into_bytes())
+        }
+        _ => {
+            return Err(Error::new_eval_internal(
+                String::from("hmac"),
+                format!("unsupported algorithm: {}, wanted sha256 or sha512", alg),
+            ))
+        }
+    };
+    Ok(Variable::String(signature))
+}
+
+/// Translates decoded bytes into Variable::String.
+fn bytes_to_variable(operation: &str, bytes: Vec<u8>) -> Result<Variable> {
+    String::from_utf8(bytes)
+        .map(Variable::String)
+        .map_err(|err| Error::new_eval_internal(operation.to_string(), err.to_string()))
+}
+
 /// Parses single Variable to given type.
 fn parse_single_param<T>(nodes: &[Variable]) -> Result<T>
 where
@@ -0,0 +1,185 @@
+use super::input_schema::SchemaType;
+use super::process::Process;
+use super::variable::{serde_value_to_variable, Variable};
+use crate::error::types::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+/// Typed parameter of a Process, value is given per task and read in steps with PARAM(name).
+pub struct ParamDefinition {
+    pub name: String,
+
+    #[serde(rename = "type")]
+    pub param_type: SchemaType,
+
+    /// Value used when task does not provide one, parameter without default is required.
+    pub default: Option<Value>,
+}
+
+impl ParamDefinition {
+    /// Checks if value has declared type.
+    fn check(&self, value: &Value) -> Result<()> {
+        if !self.param_type.matches(value) {
+            return Err(Error::new_validation(
+                "Process",
+                format!(
+                    "param {} expected {:?}, got {:?}",
+                    self.name,
+                    self.param_type,
+                    SchemaType::of(value)
+                )
+                .as_str(),
+                "params",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Process {
+    /// Validates declared params, names have to be unique and defaults have to match types.
+    pub(crate) fn validate_params(&self) -> Result<()> {
+        let mut names = vec![];
+        for param in self.params.iter().flatten() {
+            if names.contains(&&param.name) {
+                return Err(Error::new_validation(
+                    "Process",
+                    format!("param {} is declared more than once", param.name).as_str(),
+                    "params",
+                ));
+            }
+            names.push(&param.name);
+            if let Some(default) = &param.default {
+                param.check(default)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Combines values given by task with declared defaults.
+    /// Fails on missing required params, undeclared params and values of wrong type.
+    pub fn resolve_params(
+        &self,
+        given: &HashMap<String, Value>,
+    ) -> Result<HashMap<String, Variable>> {
+        let declared = self.params.as_deref().unwrap_or_default();
+        if let Some(name) = given
+            .keys()
+            .find(|name| !declared.iter().any(|p| &&p.name == name))
+        {
+            return Err(Error::new_validation(
+                "Process",
+                format!("param {} is not declared", name).as_str(),
+                "params",
+            ));
+        }
+
+        let mut resolved = HashMap::new();
+        for param in declared {
+            let value = given
+                .get(&param.name)
+                .or(param.default.as_ref())
+                .ok_or_else(|| {
+                    Error::new_validation(
+                        "Process",
+                        format!("missing required param {}", param.name).as_str(),
+                        "params",
+                    )
+                })?;
+            param.check(value)?;
+            resolved.insert(param.name.clone(), serde_value_to_variable(value.clone()));
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::types::Error;
+    use crate::lang::process::Process;
+    use crate::lang::variable::Variable;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn process() -> Process {
+        serde_json::from_value(json!({
+            "name": "process",
+            "definitions": [{"steps": ["DEFINE(OUT, PARAM(symbol))"]}],
+            "params": [
+                {"name": "symbol", "type": "string"},
+                {"name": "limit", "type": "integer", "default": 10}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_params() {
+        let process = process();
+
+        let given = HashMap::from([(String::from("symbol"), json!("BTC"))]);
+        assert_eq!(
+            process.resolve_params(&given).unwrap(),
+            HashMap::from([
+                (
+                    String::from("symbol"),
+                    Variable::String(String::from("BTC"))
+                ),
+                (String::from("limit"), Variable::Int(10)),
+            ])
+        );
+
+        assert_eq!(
+            process.resolve_params(&HashMap::new()),
+            Err(Error::new_validation(
+                "Process",
+                "missing required param symbol",
+                "params"
+            ))
+        );
+
+        let given = HashMap::from([
+            (String::from("symbol"), json!("BTC")),
+            (String::from("currency"), json!("USD")),
+        ]);
+        assert_eq!(
+            process.resolve_params(&given),
+            Err(Error::new_validation(
+                "Process",
+                "param currency is not declared",
+                "params"
+            ))
+        );
+
+        let given = HashMap::from([
+            (String::from("symbol"), json!("BTC")),
+            (String::from("limit"), json!("10")),
+        ]);
+        assert_eq!(
+            process.resolve_params(&given),
+            Err(Error::new_validation(
+                "Process",
+                "param limit expected Integer, got String",
+                "params"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_params() {
+        let mut process = process();
+        assert!(process.validate_params().is_ok());
+
+        process.params.as_mut().unwrap()[1].default = Some(json!(true));
+        assert_eq!(
+            process.validate_params(),
+            Err(Error::new_validation(
+                "Process",
+                "param limit expected Integer, got Boolean",
+                "params"
+            ))
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use super::input_schema::InputSchema;
+use super::lexer::{Keyword, Lexer, Token};
+use super::params::ParamDefinition;
+use super::testing::ProcessTestCase;
 use crate::error::types::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -33,6 +37,15 @@ pub struct Definition {
     /// Subtrees will be run in a order of initialization.
     /// Implicit subtress will be deleted from 'SharedState' in order to prevent multiple runs.
     pub implicit_subtrees: Option<Vec<String>>,
+
+    /// Marks Definition to be run concurrently with neighbouring parallel Definitions.
+    /// Parallel Definition is evaluated on its own copy of variables and cannot use mounts.
+    #[serde(default)]
+    pub parallel: bool,
+
+    /// Variables that will be merged back into shared state after parallel Definition is run.
+    /// Required for parallel Definitions, merged in order of Definitions.
+    pub outputs: Option<Vec<String>>,
 }
 
 impl Definition {
@@ -46,7 +59,43 @@ impl Definition {
             steps,
             subtrees: None,
             implicit_subtrees: None,
+            parallel: false,
+            outputs: None,
+        }
+    }
+
+    fn collect_secrets(&self, names: &mut Vec<String>) {
+        for step in self.steps.iter().filter(|s| !s.is_empty()) {
+            // step that cannot be parsed fails when engine is built, it references no secrets.
+            let tokens = Lexer::new(step).make_tokens().unwrap_or_default();
+            for window in tokens.windows(3) {
+                if let [Token::Keyword(Keyword::Secret), Token::LeftBracket, Token::Var { value, .. }] =
+                    window
+                {
+                    names.push(value.clone());
+                }
+            }
         }
+        for subtree in self.subtrees.iter().flatten() {
+            subtree.definition.collect_secrets(names);
+        }
+    }
+
+    /// Checks if steps, including subtrees, read mounted resources.
+    fn uses_mounts(&self) -> bool {
+        let reads_mount = |step: &String| {
+            Lexer::new(step)
+                .make_tokens()
+                .unwrap_or_default()
+                .iter()
+                .any(|token| matches!(token, Token::Keyword(Keyword::ReadMountedToString)))
+        };
+        self.steps.iter().any(reads_mount)
+            || self
+                .subtrees
+                .iter()
+                .flatten()
+                .any(|subtree| subtree.definition.uses_mounts())
     }
 
     fn name(&self) -> String {
@@ -57,7 +106,7 @@ impl Definition {
     }
 
     /// Validates if Definition fields are valid.
-    fn validate(&self) -> Result<()> {
+    pub(crate) fn validate(&self) -> Result<()> {
         // steps cannot be empty.
         if self.steps.is_empty() {
             return Err(Error::new_validation(
@@ -66,6 +115,22 @@ impl Definition {
                 "steps",
             ));
         }
+        // parallel definition has to declare what should be merged back.
+        if self.parallel && self.outputs.as_ref().filter(|o| !o.is_empty()).is_none() {
+            return Err(Error::new_validation(
+                self.name().as_str(),
+                "Parallel definition must declare outputs",
+                "outputs",
+            ));
+        }
+        // parallel definition is evaluated on its own scope, which has no mounted readers.
+        if self.parallel && self.uses_mounts() {
+            return Err(Error::new_validation(
+                self.name().as_str(),
+                "Parallel definition cannot use mounts",
+                "steps",
+            ));
+        }
         if let Some(subtrees) = &self.subtrees {
             // validate nested subtrees.
             for subtree in subtrees {
@@ -110,6 +175,22 @@ pub struct Process {
 
     // set of mounts to perform.
     pub mounts: Option<Vec<MountOption>>,
+
+    // set of shared library definitions to import, e.g. "normalize@v2" or "normalize" for the latest version.
+    pub imports: Option<Vec<String>>,
+
+    // declarative test cases, run with `POST /process/test` or `cli test <file>`.
+    pub tests: Option<Vec<ProcessTestCase>>,
+
+    // schema that IN variable is checked against before process is run.
+    pub input_schema: Option<InputSchema>,
+
+    // if set, inputs rejected by input_schema are saved to quarantine store.
+    #[serde(default)]
+    pub quarantine_rejected: bool,
+
+    // typed params, values are given per task and read with PARAM keyword.
+    pub params: Option<Vec<ParamDefinition>>,
 }
 
 impl Process {
@@ -122,7 +203,23 @@ impl Process {
             name: name.into(),
             definitions,
             mounts,
+            imports: None,
+            tests: None,
+            input_schema: None,
+            quarantine_rejected: false,
+            params: None,
+        }
+    }
+
+    /// Returns sorted names of secrets used with SECRET keyword in all steps, including subtrees.
+    pub fn referenced_secrets(&self) -> Vec<String> {
+        let mut names = vec![];
+        for definition in &self.definitions {
+            definition.collect_secrets(&mut names);
         }
+        names.sort();
+        names.dedup();
+        names
     }
 
     pub fn try_to_string(&self) -> Result<String> {
@@ -146,6 +243,26 @@ impl Process {
             definition.validate()?;
         }
 
+        self.validate_parallel()?;
+        self.validate_params()
+    }
+
+    /// Validates parallel definitions, they cannot be used by a process that declares mounts.
+    pub(crate) fn validate_parallel(&self) -> Result<()> {
+        for definition in self.definitions.iter().filter(|d| d.parallel) {
+            if self
+                .mounts
+                .as_ref()
+                .is_some_and(|mounts| !mounts.is_empty())
+            {
+                return Err(Error::new_validation(
+                    "Process",
+                    "Parallel definitions cannot be used with mounts",
+                    "mounts",
+                ));
+            }
+            definition.validate()?;
+        }
         Ok(())
     }
 }
@@ -164,7 +281,7 @@ mod tests {
     use super::Process;
     use crate::{
         error::types::Error,
-        lang::process::{Definition, MountOption},
+        lang::process::{Definition, MountOption, SubTree},
     };
 
     #[test]
@@ -187,7 +304,7 @@ mod tests {
                 String::from("MOCK DEFINITION 1"),
                 String::from("MOCK DEFINITION 2"),
             ])],
-            mounts: None,
+            ..Default::default()
         };
 
         let process: Process = serde_json::from_str(content).expect("failed to deserialize");
@@ -226,6 +343,7 @@ mod tests {
                 path: String::from("p"),
                 mount_type: crate::lang::process::MountType::File,
             }]),
+            ..Default::default()
         };
 
         let process: Process = serde_json::from_str(content).expect("failed to deserialize");
@@ -237,14 +355,14 @@ mod tests {
         let valid_process = Process {
             name: String::from("test process"),
             definitions: vec![Definition::new(vec!["def1"])],
-            mounts: None,
+            ..Default::default()
         };
         assert_true!(valid_process.validate().is_ok());
 
         let invalid_process_empty_definitions = Process {
             name: String::from("test process"),
             definitions: vec![],
-            mounts: None,
+            ..Default::default()
         };
         assert_eq!(
             invalid_process_empty_definitions.validate(),
@@ -255,4 +373,82 @@ mod tests {
             ))
         )
     }
+
+    #[test]
+    fn test_validate_parallel_definition() {
+        let mut definition = Definition::new(vec!["def1"]);
+        definition.parallel = true;
+        assert_eq!(
+            definition.validate(),
+            Err(Error::new_validation(
+                "Definition",
+                "Parallel definition must declare outputs",
+                "outputs",
+            ))
+        );
+
+        definition.outputs = Some(vec![String::from("OUT")]);
+        assert_true!(definition.validate().is_ok());
+
+        definition.steps = vec![String::from("DEFINE(OUT, READMOUNTEDTOSTRING(file))")];
+        assert_eq!(
+            definition.validate(),
+            Err(Error::new_validation(
+                "Definition",
+                "Parallel definition cannot use mounts",
+                "steps",
+            ))
+        );
+
+        // mount read by a subtree is used by the definition too.
+        definition.steps = vec![String::from("RUNSUBTREE(read)")];
+        definition.subtrees = Some(vec![SubTree {
+            name: String::from("read"),
+            definition: Definition::new(vec!["DEFINE(OUT, READMOUNTEDTOSTRING(file))"]),
+        }]);
+        assert_true!(definition.validate().is_err());
+
+        definition.subtrees = None;
+        let mut process = Process::new(
+            "parallel",
+            vec![definition],
+            Some(vec![MountOption {
+                alias: String::from("file"),
+                path: String::from("path"),
+                mount_type: crate::lang::process::MountType::File,
+            }]),
+        );
+        assert_eq!(
+            process.validate_parallel(),
+            Err(Error::new_validation(
+                "Process",
+                "Parallel definitions cannot be used with mounts",
+                "mounts",
+            ))
+        );
+        process.mounts = None;
+        assert_true!(process.validate_parallel().is_ok());
+    }
+
+    #[test]
+    fn test_referenced_secrets() {
+        let mut definition = Definition::new(vec![
+            "DEFINE(token, SECRET(api_token))",
+            "DEFINE(OUT, VEC(SECRET(psql_password), SECRET(api_token)))",
+        ]);
+        definition.subtrees = Some(vec![SubTree {
+            name: String::from("subtree"),
+            definition: Definition::new(vec!["DEFINE(OUT, SECRET(nested))"]),
+        }]);
+        let process = Process::new("process", vec![definition], None);
+
+        assert_eq!(
+            process.referenced_secrets(),
+            vec![
+                String::from("api_token"),
+                String::from("nested"),
+                String::from("psql_password")
+            ]
+        );
+    }
 }
@@ -0,0 +1,215 @@
+use super::lexer::Keyword;
+use super::variable::Variable;
+use crate::error::types::{Error, Result};
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// Function implemented outside of the language that can be called from steps
+/// the same way as built-in keywords: MY_FUNCTION(GET(var), INT(1)).
+///
+/// Arguments are evaluated before being passed to the function.
+pub trait NativeFunction: Send + Sync {
+    /// Name under which function is available in steps, matched case-insensitively.
+    fn name(&self) -> &str;
+
+    /// Exact number of arguments that function takes.
+    fn arity(&self) -> usize;
+
+    /// Evaluates function with already evaluated arguments.
+    fn evaluate(&self, args: &[Variable]) -> Result<Variable>;
+}
+
+#[derive(Clone, Default)]
+/// Set of NativeFunctions registered by embedder.
+/// Lexer resolves identifiers against registry before treating them as variables.
+pub struct Registry {
+    functions: HashMap<String, Arc<dyn NativeFunction>>,
+}
+
+impl Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("functions", &self.functions.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers NativeFunction, fails if its name is taken by built-in keyword or other function.
+    pub fn register<F: NativeFunction + 'static>(&mut self, function: F) -> Result<()> {
+        let name = function.name().to_lowercase();
+        if name.is_empty() || Keyword::from_string(&name).is_some() {
+            return Err(Error::new_validation(
+                "Registry",
+                format!("Function name {} is empty or reserved", function.name()).as_str(),
+                "name",
+            ));
+        }
+        if self.functions.contains_key(&name) {
+            return Err(Error::new_validation(
+                "Registry",
+                format!("Function {} is already registered", function.name()).as_str(),
+                "name",
+            ));
+        }
+        self.functions.insert(name, Arc::new(function));
+        Ok(())
+    }
+
+    /// Returns registered NativeFunction by its name.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn NativeFunction>> {
+        self.functions.get(&name.to_lowercase()).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(&name.to_lowercase())
+    }
+
+    /// Checks arguments count and evaluates registered function.
+    pub(crate) fn evaluate(&self, name: &str, args: &[Variable]) -> Result<Variable> {
+        let function = self.get(name).ok_or_else(|| {
+            Error::new_eval_internal(
+                String::from("Registry::evaluate"),
+                format!("function {} is not registered", name),
+            )
+        })?;
+        if function.arity() != args.len() {
+            return Err(Error::new_eval_internal(
+                String::from("Registry::evaluate"),
+                format!(
+                    "function: {} - wanted {} arguments, got {}",
+                    name,
+                    function.arity(),
+                    args.len()
+                ),
+            ));
+        }
+        function.evaluate(args)
+    }
+}
+
+#[cfg(test)]
+/// Test fixture, doubles Int argument: DOUBLE(INT(2)).
+pub(crate) struct Double;
+
+#[cfg(test)]
+impl NativeFunction for Double {
+    fn name(&self) -> &str {
+        "DOUBLE"
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn evaluate(&self, args: &[Variable]) -> Result<Variable> {
+        match args[0] {
+            Variable::Int(i) => Ok(Variable::Int(i * 2)),
+            _ => Err(Error::new_eval_internal(
+                String::from("double"),
+                String::from("wanted Variable::Int"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Double, NativeFunction, Registry};
+    use crate::error::types::{Error, Result};
+    use crate::lang::engine::Engine;
+    use crate::lang::process::{Definition, Process};
+    use crate::lang::variable::Variable;
+
+    struct Shadow;
+
+    impl NativeFunction for Shadow {
+        fn name(&self) -> &str {
+            "add"
+        }
+
+        fn arity(&self) -> usize {
+            2
+        }
+
+        fn evaluate(&self, _: &[Variable]) -> Result<Variable> {
+            Ok(Variable::None)
+        }
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = Registry::new();
+        assert!(registry.register(Double).is_ok());
+        assert!(registry.contains("double"));
+        assert!(registry.register(Double).is_err());
+        assert!(registry.register(Shadow).is_err());
+    }
+
+    #[test]
+    fn test_engine_with_native_function() {
+        let process = Process::new(
+            "native",
+            vec![Definition::new(vec![
+                "DEFINE(OUT, DOUBLE(ADD(INT(1), INT(2))))",
+            ])],
+            None,
+        );
+
+        let mut engine = Engine::builder()
+            .register(Double)
+            .unwrap()
+            .build(Variable::None, process)
+            .unwrap();
+        engine.fire().unwrap();
+        assert_eq!(engine.get("OUT").unwrap(), &Variable::Int(6));
+    }
+
+    #[test]
+    fn test_variable_named_like_native_function() {
+        let process = Process::new(
+            "native",
+            vec![Definition::new(vec![
+                "DEFINE(double, INT(4))",
+                "DEFINE(OUT, DOUBLE(GET(double)))",
+            ])],
+            None,
+        );
+
+        let mut engine = Engine::builder()
+            .register(Double)
+            .unwrap()
+            .build(Variable::None, process)
+            .unwrap();
+        engine.fire().unwrap();
+        assert_eq!(engine.get("double").unwrap(), &Variable::Int(4));
+        assert_eq!(engine.get("OUT").unwrap(), &Variable::Int(8));
+    }
+
+    #[test]
+    fn test_native_function_arguments_count() {
+        let process = Process::new(
+            "native",
+            vec![Definition::new(vec!["DEFINE(OUT, DOUBLE(INT(1), INT(2)))"])],
+            None,
+        );
+
+        let mut engine = Engine::builder()
+            .register(Double)
+            .unwrap()
+            .build(Variable::None, process)
+            .unwrap();
+        assert_eq!(
+            engine.fire(),
+            Err(Error::new_eval_internal(
+                String::from("Registry::evaluate"),
+                String::from("function: double - wanted 1 arguments, got 2"),
+            ))
+        );
+    }
+}
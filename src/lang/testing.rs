@@ -0,0 +1,187 @@
+use super::engine::Engine;
+use super::process::{MountOption, Process};
+use super::registry::Registry;
+use super::variable::{serde_value_to_variable, Variable};
+use crate::core::task::InputData;
+use crate::error::types::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+/// Single declarative test case of a Process.
+/// Either expected_out or expected_error should be set, case without any of them
+/// passes as long as Process evaluates without error.
+pub struct ProcessTestCase {
+    pub name: String,
+
+    /// Data passed to Process as IN (and initial OUT) variable.
+    pub input: InputData,
+
+    /// Mounts used instead of Process's ones, handy for pointing to fixtures.
+    pub mounts: Option<Vec<MountOption>>,
+
+    /// Variables defined before Process is run.
+    pub state: Option<HashMap<String, Value>>,
+
+    /// Values of params declared by Process, the same as given on task creation.
+    pub params: Option<HashMap<String, Value>>,
+
+    /// Expected value of OUT variable after evaluation.
+    pub expected_out: Option<Value>,
+
+    /// Expected part of error message, case fails if Process evaluates successfully.
+    pub expected_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TestCaseResult {
+    pub name: String,
+    pub passed: bool,
+    /// Describes why test case failed.
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+/// Pass/fail report of all Process's test cases.
+pub struct TestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub results: Vec<TestCaseResult>,
+}
+
+impl TestReport {
+    pub fn success(&self) -> bool {
+        self.failed == 0
+    }
+
+    fn push(&mut self, result: TestCaseResult) {
+        if result.passed {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.results.push(result);
+    }
+}
+
+impl ProcessTestCase {
+    /// Runs Process against test case and compares result with expectations.
+    fn run(&self, process: &Process, registry: &Registry) -> TestCaseResult {
+        let (passed, message) = match (self.evaluate(process, registry), &self.expected_error) {
+            (Ok(out), Some(expected)) => (
+                false,
+                Some(format!(
+                    "expected error containing '{expected}', got OUT: {out}"
+                )),
+            ),
+            (Ok(out), None) => match &self.expected_out {
+                Some(expected) if expected != &out => {
+                    (false, Some(format!("expected OUT: {expected}, got: {out}")))
+                }
+                _ => (true, None),
+            },
+            (Err(err), Some(expected)) if err.to_string().contains(expected.as_str()) => {
+                (true, None)
+            }
+            (Err(err), _) => (false, Some(format!("unexpected error: {err}"))),
+        };
+
+        TestCaseResult {
+            name: self.name.clone(),
+            passed,
+            message,
+        }
+    }
+
+    fn evaluate(&self, process: &Process, registry: &Registry) -> Result<Value> {
+        let mut process = process.clone();
+        process.tests = None;
+        if self.mounts.is_some() {
+            process.mounts = self.mounts.clone();
+        }
+
+        let input = Variable::from(self.input.clone());
+        process.validate_input(&input)?;
+        let params = process.resolve_params(&self.params.clone().unwrap_or_default())?;
+
+        let mut engine = Engine::builder()
+            .with_registry(registry.clone())
+            .with_params(params)
+            .build(input, process)?;
+        for (name, value) in self.state.clone().unwrap_or_default() {
+            engine.set(name, serde_value_to_variable(value));
+        }
+        engine.fire()?;
+
+        Ok(engine
+            .get("OUT")
+            .cloned()
+            .map(Value::from)
+            .unwrap_or_default())
+    }
+}
+
+impl Process {
+    /// Runs all declared test cases of a Process.
+    pub fn run_tests(&self, registry: &Registry) -> TestReport {
+        let mut report = TestReport::default();
+        for case in self.tests.as_ref().unwrap_or(&vec![]) {
+            debug!("running {} test case of {} process", case.name, self.name);
+            report.push(case.run(self, registry));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lang::process::Process;
+    use crate::lang::registry::Registry;
+
+    #[test]
+    fn test_run_tests() {
+        let content = r#"
+        {
+            "name": "test process",
+            "definitions": [
+                {
+                    "steps": [
+                        "DEFINE(OUT, ADD(EXTRACT(GET(IN), value), GET(offset)))"
+                    ]
+                }
+            ],
+            "tests": [
+                {
+                    "name": "adds offset",
+                    "input": {"Json": {"value": 1}},
+                    "state": {"offset": 2},
+                    "expected_out": 3
+                },
+                {
+                    "name": "wrong expectation",
+                    "input": {"Json": {"value": 1}},
+                    "state": {"offset": 2},
+                    "expected_out": 4
+                },
+                {
+                    "name": "missing field",
+                    "input": {"Json": {}},
+                    "state": {"offset": 2},
+                    "expected_error": "json does not have value field"
+                }
+            ]
+        }"#;
+        let process: Process = serde_json::from_str(content).unwrap();
+
+        let report = process.run_tests(&Registry::default());
+        assert_eq!(report.passed, 2);
+        assert_eq!(report.failed, 1);
+        assert!(!report.success());
+        assert_eq!(report.results[1].name, "wrong expectation");
+        assert_eq!(
+            report.results[1].message,
+            Some(String::from("expected OUT: 4, got: 3"))
+        );
+    }
+}
@@ -159,7 +159,19 @@ impl From<InputData> for Variable {
     }
 }
 
-fn serde_value_to_variable(v: Value) -> Variable {
+impl From<Variable> for InputData {
+    /// Translates Variable into InputData, so evaluated data can be an input of another task.
+    fn from(variable: Variable) -> Self {
+        match variable {
+            Variable::String(s) => InputData::String(s),
+            Variable::Vector(v) => InputData::Vector(v.into_iter().map(Self::from).collect()),
+            Variable::Json(j) => InputData::Json(j),
+            other => InputData::Json(Value::from(other)),
+        }
+    }
+}
+
+pub fn serde_value_to_variable(v: Value) -> Variable {
     if v.is_boolean() {
         return Variable::Bool(v.as_bool().unwrap());
     } else if v.is_string() {
@@ -182,6 +194,29 @@ fn serde_value_to_variable(v: Value) -> Variable {
     Variable::None
 }
 
+impl From<Variable> for Value {
+    /// Translates Variable into serde_json::Value, Variable::None becomes null.
+    fn from(variable: Variable) -> Self {
+        match variable {
+            Variable::None => Value::Null,
+            Variable::Bool(b) => Value::Bool(b),
+            Variable::Int(i) => Value::from(i as i64),
+            // go through string representation, so FLOAT(2.1) becomes 2.1 instead of 2.0999999046325684.
+            Variable::Float(f) => f
+                .to_string()
+                .parse::<f64>()
+                .map(Value::from)
+                .unwrap_or(Value::Null),
+            Variable::String(s) => Value::String(s),
+            Variable::Vector(v) => Value::Array(v.into_iter().map(Self::from).collect()),
+            Variable::Object(o) => {
+                Value::Object(o.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+            Variable::Json(j) => j,
+        }
+    }
+}
+
 pub fn value_object_to_variable_object(v: Value) -> Variable {
     let m: HashMap<String, Variable> = v
         .as_object()
@@ -510,4 +545,20 @@ mod tests {
         );
         assert_eq!(v1.extract(&index, true).unwrap(), Variable::Int(1));
     }
+
+    #[test]
+    fn test_variable_to_value() {
+        let variable = Variable::Vector(vec![
+            Variable::None,
+            Variable::Bool(true),
+            Variable::Int(1),
+            Variable::Float(2.1),
+            Variable::String(String::from("s")),
+            Variable::Object(HashMap::from([(String::from("k"), Variable::Int(2))])),
+        ]);
+        assert_eq!(
+            Value::from(variable),
+            serde_json::json!([null, true, 1, 2.1, "s", {"k": 2}])
+        );
+    }
 }
@@ -1,18 +1,21 @@
 extern crate datatracker_rust;
 use datatracker_rust::core::channels::ChannelsManager;
 use datatracker_rust::core::manager::TaskCommand;
+use datatracker_rust::core::secret::MasterKey;
 use datatracker_rust::core::task::TrackingTask;
 use datatracker_rust::core::tracker::Tracker;
 use datatracker_rust::core::types::{Direction, Hook};
 use datatracker_rust::lang::process::{Definition, Process};
+use datatracker_rust::lang::registry::Registry;
 use datatracker_rust::persistance::interface::Db;
 use datatracker_rust::persistance::sqlite::{establish_connection, SqliteClient};
-use datatracker_rust::server::build::rocket;
+use datatracker_rust::server::build::rocket_with_registry;
 use datatracker_rust::server::proto::StatsService;
 use datatracker_rust::server::task::TaskKindRequest;
 use datatracker_rust::stats::stats_server::StatsServer;
 use datatracker_rust::wrap::StdoutAPI;
 use diesel_migrations::embed_migrations;
+use std::env;
 use tokio::join;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::channel;
@@ -35,8 +38,13 @@ async fn main() {
 
     let api = StdoutAPI::default();
     let pers = SqliteClient::new(establish_connection());
-    let db = Db::new(Box::new(pers));
+    let master_key_path =
+        env::var("MASTER_KEY_PATH").unwrap_or_else(|_| String::from("master.key"));
+    let db = Db::new(Box::new(pers))
+        .with_master_key(MasterKey::load_or_create(master_key_path).expect("invalid master key"));
     let channels_manager = ChannelsManager::default();
+    // native functions available in tasks, process tests and dry runs.
+    let registry = Registry::default();
 
     let mut tracker = Tracker::new(
         api,
@@ -46,7 +54,8 @@ async fn main() {
         shutdown_recv,
         shutdown_notify.clone(),
         cmd_receive,
-    );
+    )
+    .with_registry(registry.clone());
     info!("initialized");
 
     let process = Process::new(
@@ -99,7 +108,7 @@ async fn main() {
             .unwrap();
     });
 
-    let rocket = rocket(cmd_send, tt_send, db);
+    let rocket = rocket_with_registry(cmd_send, tt_send, db, registry);
     let (_, _, _) = join!(rocket.launch(), start, rpc_server_start);
 }
 
@@ -0,0 +1,63 @@
+use crate::core::alert::Alert;
+use crate::error::types::{Error, Result};
+use crate::schema::alerts;
+use chrono::{TimeZone, Utc};
+use diesel::{Insertable, Queryable};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable)]
+#[table_name = "alerts"]
+pub struct AlertModel {
+    pub task_id: String,
+    pub rule: String,
+    pub severity: String, // json of Severity.
+    pub status: String,   // json of AlertStatus.
+    pub value: String,    // json of output.
+    pub fired_at: chrono::NaiveDateTime,
+    pub notified_at: Option<chrono::NaiveDateTime>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+}
+
+impl AlertModel {
+    pub fn from_alert(alert: &Alert) -> Self {
+        Self {
+            task_id: alert.task_id.to_string(),
+            rule: alert.rule.clone(),
+            severity: serde_json::json!(alert.severity).to_string(),
+            status: serde_json::json!(alert.status).to_string(),
+            value: alert.value.to_string(),
+            fired_at: alert.fired_at.naive_utc(),
+            notified_at: alert.notified_at.map(|at| at.naive_utc()),
+            resolved_at: alert.resolved_at.map(|at| at.naive_utc()),
+        }
+    }
+
+    pub fn to_alert(&self) -> Result<Alert> {
+        let parse_json = |value: &str, field: &str| {
+            serde_json::from_str(value).map_err(|err| {
+                Error::new_persistance_parsing(
+                    format!("failed to parse {}", field),
+                    err.to_string(),
+                    field.to_string(),
+                )
+            })
+        };
+        Ok(Alert {
+            task_id: Uuid::from_str(&self.task_id).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse uuid"),
+                    err.to_string(),
+                    String::from("task_id"),
+                )
+            })?,
+            rule: self.rule.clone(),
+            severity: parse_json(&self.severity, "severity")?,
+            status: parse_json(&self.status, "status")?,
+            value: parse_json(&self.value, "value")?,
+            fired_at: Utc.from_utc_datetime(&self.fired_at),
+            notified_at: self.notified_at.map(|at| Utc.from_utc_datetime(&at)),
+            resolved_at: self.resolved_at.map(|at| Utc.from_utc_datetime(&at)),
+        })
+    }
+}
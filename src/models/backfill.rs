@@ -0,0 +1,57 @@
+use crate::core::backfill::Backfill;
+use crate::error::types::{Error, Result};
+use crate::schema::backfills;
+use chrono::{TimeZone, Utc};
+use diesel::{Insertable, Queryable};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable)]
+#[table_name = "backfills"]
+pub struct BackfillModel {
+    pub task_id: String,
+    pub total: i32,
+    pub processed: i32,
+    pub failed: i32,
+    pub status: String, // json of BackfillStatus.
+    pub started_at: chrono::NaiveDateTime,
+    pub finished_at: Option<chrono::NaiveDateTime>,
+}
+
+impl BackfillModel {
+    pub fn from_backfill(backfill: &Backfill) -> Self {
+        Self {
+            task_id: backfill.task_id.to_string(),
+            total: backfill.total,
+            processed: backfill.processed,
+            failed: backfill.failed,
+            status: serde_json::json!(backfill.status).to_string(),
+            started_at: backfill.started_at.naive_utc(),
+            finished_at: backfill.finished_at.map(|at| at.naive_utc()),
+        }
+    }
+
+    pub fn to_backfill(&self) -> Result<Backfill> {
+        Ok(Backfill {
+            task_id: Uuid::from_str(&self.task_id).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse uuid"),
+                    err.to_string(),
+                    String::from("task_id"),
+                )
+            })?,
+            total: self.total,
+            processed: self.processed,
+            failed: self.failed,
+            status: serde_json::from_str(&self.status).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse status"),
+                    err.to_string(),
+                    String::from("status"),
+                )
+            })?,
+            started_at: Utc.from_utc_datetime(&self.started_at),
+            finished_at: self.finished_at.map(|at| Utc.from_utc_datetime(&at)),
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use crate::core::dead_letter::DeadLetter;
+use crate::core::task::InputData;
+use crate::error::types::{Error, Result};
+use crate::schema::dead_letters;
+use chrono::{TimeZone, Utc};
+use diesel::{Insertable, Queryable};
+use std::convert::TryFrom;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable)]
+#[table_name = "dead_letters"]
+pub struct DeadLetterModel {
+    pub id: String,
+    pub task_id: String,
+    pub input: String, // json of InputData.
+    pub phase: String, // json of RetryPhase.
+    pub error: String,
+    pub attempts: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl DeadLetterModel {
+    pub fn from_dead_letter(dl: &DeadLetter) -> Result<Self> {
+        Ok(Self {
+            id: dl.id.to_string(),
+            task_id: dl.task_id.to_string(),
+            input: dl.input.try_to_string()?,
+            phase: serde_json::json!(dl.phase).to_string(),
+            error: dl.error.clone(),
+            attempts: dl.attempts,
+            created_at: dl.created_at.naive_utc(),
+        })
+    }
+
+    pub fn to_dead_letter(&self) -> Result<DeadLetter> {
+        let parse_uuid = |value: &str, field: &str| {
+            Uuid::from_str(value).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse uuid"),
+                    err.to_string(),
+                    field.to_string(),
+                )
+            })
+        };
+        Ok(DeadLetter {
+            id: parse_uuid(&self.id, "id")?,
+            task_id: parse_uuid(&self.task_id, "task_id")?,
+            input: InputData::try_from(self.input.as_str())?,
+            phase: serde_json::from_str(&self.phase).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse phase"),
+                    err.to_string(),
+                    String::from("phase"),
+                )
+            })?,
+            error: self.error.clone(),
+            attempts: self.attempts,
+            created_at: Utc.from_utc_datetime(&self.created_at),
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use crate::core::dedup::Fingerprint;
+use crate::error::types::{Error, Result};
+use crate::schema::fingerprints;
+use diesel::{Insertable, Queryable};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable)]
+#[table_name = "fingerprints"]
+pub struct FingerprintModel {
+    pub task_id: String,
+    pub hash: String,
+    pub numbers: String, // json of numbers of the output.
+}
+
+impl FingerprintModel {
+    pub fn from_fingerprint(fingerprint: &Fingerprint) -> Self {
+        Self {
+            task_id: fingerprint.task_id.to_string(),
+            hash: fingerprint.hash.clone(),
+            numbers: serde_json::json!(fingerprint.numbers).to_string(),
+        }
+    }
+
+    pub fn to_fingerprint(&self) -> Result<Fingerprint> {
+        Ok(Fingerprint {
+            task_id: Uuid::from_str(&self.task_id).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse uuid"),
+                    err.to_string(),
+                    String::from("task_id"),
+                )
+            })?,
+            hash: self.hash.clone(),
+            numbers: serde_json::from_str(&self.numbers).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse numbers"),
+                    err.to_string(),
+                    String::from("numbers"),
+                )
+            })?,
+        })
+    }
+}
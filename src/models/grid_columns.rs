@@ -0,0 +1,30 @@
+use crate::error::types::{Error, Result};
+use crate::schema::grid_columns;
+use diesel::{Insertable, Queryable};
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable)]
+#[table_name = "grid_columns"]
+pub struct GridColumnsModel {
+    pub task_id: String,
+    pub columns: String, // json of keys of written objects.
+}
+
+impl GridColumnsModel {
+    pub fn new(task_id: Uuid, columns: &[String]) -> Self {
+        Self {
+            task_id: task_id.to_string(),
+            columns: serde_json::json!(columns).to_string(),
+        }
+    }
+
+    pub fn to_columns(&self) -> Result<Vec<String>> {
+        serde_json::from_str(&self.columns).map_err(|err| {
+            Error::new_persistance_parsing(
+                String::from("failed to parse columns"),
+                err.to_string(),
+                String::from("columns"),
+            )
+        })
+    }
+}
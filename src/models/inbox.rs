@@ -0,0 +1,47 @@
+use crate::core::inbox::InboxEntry;
+use crate::core::task::InputData;
+use crate::error::types::{Error, Result};
+use crate::schema::inbox;
+use chrono::{TimeZone, Utc};
+use diesel::{Insertable, Queryable};
+use std::convert::TryFrom;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable)]
+#[table_name = "inbox"]
+pub struct InboxModel {
+    pub id: String,
+    pub task_id: String,
+    pub input: String, // json of InputData.
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl InboxModel {
+    pub fn from_inbox_entry(entry: &InboxEntry) -> Result<Self> {
+        Ok(Self {
+            id: entry.id.to_string(),
+            task_id: entry.task_id.to_string(),
+            input: entry.input.try_to_string()?,
+            created_at: entry.created_at.naive_utc(),
+        })
+    }
+
+    pub fn to_inbox_entry(&self) -> Result<InboxEntry> {
+        let parse_uuid = |value: &str, field: &str| {
+            Uuid::from_str(value).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse uuid"),
+                    err.to_string(),
+                    field.to_string(),
+                )
+            })
+        };
+        Ok(InboxEntry {
+            id: parse_uuid(&self.id, "id")?,
+            task_id: parse_uuid(&self.task_id, "task_id")?,
+            input: InputData::try_from(self.input.as_str())?,
+            created_at: Utc.from_utc_datetime(&self.created_at),
+        })
+    }
+}
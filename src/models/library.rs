@@ -0,0 +1,42 @@
+use crate::error::types::{Error, Result};
+use crate::lang::library::LibraryDefinition;
+use crate::schema::library;
+use diesel::{Insertable, Queryable};
+
+#[derive(Queryable, Insertable)]
+#[table_name = "library"]
+pub struct LibraryModel {
+    pub name: String,
+    pub version: i32,
+    pub definition: String, // json of Definition.
+}
+
+impl LibraryModel {
+    pub fn from_library_definition(ld: &LibraryDefinition) -> Result<Self> {
+        Ok(Self {
+            name: ld.name.clone(),
+            version: ld.version,
+            definition: serde_json::to_string(&ld.definition).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to serialize definition"),
+                    err.to_string(),
+                    String::from("definition"),
+                )
+            })?,
+        })
+    }
+
+    pub fn to_library_definition(&self) -> Result<LibraryDefinition> {
+        Ok(LibraryDefinition {
+            name: self.name.clone(),
+            version: self.version,
+            definition: serde_json::from_str(&self.definition).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to deserialize definition"),
+                    err.to_string(),
+                    String::from("definition"),
+                )
+            })?,
+        })
+    }
+}
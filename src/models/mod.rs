@@ -1,3 +1,12 @@
+pub mod alert;
+pub mod backfill;
+pub mod dead_letter;
+pub mod fingerprint;
+pub mod grid_columns;
+pub mod inbox;
+pub mod library;
 pub mod location;
+pub mod quarantine;
 pub mod report;
+pub mod secret;
 pub mod task;
@@ -0,0 +1,50 @@
+use crate::core::quarantine::QuarantinedInput;
+use crate::core::task::InputData;
+use crate::error::types::{Error, Result};
+use crate::schema::quarantine;
+use chrono::{TimeZone, Utc};
+use diesel::{Insertable, Queryable};
+use std::convert::TryFrom;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable)]
+#[table_name = "quarantine"]
+pub struct QuarantineModel {
+    pub id: String,
+    pub task_id: String,
+    pub input: String, // json of InputData.
+    pub error: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl QuarantineModel {
+    pub fn from_quarantined_input(qi: &QuarantinedInput) -> Result<Self> {
+        Ok(Self {
+            id: qi.id.to_string(),
+            task_id: qi.task_id.to_string(),
+            input: qi.input.try_to_string()?,
+            error: qi.error.clone(),
+            created_at: qi.created_at.naive_utc(),
+        })
+    }
+
+    pub fn to_quarantined_input(&self) -> Result<QuarantinedInput> {
+        let parse_uuid = |value: &str, field: &str| {
+            Uuid::from_str(value).map_err(|err| {
+                Error::new_persistance_parsing(
+                    String::from("failed to parse uuid"),
+                    err.to_string(),
+                    field.to_string(),
+                )
+            })
+        };
+        Ok(QuarantinedInput {
+            id: parse_uuid(&self.id, "id")?,
+            task_id: parse_uuid(&self.task_id, "task_id")?,
+            input: InputData::try_from(self.input.as_str())?,
+            error: self.error.clone(),
+            created_at: Utc.from_utc_datetime(&self.created_at),
+        })
+    }
+}
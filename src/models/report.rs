@@ -10,6 +10,8 @@ pub struct ReportModel {
     pub phases: String,
     pub failed: bool,
     pub start: chrono::NaiveDateTime,
+    pub error: Option<String>,
+    pub items: Option<String>, // json of batched inputs outcomes.
 }
 
 impl ReportModel {
@@ -19,6 +21,8 @@ impl ReportModel {
             phases: json!(report.phases).to_string(),
             failed: !report.success,
             start: report.start.naive_utc(),
+            error: report.error.clone(),
+            items: (!report.items.is_empty()).then(|| json!(report.items).to_string()),
         }
     }
 }
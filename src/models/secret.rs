@@ -0,0 +1,11 @@
+use crate::schema::secrets;
+use diesel::{Insertable, Queryable};
+
+#[derive(Queryable, Insertable, Clone)]
+#[table_name = "secrets"]
+/// Encrypted secret, value can be read only with MasterKey it was encrypted with.
+pub struct SecretModel {
+    pub name: String,
+    pub nonce: String,      // base64 encoded.
+    pub ciphertext: String, // base64 encoded.
+}
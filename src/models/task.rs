@@ -17,7 +17,20 @@ pub struct TaskModel {
     pub process: String,       // definition of handling data.
     pub input: Option<String>, // json of input definition.
     pub status: State,
-    pub kind: String, // json of TaskKind definition.
+    pub kind: String,                           // json of TaskKind definition.
+    pub params: Option<String>,                 // json of process params values.
+    pub invocations: Option<i32>,               // number of invocations left.
+    pub ends_at: Option<chrono::NaiveDateTime>, // date after which task is finished.
+    pub retry_policy: Option<String>,           // json of RetryPolicy.
+    pub buffer: Option<String>,                 // json of BufferConfig.
+    pub dropped_inputs: i64,                    // number of inputs dropped on buffer overflow.
+    pub batch: Option<String>,                  // json of BatchConfig.
+    pub active_window: Option<String>,          // json of ActiveWindow.
+    pub timestamp_format: String,               // strftime format of timestamp.
+    pub timestamp_timezone: String,             // timezone of timestamp.
+    pub write_mode: String,                     // json of WriteMode.
+    pub dedup: Option<String>,                  // json of DedupConfig.
+    pub alerts: Option<String>,                 // json of AlertConfig.
 }
 
 impl TaskModel {
@@ -36,6 +49,37 @@ impl TaskModel {
             process: tt.process.try_to_string().unwrap_or_default(),
             input: tt.input.as_ref().map(|f| f.to_json()),
             kind: tt.kind_request.to_json(),
+            params: (!tt.params.is_empty()).then(|| serde_json::json!(tt.params).to_string()),
+            invocations: tt.invocations,
+            ends_at: tt.ends_at.map(|ends_at| ends_at.naive_utc()),
+            retry_policy: tt
+                .retry_policy
+                .as_ref()
+                .map(|policy| serde_json::json!(policy).to_string()),
+            buffer: tt
+                .buffer
+                .as_ref()
+                .map(|buffer| serde_json::json!(buffer).to_string()),
+            dropped_inputs: tt.dropped_inputs,
+            batch: tt
+                .batch
+                .as_ref()
+                .map(|batch| serde_json::json!(batch).to_string()),
+            active_window: tt
+                .active_window
+                .as_ref()
+                .map(|window| serde_json::json!(window).to_string()),
+            timestamp_format: tt.timestamp_format.clone(),
+            timestamp_timezone: tt.timestamp_timezone.clone(),
+            write_mode: tt.write_mode.to_json(),
+            dedup: tt
+                .dedup
+                .as_ref()
+                .map(|dedup| serde_json::json!(dedup).to_string()),
+            alerts: tt
+                .alerts
+                .as_ref()
+                .map(|alerts| serde_json::json!(alerts).to_string()),
         }
     }
 }
@@ -1,7 +1,12 @@
 use crate::{
-    core::{handler::Report, task::TrackingTask, types::State},
+    core::{
+        alert::Alert, backfill::Backfill, dead_letter::DeadLetter, dedup::Fingerprint,
+        handler::Report, inbox::InboxEntry, quarantine::QuarantinedInput, task::TrackingTask,
+        types::State,
+    },
     error::types::Error,
-    models::report::ReportModel,
+    lang::library::LibraryDefinition,
+    models::{report::ReportModel, secret::SecretModel},
 };
 
 use super::interface::{PResult, Persistance};
@@ -15,6 +20,16 @@ pub struct InMemoryPersistance {
     pub tasks: HashMap<Uuid, TrackingTask>,
     last_report: i32,
     pub reports: HashMap<i32, ReportModel>,
+    pub library: HashMap<(String, i32), LibraryDefinition>,
+    pub library_versions: HashMap<String, i32>,
+    pub quarantine: Vec<QuarantinedInput>,
+    pub dead_letters: Vec<DeadLetter>,
+    pub inbox: Vec<InboxEntry>,
+    pub fingerprints: HashMap<Uuid, Fingerprint>,
+    pub columns: HashMap<Uuid, Vec<String>>,
+    pub alerts: HashMap<(Uuid, String), Alert>,
+    pub backfills: HashMap<Uuid, Backfill>,
+    pub secrets: HashMap<String, SecretModel>,
 }
 
 impl InMemoryPersistance {
@@ -63,6 +78,31 @@ impl Persistance for InMemoryPersistance {
         Ok(())
     }
 
+    fn update_task(&mut self, task: &TrackingTask) -> PResult<()> {
+        self.tasks.entry(task.id).and_modify(|tt| {
+            tt.name = task.name.clone();
+            tt.process = task.process.clone();
+            tt.kind_request = task.kind_request.clone();
+            tt.spreadsheet_id = task.spreadsheet_id.clone();
+            tt.sheet = task.sheet.clone();
+        });
+        Ok(())
+    }
+
+    fn update_task_invocations(&mut self, uuid: Uuid, invocations: Option<i32>) -> PResult<()> {
+        self.tasks
+            .entry(uuid)
+            .and_modify(|tt| tt.invocations = invocations);
+        Ok(())
+    }
+
+    fn update_task_dropped_inputs(&mut self, uuid: Uuid, dropped_inputs: i64) -> PResult<()> {
+        self.tasks
+            .entry(uuid)
+            .and_modify(|tt| tt.dropped_inputs = dropped_inputs);
+        Ok(())
+    }
+
     fn delete_task(&mut self, uuid: Uuid) -> PResult<()> {
         self.tasks.remove(&uuid).ok_or_else(|| {
             Error::new_persistance_internal(
@@ -99,4 +139,206 @@ impl Persistance for InMemoryPersistance {
                 .collect(),
         ))
     }
+
+    fn save_library_definition(&mut self, definition: &LibraryDefinition) -> PResult<()> {
+        self.library.insert(
+            (definition.name.clone(), definition.version),
+            definition.clone(),
+        );
+        Ok(())
+    }
+
+    fn next_library_version(&mut self, name: &str) -> PResult<i32> {
+        let last_version = self.library_versions.entry(name.to_string()).or_insert(0);
+        *last_version += 1;
+        Ok(*last_version)
+    }
+
+    fn read_library_definition(
+        &mut self,
+        name: &str,
+        version: Option<i32>,
+    ) -> PResult<LibraryDefinition> {
+        self.library
+            .values()
+            .filter(|ld| ld.name == name && (version.is_none() || version == Some(ld.version)))
+            .max_by_key(|ld| ld.version)
+            .cloned()
+            .ok_or_else(|| {
+                Error::new_persistance_internal(
+                    String::from("could not find library definition"),
+                    String::default(),
+                )
+            })
+    }
+
+    fn list_library_definitions(&mut self) -> PResult<Vec<LibraryDefinition>> {
+        Ok(self.library.values().cloned().collect())
+    }
+
+    fn delete_library_definition(&mut self, name: &str, version: i32) -> PResult<()> {
+        self.library
+            .remove(&(name.to_string(), version))
+            .ok_or_else(|| {
+                Error::new_persistance_internal(
+                    String::from("there's no library definition to delete"),
+                    String::default(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn save_quarantined_input(&mut self, input: &QuarantinedInput) -> PResult<()> {
+        self.quarantine.push(input.clone());
+        Ok(())
+    }
+
+    fn read_quarantined_inputs(&mut self, task_id: Uuid) -> PResult<Vec<QuarantinedInput>> {
+        Ok(self
+            .quarantine
+            .iter()
+            .filter(|qi| qi.task_id == task_id)
+            .cloned()
+            .collect())
+    }
+
+    fn save_dead_letter(&mut self, dead_letter: &DeadLetter) -> PResult<()> {
+        self.dead_letters.retain(|dl| dl.id != dead_letter.id);
+        self.dead_letters.push(dead_letter.clone());
+        Ok(())
+    }
+
+    fn read_dead_letters(&mut self, task_id: Uuid) -> PResult<Vec<DeadLetter>> {
+        Ok(self
+            .dead_letters
+            .iter()
+            .filter(|dl| dl.task_id == task_id)
+            .cloned()
+            .collect())
+    }
+
+    fn read_dead_letter(&mut self, id: Uuid) -> PResult<DeadLetter> {
+        self.dead_letters
+            .iter()
+            .find(|dl| dl.id == id)
+            .cloned()
+            .ok_or_else(|| {
+                Error::new_persistance_internal(
+                    String::from("could not find dead letter"),
+                    String::default(),
+                )
+            })
+    }
+
+    fn delete_dead_letter(&mut self, id: Uuid) -> PResult<()> {
+        let len = self.dead_letters.len();
+        self.dead_letters.retain(|dl| dl.id != id);
+        if self.dead_letters.len() == len {
+            return Err(Error::new_persistance_internal(
+                String::from("there's no dead letter to delete"),
+                String::default(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn save_inbox_entry(&mut self, entry: &InboxEntry) -> PResult<()> {
+        self.inbox.push(entry.clone());
+        Ok(())
+    }
+
+    fn read_inbox(&mut self, task_id: Uuid) -> PResult<Vec<InboxEntry>> {
+        Ok(self
+            .inbox
+            .iter()
+            .filter(|entry| entry.task_id == task_id)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_inbox_entry(&mut self, id: Uuid) -> PResult<()> {
+        self.inbox.retain(|entry| entry.id != id);
+        Ok(())
+    }
+
+    fn save_fingerprint(&mut self, fingerprint: &Fingerprint) -> PResult<()> {
+        self.fingerprints
+            .insert(fingerprint.task_id, fingerprint.clone());
+        Ok(())
+    }
+
+    fn read_fingerprint(&mut self, task_id: Uuid) -> PResult<Option<Fingerprint>> {
+        Ok(self.fingerprints.get(&task_id).cloned())
+    }
+
+    fn save_columns(&mut self, task_id: Uuid, columns: &[String]) -> PResult<()> {
+        self.columns.insert(task_id, columns.to_vec());
+        Ok(())
+    }
+
+    fn read_columns(&mut self, task_id: Uuid) -> PResult<Option<Vec<String>>> {
+        Ok(self.columns.get(&task_id).cloned())
+    }
+
+    fn save_alert(&mut self, alert: &Alert) -> PResult<()> {
+        self.alerts
+            .insert((alert.task_id, alert.rule.clone()), alert.clone());
+        Ok(())
+    }
+
+    fn read_alerts(&mut self, task_id: Uuid) -> PResult<Vec<Alert>> {
+        let mut alerts: Vec<Alert> = self
+            .alerts
+            .values()
+            .filter(|alert| alert.task_id == task_id)
+            .cloned()
+            .collect();
+        alerts.sort_by(|a, b| a.rule.cmp(&b.rule));
+        Ok(alerts)
+    }
+
+    fn list_alerts(&mut self) -> PResult<Vec<Alert>> {
+        let mut alerts: Vec<Alert> = self.alerts.values().cloned().collect();
+        alerts.sort_by(|a, b| b.fired_at.cmp(&a.fired_at));
+        Ok(alerts)
+    }
+
+    fn save_backfill(&mut self, backfill: &Backfill) -> PResult<()> {
+        self.backfills.insert(backfill.task_id, backfill.clone());
+        Ok(())
+    }
+
+    fn read_backfill(&mut self, task_id: Uuid) -> PResult<Option<Backfill>> {
+        Ok(self.backfills.get(&task_id).cloned())
+    }
+
+    fn save_secret(&mut self, secret: &SecretModel) -> PResult<()> {
+        self.secrets.insert(secret.name.clone(), secret.clone());
+        Ok(())
+    }
+
+    fn read_secret(&mut self, name: &str) -> PResult<SecretModel> {
+        self.secrets.get(name).cloned().ok_or_else(|| {
+            Error::new_persistance_internal(
+                format!("could not find {name} secret"),
+                String::default(),
+            )
+        })
+    }
+
+    fn list_secrets(&mut self) -> PResult<Vec<String>> {
+        let mut names: Vec<String> = self.secrets.keys().cloned().collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn delete_secret(&mut self, name: &str) -> PResult<()> {
+        self.secrets.remove(name).ok_or_else(|| {
+            Error::new_persistance_internal(
+                String::from("there's no secret to delete"),
+                String::default(),
+            )
+        })?;
+        Ok(())
+    }
 }
@@ -1,9 +1,22 @@
 use crate::{
-    core::{handler::Report, task::TrackingTask, types::State},
+    core::{
+        alert::Alert,
+        backfill::Backfill,
+        dead_letter::DeadLetter,
+        dedup::Fingerprint,
+        handler::Report,
+        inbox::InboxEntry,
+        quarantine::QuarantinedInput,
+        secret::{Credential, MasterKey},
+        task::TrackingTask,
+        types::State,
+    },
     error::types::Error,
-    models::report::ReportModel,
+    lang::{library::LibraryDefinition, process::Process},
+    models::{report::ReportModel, secret::SecretModel},
 };
 use mockall::*;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -18,16 +31,71 @@ pub trait Persistance {
     fn save_task(&mut self, task: &TrackingTask) -> PResult<()>;
     fn read_task(&mut self, uuid: Uuid) -> PResult<TrackingTask>;
     fn update_task_status(&mut self, uuid: Uuid, status: State) -> PResult<()>;
+    /// Saves fields of a task that can be changed with TaskUpdateRequest.
+    fn update_task(&mut self, task: &TrackingTask) -> PResult<()>;
+    /// Saves number of invocations left for a task.
+    fn update_task_invocations(&mut self, uuid: Uuid, invocations: Option<i32>) -> PResult<()>;
+    /// Saves number of inputs dropped on buffer overflow.
+    fn update_task_dropped_inputs(&mut self, uuid: Uuid, dropped_inputs: i64) -> PResult<()>;
     fn delete_task(&mut self, uuid: Uuid) -> PResult<()>;
     fn get_tasks_by_status(&mut self, statuses: &[State]) -> PResult<Vec<TrackingTask>>;
     fn save_report(&mut self, report: &Report) -> PResult<i32>;
     fn read_reports(&mut self, uuid: Uuid) -> PResult<Option<Vec<ReportModel>>>;
+    fn save_library_definition(&mut self, definition: &LibraryDefinition) -> PResult<()>;
+    /// Reserves and returns next version of named LibraryDefinition. Versions are never reused,
+    /// even after the latest one is deleted.
+    fn next_library_version(&mut self, name: &str) -> PResult<i32>;
+    /// Returns latest version of LibraryDefinition if version is None.
+    fn read_library_definition(
+        &mut self,
+        name: &str,
+        version: Option<i32>,
+    ) -> PResult<LibraryDefinition>;
+    fn list_library_definitions(&mut self) -> PResult<Vec<LibraryDefinition>>;
+    fn delete_library_definition(&mut self, name: &str, version: i32) -> PResult<()>;
+    fn save_quarantined_input(&mut self, input: &QuarantinedInput) -> PResult<()>;
+    /// Returns inputs rejected for given task, oldest first.
+    fn read_quarantined_inputs(&mut self, task_id: Uuid) -> PResult<Vec<QuarantinedInput>>;
+    fn save_dead_letter(&mut self, dead_letter: &DeadLetter) -> PResult<()>;
+    /// Returns dead letters of given task, oldest first.
+    fn read_dead_letters(&mut self, task_id: Uuid) -> PResult<Vec<DeadLetter>>;
+    fn read_dead_letter(&mut self, id: Uuid) -> PResult<DeadLetter>;
+    fn delete_dead_letter(&mut self, id: Uuid) -> PResult<()>;
+    fn save_inbox_entry(&mut self, entry: &InboxEntry) -> PResult<()>;
+    /// Returns persisted inputs of given task, oldest first.
+    fn read_inbox(&mut self, task_id: Uuid) -> PResult<Vec<InboxEntry>>;
+    fn delete_inbox_entry(&mut self, id: Uuid) -> PResult<()>;
+    /// Saves fingerprint of the last written output of a task, overwrites previous one.
+    fn save_fingerprint(&mut self, fingerprint: &Fingerprint) -> PResult<()>;
+    /// Returns fingerprint of the last written output of a task, None if nothing was written yet.
+    fn read_fingerprint(&mut self, task_id: Uuid) -> PResult<Option<Fingerprint>>;
+    /// Saves keys of objects written by a task in order of their columns, overwrites previous ones.
+    fn save_columns(&mut self, task_id: Uuid, columns: &[String]) -> PResult<()>;
+    /// Returns keys of objects written by a task in order of their columns, None if nothing was written yet.
+    fn read_columns(&mut self, task_id: Uuid) -> PResult<Option<Vec<String>>>;
+    /// Saves state of task's alert, overwrites previous state of the same rule.
+    fn save_alert(&mut self, alert: &Alert) -> PResult<()>;
+    /// Returns alerts of given task, ordered by rule.
+    fn read_alerts(&mut self, task_id: Uuid) -> PResult<Vec<Alert>>;
+    /// Returns alerts of all tasks, most recently fired first.
+    fn list_alerts(&mut self) -> PResult<Vec<Alert>>;
+    /// Saves progress of task's backfill, overwrites progress of the previous one.
+    fn save_backfill(&mut self, backfill: &Backfill) -> PResult<()>;
+    /// Returns progress of the last backfill of a task, None if task was never backfilled.
+    fn read_backfill(&mut self, task_id: Uuid) -> PResult<Option<Backfill>>;
+    /// Saves encrypted secret, overwrites existing one with the same name.
+    fn save_secret(&mut self, secret: &SecretModel) -> PResult<()>;
+    fn read_secret(&mut self, name: &str) -> PResult<SecretModel>;
+    fn list_secrets(&mut self) -> PResult<Vec<String>>;
+    fn delete_secret(&mut self, name: &str) -> PResult<()>;
 }
 
 #[derive(Clone)]
 /// Tracker persistance state shared across all handlers.
 pub struct Db {
     shared: Arc<Mutex<Box<dyn Persistance + Send>>>,
+    /// Encrypts and decrypts secrets, secrets cannot be used if it's not set.
+    master_key: Option<Arc<MasterKey>>,
 }
 
 impl Db {
@@ -35,8 +103,25 @@ impl Db {
     pub fn new(p: Box<dyn Persistance + Send>) -> Self {
         Db {
             shared: Arc::new(Mutex::new(p)),
+            master_key: None,
         }
     }
+
+    /// Sets MasterKey used for secrets encryption.
+    pub fn with_master_key(mut self, master_key: MasterKey) -> Self {
+        self.master_key = Some(Arc::new(master_key));
+        self
+    }
+
+    fn master_key(&self) -> PResult<&MasterKey> {
+        self.master_key.as_deref().ok_or_else(|| {
+            Error::new_internal(
+                String::from("Db::master_key"),
+                String::from("master key is not configured"),
+                String::default(),
+            )
+        })
+    }
     pub async fn get(&self, key: &Uuid) -> PResult<u32> {
         self.shared.lock().await.read_location(key)
     }
@@ -49,9 +134,32 @@ impl Db {
     pub async fn read_task(&mut self, uuid: Uuid) -> PResult<TrackingTask> {
         self.shared.lock().await.read_task(uuid)
     }
+    pub async fn update_task(&mut self, task: &TrackingTask) -> PResult<()> {
+        self.shared.lock().await.update_task(task)
+    }
     pub async fn update_task_status(&mut self, uuid: Uuid, status: State) -> PResult<()> {
         self.shared.lock().await.update_task_status(uuid, status)
     }
+    pub async fn update_task_invocations(
+        &mut self,
+        uuid: Uuid,
+        invocations: Option<i32>,
+    ) -> PResult<()> {
+        self.shared
+            .lock()
+            .await
+            .update_task_invocations(uuid, invocations)
+    }
+    pub async fn update_task_dropped_inputs(
+        &mut self,
+        uuid: Uuid,
+        dropped_inputs: i64,
+    ) -> PResult<()> {
+        self.shared
+            .lock()
+            .await
+            .update_task_dropped_inputs(uuid, dropped_inputs)
+    }
     pub async fn delete_task(&mut self, uuid: Uuid) -> PResult<()> {
         self.shared.lock().await.delete_task(uuid)
     }
@@ -64,4 +172,129 @@ impl Db {
     pub async fn read_reports(&self, uuid: Uuid) -> PResult<Option<Vec<ReportModel>>> {
         self.shared.lock().await.read_reports(uuid)
     }
+    pub async fn save_library_definition(&self, definition: &LibraryDefinition) -> PResult<()> {
+        self.shared.lock().await.save_library_definition(definition)
+    }
+    pub async fn next_library_version(&self, name: &str) -> PResult<i32> {
+        self.shared.lock().await.next_library_version(name)
+    }
+    pub async fn read_library_definition(
+        &self,
+        name: &str,
+        version: Option<i32>,
+    ) -> PResult<LibraryDefinition> {
+        self.shared
+            .lock()
+            .await
+            .read_library_definition(name, version)
+    }
+    pub async fn list_library_definitions(&self) -> PResult<Vec<LibraryDefinition>> {
+        self.shared.lock().await.list_library_definitions()
+    }
+    pub async fn delete_library_definition(&self, name: &str, version: i32) -> PResult<()> {
+        self.shared
+            .lock()
+            .await
+            .delete_library_definition(name, version)
+    }
+    pub async fn save_quarantined_input(&self, input: &QuarantinedInput) -> PResult<()> {
+        self.shared.lock().await.save_quarantined_input(input)
+    }
+    pub async fn read_quarantined_inputs(&self, task_id: Uuid) -> PResult<Vec<QuarantinedInput>> {
+        self.shared.lock().await.read_quarantined_inputs(task_id)
+    }
+    pub async fn save_dead_letter(&self, dead_letter: &DeadLetter) -> PResult<()> {
+        self.shared.lock().await.save_dead_letter(dead_letter)
+    }
+    pub async fn read_dead_letters(&self, task_id: Uuid) -> PResult<Vec<DeadLetter>> {
+        self.shared.lock().await.read_dead_letters(task_id)
+    }
+    pub async fn read_dead_letter(&self, id: Uuid) -> PResult<DeadLetter> {
+        self.shared.lock().await.read_dead_letter(id)
+    }
+    pub async fn delete_dead_letter(&self, id: Uuid) -> PResult<()> {
+        self.shared.lock().await.delete_dead_letter(id)
+    }
+    pub async fn save_inbox_entry(&self, entry: &InboxEntry) -> PResult<()> {
+        self.shared.lock().await.save_inbox_entry(entry)
+    }
+    pub async fn read_inbox(&self, task_id: Uuid) -> PResult<Vec<InboxEntry>> {
+        self.shared.lock().await.read_inbox(task_id)
+    }
+    pub async fn delete_inbox_entry(&self, id: Uuid) -> PResult<()> {
+        self.shared.lock().await.delete_inbox_entry(id)
+    }
+    pub async fn save_fingerprint(&self, fingerprint: &Fingerprint) -> PResult<()> {
+        self.shared.lock().await.save_fingerprint(fingerprint)
+    }
+    pub async fn read_fingerprint(&self, task_id: Uuid) -> PResult<Option<Fingerprint>> {
+        self.shared.lock().await.read_fingerprint(task_id)
+    }
+    pub async fn save_columns(&self, task_id: Uuid, columns: &[String]) -> PResult<()> {
+        self.shared.lock().await.save_columns(task_id, columns)
+    }
+    pub async fn read_columns(&self, task_id: Uuid) -> PResult<Option<Vec<String>>> {
+        self.shared.lock().await.read_columns(task_id)
+    }
+    pub async fn save_alert(&self, alert: &Alert) -> PResult<()> {
+        self.shared.lock().await.save_alert(alert)
+    }
+    pub async fn read_alerts(&self, task_id: Uuid) -> PResult<Vec<Alert>> {
+        self.shared.lock().await.read_alerts(task_id)
+    }
+    pub async fn list_alerts(&self) -> PResult<Vec<Alert>> {
+        self.shared.lock().await.list_alerts()
+    }
+    pub async fn save_backfill(&self, backfill: &Backfill) -> PResult<()> {
+        self.shared.lock().await.save_backfill(backfill)
+    }
+    pub async fn read_backfill(&self, task_id: Uuid) -> PResult<Option<Backfill>> {
+        self.shared.lock().await.read_backfill(task_id)
+    }
+
+    /// Encrypts secret's value and saves it.
+    pub async fn save_secret(&self, name: &str, value: &str) -> PResult<()> {
+        let model = self.master_key()?.encrypt(name, value)?;
+        self.shared.lock().await.save_secret(&model)
+    }
+    /// Reads and decrypts secret's value.
+    pub async fn read_secret(&self, name: &str) -> PResult<String> {
+        let model = self.shared.lock().await.read_secret(name)?;
+        self.master_key()?.decrypt(&model)
+    }
+    pub async fn list_secrets(&self) -> PResult<Vec<String>> {
+        self.shared.lock().await.list_secrets()
+    }
+    pub async fn delete_secret(&self, name: &str) -> PResult<()> {
+        self.shared.lock().await.delete_secret(name)
+    }
+
+    /// Reads values of all given secrets.
+    pub async fn resolve_secrets(&self, names: &[String]) -> PResult<HashMap<String, String>> {
+        let mut secrets = HashMap::new();
+        for name in names {
+            secrets.insert(name.clone(), self.read_secret(name).await?);
+        }
+        Ok(secrets)
+    }
+
+    /// Fills value of a Credential that references stored secret.
+    pub async fn resolve_credential(&self, credential: &mut Credential) -> PResult<()> {
+        if let Credential::Secret { secret, resolved } = credential {
+            *resolved = Some(self.read_secret(secret).await?);
+        }
+        Ok(())
+    }
+
+    /// Reads all LibraryDefinitions imported by Process and injects them into it.
+    pub async fn resolve_imports(&self, process: Process) -> PResult<Process> {
+        let mut imported = vec![];
+        for import in process.parsed_imports()? {
+            imported.push(
+                self.read_library_definition(&import.name, import.version)
+                    .await?,
+            );
+        }
+        process.resolve_imports(&imported)
+    }
 }
@@ -1,13 +1,29 @@
 use super::interface::PResult;
 use super::interface::Persistance;
+use crate::core::alert::Alert;
+use crate::core::backfill::Backfill;
+use crate::core::dead_letter::DeadLetter;
+use crate::core::dedup::Fingerprint;
 use crate::core::handler::Report;
+use crate::core::inbox::InboxEntry;
+use crate::core::quarantine::QuarantinedInput;
 use crate::core::task::TrackingTask;
 use crate::core::types::State;
 use crate::diesel::OptionalExtension;
 use crate::diesel::RunQueryDsl;
 use crate::error::types::Error;
+use crate::lang::library::LibraryDefinition;
+use crate::models::alert::AlertModel;
+use crate::models::backfill::BackfillModel;
+use crate::models::dead_letter::DeadLetterModel;
+use crate::models::fingerprint::FingerprintModel;
+use crate::models::grid_columns::GridColumnsModel;
+use crate::models::inbox::InboxModel;
+use crate::models::library::LibraryModel;
 use crate::models::location::Location;
+use crate::models::quarantine::QuarantineModel;
 use crate::models::report::ReportModel;
+use crate::models::secret::SecretModel;
 use crate::models::task::TaskModel;
 use crate::schema::*;
 use diesel::{insert_into, ExpressionMethods, QueryDsl};
@@ -128,6 +144,61 @@ impl Persistance for SqliteClient {
         Ok(())
     }
 
+    fn update_task(&mut self, t: &TrackingTask) -> PResult<()> {
+        use crate::schema::tasks::dsl::*;
+
+        let tm = TaskModel::from_tracking_task(t);
+        let target = tasks.filter(uuid.eq(tm.uuid));
+        diesel::update(target)
+            .set((
+                name.eq(tm.name),
+                process.eq(tm.process),
+                kind.eq(tm.kind),
+                spreadsheet_id.eq(tm.spreadsheet_id),
+                sheet.eq(tm.sheet),
+            ))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute update_task query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn update_task_invocations(&mut self, id: Uuid, left: Option<i32>) -> PResult<()> {
+        use crate::schema::tasks::dsl::*;
+
+        let target = tasks.filter(uuid.eq(id.to_string()));
+        diesel::update(target)
+            .set(invocations.eq(left))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute update_task_invocations query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn update_task_dropped_inputs(&mut self, id: Uuid, dropped: i64) -> PResult<()> {
+        use crate::schema::tasks::dsl::*;
+
+        let target = tasks.filter(uuid.eq(id.to_string()));
+        diesel::update(target)
+            .set(dropped_inputs.eq(dropped))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute update_task_dropped_inputs query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
     fn delete_task(&mut self, id: Uuid) -> PResult<()> {
         use crate::schema::tasks::dsl::*;
 
@@ -177,7 +248,7 @@ impl Persistance for SqliteClient {
         use crate::schema::reports::dsl::*;
 
         let report_models: Vec<ReportModel> = reports
-            .select((task_id, phases, failed, start))
+            .select((task_id, phases, failed, start, error, items))
             .filter(task_id.eq(uuid.to_string()))
             .load(&self.conn)
             .map_err(|err| {
@@ -191,17 +262,474 @@ impl Persistance for SqliteClient {
         }
         Ok(Some(report_models))
     }
+
+    fn save_library_definition(&mut self, ld: &LibraryDefinition) -> PResult<()> {
+        let model = LibraryModel::from_library_definition(ld)?;
+        insert_into(library::table)
+            .values(&model)
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_library_definition query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn next_library_version(&mut self, n: &str) -> PResult<i32> {
+        use crate::schema::library_versions::dsl::*;
+
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let next = library_versions
+                    .select(last_version)
+                    .filter(name.eq(n))
+                    .first::<i32>(&self.conn)
+                    .optional()?
+                    .unwrap_or(0)
+                    + 1;
+                diesel::replace_into(library_versions)
+                    .values((name.eq(n), last_version.eq(next)))
+                    .execute(&self.conn)?;
+                Ok(next)
+            })
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute next_library_version query"),
+                    err.to_string(),
+                )
+            })
+    }
+
+    fn read_library_definition(&mut self, n: &str, v: Option<i32>) -> PResult<LibraryDefinition> {
+        use crate::schema::library::dsl::*;
+
+        let mut query = library.filter(name.eq(n)).into_boxed();
+        if let Some(v) = v {
+            query = query.filter(version.eq(v));
+        }
+        query
+            .order(version.desc())
+            .first::<LibraryModel>(&self.conn)
+            .optional()
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_library_definition query"),
+                    err.to_string(),
+                )
+            })?
+            .ok_or_else(|| {
+                Error::new_persistance_internal(
+                    String::from("empty Option from query read_library_definition"),
+                    "".to_string(),
+                )
+            })?
+            .to_library_definition()
+    }
+
+    fn list_library_definitions(&mut self) -> PResult<Vec<LibraryDefinition>> {
+        use crate::schema::library::dsl::*;
+
+        let models: Vec<LibraryModel> = library
+            .order((name.asc(), version.asc()))
+            .load(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute list_library_definitions query"),
+                    err.to_string(),
+                )
+            })?;
+        models
+            .iter()
+            .map(LibraryModel::to_library_definition)
+            .collect()
+    }
+
+    fn delete_library_definition(&mut self, n: &str, v: i32) -> PResult<()> {
+        use crate::schema::library::dsl::*;
+
+        let target = library.filter(name.eq(n)).filter(version.eq(v));
+        let deleted = diesel::delete(target).execute(&self.conn).map_err(|err| {
+            Error::new_persistance_internal(
+                String::from("could not delete library definition"),
+                err.to_string(),
+            )
+        })?;
+        if deleted == 0 {
+            return Err(Error::new_persistance_internal(
+                String::from("there's no library definition to delete"),
+                String::default(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn save_quarantined_input(&mut self, input: &QuarantinedInput) -> PResult<()> {
+        let model = QuarantineModel::from_quarantined_input(input)?;
+        insert_into(quarantine::table)
+            .values(&model)
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_quarantined_input query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_quarantined_inputs(&mut self, uuid: Uuid) -> PResult<Vec<QuarantinedInput>> {
+        use crate::schema::quarantine::dsl::*;
+
+        let models: Vec<QuarantineModel> = quarantine
+            .filter(task_id.eq(uuid.to_string()))
+            .order(created_at.asc())
+            .load(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_quarantined_inputs query"),
+                    err.to_string(),
+                )
+            })?;
+        models
+            .iter()
+            .map(QuarantineModel::to_quarantined_input)
+            .collect()
+    }
+
+    fn save_dead_letter(&mut self, dead_letter: &DeadLetter) -> PResult<()> {
+        let model = DeadLetterModel::from_dead_letter(dead_letter)?;
+        diesel::replace_into(dead_letters::table)
+            .values(&model)
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_dead_letter query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_dead_letters(&mut self, uuid: Uuid) -> PResult<Vec<DeadLetter>> {
+        use crate::schema::dead_letters::dsl::*;
+
+        let models: Vec<DeadLetterModel> = dead_letters
+            .filter(task_id.eq(uuid.to_string()))
+            .order(created_at.asc())
+            .load(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_dead_letters query"),
+                    err.to_string(),
+                )
+            })?;
+        models.iter().map(DeadLetterModel::to_dead_letter).collect()
+    }
+
+    fn read_dead_letter(&mut self, uuid: Uuid) -> PResult<DeadLetter> {
+        use crate::schema::dead_letters::dsl::*;
+
+        dead_letters
+            .filter(id.eq(uuid.to_string()))
+            .first::<DeadLetterModel>(&self.conn)
+            .optional()
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_dead_letter query"),
+                    err.to_string(),
+                )
+            })?
+            .ok_or_else(|| {
+                Error::new_persistance_internal(
+                    String::from("empty Option from query read_dead_letter"),
+                    "".to_string(),
+                )
+            })?
+            .to_dead_letter()
+    }
+
+    fn delete_dead_letter(&mut self, uuid: Uuid) -> PResult<()> {
+        use crate::schema::dead_letters::dsl::*;
+
+        diesel::delete(dead_letters.filter(id.eq(uuid.to_string())))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("could not delete dead letter"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn save_inbox_entry(&mut self, entry: &InboxEntry) -> PResult<()> {
+        let model = InboxModel::from_inbox_entry(entry)?;
+        insert_into(inbox::table)
+            .values(&model)
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_inbox_entry query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_inbox(&mut self, uuid: Uuid) -> PResult<Vec<InboxEntry>> {
+        use crate::schema::inbox::dsl::*;
+
+        let models: Vec<InboxModel> = inbox
+            .filter(task_id.eq(uuid.to_string()))
+            .order(created_at.asc())
+            .load(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_inbox query"),
+                    err.to_string(),
+                )
+            })?;
+        models.iter().map(InboxModel::to_inbox_entry).collect()
+    }
+
+    fn delete_inbox_entry(&mut self, uuid: Uuid) -> PResult<()> {
+        use crate::schema::inbox::dsl::*;
+
+        diesel::delete(inbox.filter(id.eq(uuid.to_string())))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("could not delete inbox entry"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn save_fingerprint(&mut self, fingerprint: &Fingerprint) -> PResult<()> {
+        diesel::replace_into(fingerprints::table)
+            .values(&FingerprintModel::from_fingerprint(fingerprint))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_fingerprint query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_fingerprint(&mut self, uuid: Uuid) -> PResult<Option<Fingerprint>> {
+        use crate::schema::fingerprints::dsl::*;
+
+        fingerprints
+            .filter(task_id.eq(uuid.to_string()))
+            .first::<FingerprintModel>(&self.conn)
+            .optional()
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_fingerprint query"),
+                    err.to_string(),
+                )
+            })?
+            .map(|model| model.to_fingerprint())
+            .transpose()
+    }
+
+    fn save_columns(&mut self, uuid: Uuid, columns: &[String]) -> PResult<()> {
+        diesel::replace_into(grid_columns::table)
+            .values(&GridColumnsModel::new(uuid, columns))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_columns query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_columns(&mut self, uuid: Uuid) -> PResult<Option<Vec<String>>> {
+        use crate::schema::grid_columns::dsl::*;
+
+        grid_columns
+            .filter(task_id.eq(uuid.to_string()))
+            .first::<GridColumnsModel>(&self.conn)
+            .optional()
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_columns query"),
+                    err.to_string(),
+                )
+            })?
+            .map(|model| model.to_columns())
+            .transpose()
+    }
+
+    fn save_alert(&mut self, alert: &Alert) -> PResult<()> {
+        diesel::replace_into(alerts::table)
+            .values(&AlertModel::from_alert(alert))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_alert query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_alerts(&mut self, uuid: Uuid) -> PResult<Vec<Alert>> {
+        use crate::schema::alerts::dsl::*;
+
+        let models: Vec<AlertModel> = alerts
+            .filter(task_id.eq(uuid.to_string()))
+            .order(rule.asc())
+            .load(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_alerts query"),
+                    err.to_string(),
+                )
+            })?;
+        models.iter().map(AlertModel::to_alert).collect()
+    }
+
+    fn list_alerts(&mut self) -> PResult<Vec<Alert>> {
+        use crate::schema::alerts::dsl::*;
+
+        let models: Vec<AlertModel> =
+            alerts
+                .order(fired_at.desc())
+                .load(&self.conn)
+                .map_err(|err| {
+                    Error::new_persistance_internal(
+                        String::from("failed to execute list_alerts query"),
+                        err.to_string(),
+                    )
+                })?;
+        models.iter().map(AlertModel::to_alert).collect()
+    }
+
+    fn save_backfill(&mut self, backfill: &Backfill) -> PResult<()> {
+        diesel::replace_into(backfills::table)
+            .values(&BackfillModel::from_backfill(backfill))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_backfill query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_backfill(&mut self, uuid: Uuid) -> PResult<Option<Backfill>> {
+        use crate::schema::backfills::dsl::*;
+
+        backfills
+            .filter(task_id.eq(uuid.to_string()))
+            .first::<BackfillModel>(&self.conn)
+            .optional()
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_backfill query"),
+                    err.to_string(),
+                )
+            })?
+            .map(|model| model.to_backfill())
+            .transpose()
+    }
+
+    fn save_secret(&mut self, secret: &SecretModel) -> PResult<()> {
+        diesel::replace_into(secrets::table)
+            .values(secret)
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute save_secret query"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn read_secret(&mut self, n: &str) -> PResult<SecretModel> {
+        use crate::schema::secrets::dsl::*;
+
+        secrets
+            .filter(name.eq(n))
+            .first::<SecretModel>(&self.conn)
+            .optional()
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute read_secret query"),
+                    err.to_string(),
+                )
+            })?
+            .ok_or_else(|| {
+                Error::new_persistance_internal(
+                    String::from("empty Option from query read_secret"),
+                    "".to_string(),
+                )
+            })
+    }
+
+    fn list_secrets(&mut self) -> PResult<Vec<String>> {
+        use crate::schema::secrets::dsl::*;
+
+        secrets
+            .select(name)
+            .order(name.asc())
+            .load(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("failed to execute list_secrets query"),
+                    err.to_string(),
+                )
+            })
+    }
+
+    fn delete_secret(&mut self, n: &str) -> PResult<()> {
+        use crate::schema::secrets::dsl::*;
+
+        diesel::delete(secrets.filter(name.eq(n)))
+            .execute(&self.conn)
+            .map_err(|err| {
+                Error::new_persistance_internal(
+                    String::from("could not delete secret"),
+                    err.to_string(),
+                )
+            })?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::task::{TaskInput, TrackingTask};
+    use crate::core::alert::{Alert, AlertConfig, AlertRule, AlertStatus, Severity};
+    use crate::core::backfill::{Backfill, BackfillStatus};
+    use crate::core::buffer::{BatchConfig, BufferConfig, OverflowPolicy};
+    use crate::core::dead_letter::DeadLetter;
+    use crate::core::dedup::{DedupConfig, Fingerprint};
+    use crate::core::inbox::InboxEntry;
+    use crate::core::notifier::Notifier;
+    use crate::core::quarantine::QuarantinedInput;
+    use crate::core::retry::{RetryPhase, RetryPolicy};
+    use crate::core::schedule::{ActiveWindow, OutsideWindow};
+    use crate::core::task::{InputData, TaskInput, TrackingTask};
     use crate::core::types::*;
+    use crate::core::write_mode::WriteMode;
+    use crate::lang::library::LibraryDefinition;
     use crate::lang::process::{Definition, Process};
     use crate::persistance::interface::Persistance;
     use crate::server::task::TaskKindRequest;
+    use chrono::{TimeZone, Utc};
     use diesel::{Connection, SqliteConnection};
     use diesel_migrations::embed_migrations;
+    use std::collections::HashMap;
     use std::fs::{self, File};
     use uuid::Uuid;
 
@@ -242,7 +770,39 @@ mod tests {
             direction: Direction::Vertical,
             with_timestamp: true,
             timestamp_position: TimestampPosition::Before,
-            invocations: None,
+            timestamp_format: String::from("%H:%M"),
+            timestamp_timezone: String::from("Europe/Warsaw"),
+            invocations: Some(3),
+            ends_at: Some(Utc.with_ymd_and_hms(2022, 10, 11, 12, 0, 0).unwrap()),
+            retry_policy: Some(RetryPolicy::default()),
+            buffer: Some(BufferConfig {
+                capacity: 5,
+                overflow: OverflowPolicy::Coalesce,
+                durable: true,
+            }),
+            dropped_inputs: 0,
+            batch: Some(BatchConfig {
+                max_items: 10,
+                max_wait_ms: 500,
+            }),
+            active_window: Some(ActiveWindow {
+                start_hour: 9,
+                end_hour: 17,
+                outside: OutsideWindow::Defer,
+                ..Default::default()
+            }),
+            write_mode: WriteMode::Upsert { key: 1 },
+            dedup: Some(DedupConfig { tolerance: 0.5 }),
+            alerts: Some(AlertConfig {
+                rules: vec![AlertRule {
+                    name: String::from("price"),
+                    condition: String::from("GT(IN, INT(100))"),
+                    resolve: None,
+                    severity: Severity::Critical,
+                    cooldown_secs: 60,
+                }],
+                notifiers: vec![Notifier::Log],
+            }),
             process,
             callbacks: None,
             status: State::Created,
@@ -251,6 +811,7 @@ mod tests {
             }),
             kind: None,
             kind_request: TaskKindRequest::Ticker { interval_secs: 1 },
+            params: HashMap::new(),
         };
 
         let mut client = SqliteClient::new(connection);
@@ -260,15 +821,327 @@ mod tests {
         assert_eq!(tt, tt_db);
 
         client.update_task_status(id, State::Quit).unwrap();
+        client.update_task_invocations(id, Some(2)).unwrap();
+        client.update_task_dropped_inputs(id, 7).unwrap();
+        let mut updated = tt.clone();
+        updated.name = Some(String::from("updated"));
+        updated.kind_request = TaskKindRequest::Ticker { interval_secs: 60 };
+        client.update_task(&updated).unwrap();
 
         let tt_db = client.read_task(id).unwrap();
         assert_eq!(tt_db.status, State::Quit);
+        assert_eq!(tt_db.invocations, Some(2));
+        assert_eq!(tt_db.dropped_inputs, 7);
+        assert_eq!(tt_db.name, Some(String::from("updated")));
+        assert_eq!(
+            tt_db.kind_request,
+            TaskKindRequest::Ticker { interval_secs: 60 }
+        );
 
         client.delete_task(id).unwrap();
         assert!(client.read_task(id).is_err());
         fs::remove_file(file_name).unwrap();
     }
 
+    #[test]
+    fn test_save_read_library_definition() {
+        let file_name = "test_library.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_library.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        for wanted_version in 1..=2 {
+            let version = client.next_library_version("normalize").unwrap();
+            assert_eq!(version, wanted_version);
+            client
+                .save_library_definition(&LibraryDefinition::new(
+                    "normalize",
+                    version,
+                    Definition::new(vec!["DEFINE(OUT, GET(IN))"]),
+                ))
+                .unwrap();
+        }
+
+        assert_eq!(
+            client
+                .read_library_definition("normalize", None)
+                .unwrap()
+                .version,
+            2
+        );
+        assert_eq!(
+            client
+                .read_library_definition("normalize", Some(1))
+                .unwrap()
+                .version,
+            1
+        );
+        assert_eq!(client.list_library_definitions().unwrap().len(), 2);
+
+        client.delete_library_definition("normalize", 2).unwrap();
+        assert!(client.delete_library_definition("normalize", 2).is_err());
+        assert_eq!(
+            client
+                .read_library_definition("normalize", None)
+                .unwrap()
+                .version,
+            1
+        );
+
+        // deleted versions are not reused.
+        assert_eq!(client.next_library_version("normalize").unwrap(), 3);
+        assert_eq!(client.next_library_version("other").unwrap(), 1);
+        assert!(client.read_library_definition("missing", None).is_err());
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_save_read_quarantined_inputs() {
+        let file_name = "test_quarantine.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_quarantine.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        let task_id = Uuid::new_v4();
+        let input = QuarantinedInput::new(
+            task_id,
+            InputData::String(String::from("invalid")),
+            String::from("expected Object, got String"),
+        );
+        client.save_quarantined_input(&input).unwrap();
+        client
+            .save_quarantined_input(&QuarantinedInput::new(
+                Uuid::new_v4(),
+                InputData::String(String::from("other task")),
+                String::default(),
+            ))
+            .unwrap();
+
+        let read = client.read_quarantined_inputs(task_id).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].id, input.id);
+        assert_eq!(read[0].input, input.input);
+        assert_eq!(read[0].error, input.error);
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_save_read_delete_dead_letters() {
+        let file_name = "test_dead_letters.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_dead_letters.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        let task_id = Uuid::new_v4();
+        let dead_letter = DeadLetter::new(
+            task_id,
+            InputData::String(String::from("input")),
+            RetryPhase::Write,
+            String::from("quota exceeded"),
+            3,
+        );
+        client.save_dead_letter(&dead_letter).unwrap();
+
+        let read = client.read_dead_letters(task_id).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].input, dead_letter.input);
+        assert_eq!(read[0].phase, RetryPhase::Write);
+        assert_eq!(read[0].attempts, 3);
+        assert_eq!(
+            client.read_dead_letter(dead_letter.id).unwrap().error,
+            dead_letter.error
+        );
+
+        client.delete_dead_letter(dead_letter.id).unwrap();
+        assert!(client.read_dead_letter(dead_letter.id).is_err());
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_save_read_fingerprint() {
+        let file_name = "test_fingerprint.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_fingerprint.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        let task_id = Uuid::new_v4();
+        assert!(client.read_fingerprint(task_id).unwrap().is_none());
+
+        let fingerprint = Fingerprint {
+            task_id,
+            hash: String::from("hash"),
+            numbers: vec![1.5, 2.0],
+        };
+        client.save_fingerprint(&fingerprint).unwrap();
+        // previous fingerprint is overwritten.
+        let fingerprint = Fingerprint {
+            numbers: vec![3.0],
+            ..fingerprint
+        };
+        client.save_fingerprint(&fingerprint).unwrap();
+        assert_eq!(client.read_fingerprint(task_id).unwrap(), Some(fingerprint));
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_save_read_columns() {
+        let file_name = "test_columns.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_columns.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        let task_id = Uuid::new_v4();
+        assert!(client.read_columns(task_id).unwrap().is_none());
+
+        client
+            .save_columns(task_id, &[String::from("a"), String::from("c")])
+            .unwrap();
+        // previous columns are overwritten.
+        let columns = vec![String::from("a"), String::from("c"), String::from("b")];
+        client.save_columns(task_id, &columns).unwrap();
+        assert_eq!(client.read_columns(task_id).unwrap(), Some(columns));
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_save_read_alerts() {
+        let file_name = "test_alerts.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_alerts.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        let task_id = Uuid::new_v4();
+        let fired_at = Utc.with_ymd_and_hms(2022, 10, 29, 12, 0, 0).unwrap();
+        let price = Alert {
+            task_id,
+            rule: String::from("price"),
+            severity: Severity::Critical,
+            status: AlertStatus::Firing,
+            value: serde_json::json!({"price": 120}),
+            fired_at,
+            notified_at: Some(fired_at),
+            resolved_at: None,
+        };
+        let volume = Alert {
+            rule: String::from("volume"),
+            severity: Severity::Info,
+            fired_at: Utc.with_ymd_and_hms(2022, 10, 29, 13, 0, 0).unwrap(),
+            notified_at: None,
+            ..price.clone()
+        };
+        client.save_alert(&volume).unwrap();
+        client.save_alert(&price).unwrap();
+        // state of the rule is overwritten.
+        let price = Alert {
+            status: AlertStatus::Resolved,
+            resolved_at: Some(Utc.with_ymd_and_hms(2022, 10, 29, 14, 0, 0).unwrap()),
+            ..price
+        };
+        client.save_alert(&price).unwrap();
+
+        assert_eq!(
+            client.read_alerts(task_id).unwrap(),
+            vec![price.clone(), volume.clone()]
+        );
+        assert!(client.read_alerts(Uuid::new_v4()).unwrap().is_empty());
+        assert_eq!(client.list_alerts().unwrap(), vec![volume, price]);
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_save_read_backfill() {
+        let file_name = "test_backfill.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_backfill.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        let task_id = Uuid::new_v4();
+        assert!(client.read_backfill(task_id).unwrap().is_none());
+
+        let mut backfill = Backfill {
+            started_at: Utc.with_ymd_and_hms(2022, 10, 31, 12, 0, 0).unwrap(),
+            ..Backfill::new(task_id, 3)
+        };
+        client.save_backfill(&backfill).unwrap();
+        backfill.record(false);
+        backfill.record(true);
+        backfill.status = BackfillStatus::Cancelled;
+        backfill.finished_at = Some(Utc.with_ymd_and_hms(2022, 10, 31, 12, 5, 0).unwrap());
+        client.save_backfill(&backfill).unwrap();
+
+        let read = client.read_backfill(task_id).unwrap().unwrap();
+        assert_eq!(read, backfill);
+        assert_eq!((read.processed, read.failed), (2, 1));
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_save_read_delete_inbox() {
+        let file_name = "test_inbox.sqlite3";
+        File::create(file_name).unwrap();
+
+        let connection = SqliteConnection::establish("file:test_inbox.sqlite3")
+            .unwrap_or_else(|_| panic!("Error connecting to {}", file_name));
+
+        // This will run the necessary migrations.
+        embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).unwrap();
+
+        let mut client = SqliteClient::new(connection);
+        let task_id = Uuid::new_v4();
+        let entry = InboxEntry::new(task_id, InputData::String(String::from("input")));
+        client.save_inbox_entry(&entry).unwrap();
+        client
+            .save_inbox_entry(&InboxEntry::new(
+                Uuid::new_v4(),
+                InputData::String(String::from("other task")),
+            ))
+            .unwrap();
+
+        let read = client.read_inbox(task_id).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].id, entry.id);
+        assert_eq!(read[0].input, entry.input);
+
+        client.delete_inbox_entry(entry.id).unwrap();
+        assert!(client.read_inbox(task_id).unwrap().is_empty());
+        fs::remove_file(file_name).unwrap();
+    }
+
     fn test_save_read_by_status() {
         let file_name = "test.sqlite3";
         File::create(file_name).unwrap();
@@ -301,7 +1174,18 @@ mod tests {
             direction: Direction::Vertical,
             with_timestamp: true,
             timestamp_position: TimestampPosition::Before,
+            timestamp_format: String::from(DEFAULT_TIMESTAMP_FORMAT),
+            timestamp_timezone: String::from(DEFAULT_TIMESTAMP_TIMEZONE),
             invocations: None,
+            ends_at: None,
+            retry_policy: None,
+            buffer: None,
+            dropped_inputs: 0,
+            batch: None,
+            active_window: None,
+            write_mode: WriteMode::Append,
+            dedup: None,
+            alerts: None,
             process: process.clone(),
             input: Some(TaskInput::String {
                 value: String::from("test"),
@@ -310,6 +1194,7 @@ mod tests {
             status: State::Created,
             kind: None,
             kind_request: kind_request.clone(),
+            params: HashMap::new(),
         };
         let tt2 = TrackingTask {
             id,
@@ -322,7 +1207,18 @@ mod tests {
             direction: Direction::Vertical,
             with_timestamp: true,
             timestamp_position: TimestampPosition::Before,
+            timestamp_format: String::from(DEFAULT_TIMESTAMP_FORMAT),
+            timestamp_timezone: String::from(DEFAULT_TIMESTAMP_TIMEZONE),
             invocations: None,
+            ends_at: None,
+            retry_policy: None,
+            buffer: None,
+            dropped_inputs: 0,
+            batch: None,
+            active_window: None,
+            write_mode: WriteMode::Append,
+            dedup: None,
+            alerts: None,
             process: process.clone(),
             input: Some(TaskInput::String {
                 value: String::from("test"),
@@ -331,6 +1227,7 @@ mod tests {
             status: State::Running,
             kind: None,
             kind_request: kind_request.clone(),
+            params: HashMap::new(),
         };
         let tt3 = TrackingTask {
             id,
@@ -343,7 +1240,18 @@ mod tests {
             direction: Direction::Vertical,
             with_timestamp: true,
             timestamp_position: TimestampPosition::Before,
+            timestamp_format: String::from(DEFAULT_TIMESTAMP_FORMAT),
+            timestamp_timezone: String::from(DEFAULT_TIMESTAMP_TIMEZONE),
             invocations: None,
+            ends_at: None,
+            retry_policy: None,
+            buffer: None,
+            dropped_inputs: 0,
+            batch: None,
+            active_window: None,
+            write_mode: WriteMode::Append,
+            dedup: None,
+            alerts: None,
             process,
             input: Some(TaskInput::String {
                 value: String::from("test"),
@@ -352,6 +1260,7 @@ mod tests {
             status: State::Quit,
             kind: None,
             kind_request,
+            params: HashMap::new(),
         };
 
         let mut client = SqliteClient::new(connection);
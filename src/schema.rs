@@ -1,3 +1,79 @@
+table! {
+    alerts (task_id, rule) {
+        task_id -> Text,
+        rule -> Text,
+        severity -> Text,
+        status -> Text,
+        value -> Text,
+        fired_at -> Timestamp,
+        notified_at -> Nullable<Timestamp>,
+        resolved_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    backfills (task_id) {
+        task_id -> Text,
+        total -> Integer,
+        processed -> Integer,
+        failed -> Integer,
+        status -> Text,
+        started_at -> Timestamp,
+        finished_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    dead_letters (id) {
+        id -> Text,
+        task_id -> Text,
+        input -> Text,
+        phase -> Text,
+        error -> Text,
+        attempts -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    fingerprints (task_id) {
+        task_id -> Text,
+        hash -> Text,
+        numbers -> Text,
+    }
+}
+
+table! {
+    grid_columns (task_id) {
+        task_id -> Text,
+        columns -> Text,
+    }
+}
+
+table! {
+    inbox (id) {
+        id -> Text,
+        task_id -> Text,
+        input -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    library (name, version) {
+        name -> Text,
+        version -> Integer,
+        definition -> Text,
+    }
+}
+
+table! {
+    library_versions (name) {
+        name -> Text,
+        last_version -> Integer,
+    }
+}
+
 table! {
     location (key) {
         key -> Text,
@@ -5,6 +81,16 @@ table! {
     }
 }
 
+table! {
+    quarantine (id) {
+        id -> Text,
+        task_id -> Text,
+        input -> Text,
+        error -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     reports (id) {
         id -> Integer,
@@ -12,6 +98,16 @@ table! {
         phases -> Text,
         failed -> Bool,
         start -> Timestamp,
+        error -> Nullable<Text>,
+        items -> Nullable<Text>,
+    }
+}
+
+table! {
+    secrets (name) {
+        name -> Text,
+        nonce -> Text,
+        ciphertext -> Text,
     }
 }
 
@@ -30,9 +126,43 @@ table! {
         input -> Nullable<Text>,
         status -> Text,
         kind -> Text,
+        params -> Nullable<Text>,
+        invocations -> Nullable<Integer>,
+        ends_at -> Nullable<Timestamp>,
+        retry_policy -> Nullable<Text>,
+        buffer -> Nullable<Text>,
+        dropped_inputs -> BigInt,
+        batch -> Nullable<Text>,
+        active_window -> Nullable<Text>,
+        timestamp_format -> Text,
+        timestamp_timezone -> Text,
+        write_mode -> Text,
+        dedup -> Nullable<Text>,
+        alerts -> Nullable<Text>,
     }
 }
 
+joinable!(alerts -> tasks (task_id));
+joinable!(backfills -> tasks (task_id));
+joinable!(dead_letters -> tasks (task_id));
+joinable!(fingerprints -> tasks (task_id));
+joinable!(grid_columns -> tasks (task_id));
+joinable!(inbox -> tasks (task_id));
+joinable!(quarantine -> tasks (task_id));
 joinable!(reports -> tasks (task_id));
 
-allow_tables_to_appear_in_same_query!(location, reports, tasks,);
+allow_tables_to_appear_in_same_query!(
+    alerts,
+    backfills,
+    dead_letters,
+    fingerprints,
+    grid_columns,
+    inbox,
+    library,
+    library_versions,
+    location,
+    quarantine,
+    reports,
+    secrets,
+    tasks,
+);
@@ -0,0 +1,76 @@
+use crate::core::alert::Alert;
+use crate::error::types::Result;
+use crate::persistance::interface::Db;
+use crate::server::parse_uuid;
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// Lists alerts of all tasks, most recently fired first.
+#[get("/alerts")]
+pub async fn list_alerts(db: &State<Db>) -> Result<Json<Vec<Alert>>> {
+    Ok(Json(db.list_alerts().await?))
+}
+
+/// Lists alerts of a task, both firing and resolved ones.
+#[get("/alerts/<task_id>")]
+pub async fn get_alerts(db: &State<Db>, task_id: String) -> Result<Json<Vec<Alert>>> {
+    let uuid = parse_uuid(&task_id, "get_alerts")?;
+    Ok(Json(db.read_alerts(uuid).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::alert::{Alert, AlertStatus, Severity};
+    use crate::core::manager::TaskCommand;
+    use crate::core::task::TrackingTask;
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use chrono::{Duration, Utc};
+    use rocket::http::Status;
+    use rocket::local::asynchronous::Client;
+    use tokio::sync::mpsc::channel;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn list_alerts() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+
+        let task_id = Uuid::new_v4();
+        let firing = Alert {
+            task_id,
+            rule: String::from("price"),
+            severity: Severity::Critical,
+            status: AlertStatus::Firing,
+            value: serde_json::json!(120),
+            fired_at: Utc::now(),
+            notified_at: None,
+            resolved_at: None,
+        };
+        let resolved = Alert {
+            task_id: Uuid::new_v4(),
+            status: AlertStatus::Resolved,
+            fired_at: firing.fired_at - Duration::hours(1),
+            resolved_at: Some(firing.fired_at),
+            ..firing.clone()
+        };
+        db.save_alert(&firing).await.unwrap();
+        db.save_alert(&resolved).await.unwrap();
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client.get("/alerts").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let listed: Vec<Alert> = response.into_json().await.unwrap();
+        assert_eq!(listed, vec![firing.clone(), resolved]);
+
+        let response = client.get(format!("/alerts/{}", task_id)).dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let listed: Vec<Alert> = response.into_json().await.unwrap();
+        assert_eq!(listed, vec![firing]);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::core::backfill::Backfill;
+use crate::core::manager::{Command, TaskCommand};
+use crate::core::task::InputData;
+use crate::core::types::State as TaskState;
+use crate::error::types::{Error, Result};
+use crate::persistance::interface::Db;
+use crate::server::parse_uuid;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc::Sender;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+/// Backfill settings read from rocket configuration, e.g. `ROCKET_BACKFILL_DIR`.
+pub struct BackfillConfig {
+    /// Directory files with inputs are read from, reading files is disabled if not set.
+    #[serde(default)]
+    pub backfill_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+/// Historical inputs of a task, given directly or as a path to json file with a list of inputs.
+pub struct BackfillRequest {
+    #[serde(default)]
+    pub inputs: Vec<InputData>,
+    /// Path of the file relative to configured backfill directory.
+    pub file: Option<String>,
+}
+
+impl BackfillRequest {
+    /// Returns inputs given directly followed by inputs read from the file.
+    /// File has to be inside backfill_dir.
+    pub async fn into_inputs(self, backfill_dir: Option<&Path>) -> Result<Vec<InputData>> {
+        let mut inputs = self.inputs;
+        if let Some(file) = &self.file {
+            let path = backfill_file(file, backfill_dir).await?;
+            let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+                Error::new_internal(
+                    String::from("BackfillRequest::into_inputs"),
+                    format!("failed to read {}", file),
+                    e.to_string(),
+                )
+            })?;
+            let read: Vec<InputData> = serde_json::from_str(&content).map_err(|e| {
+                Error::new_internal(
+                    String::from("BackfillRequest::into_inputs"),
+                    format!("failed to parse inputs of {}", file),
+                    e.to_string(),
+                )
+            })?;
+            inputs.extend(read);
+        }
+        if inputs.is_empty() {
+            return Err(Error::new_validation(
+                "BackfillRequest",
+                "there are no inputs to backfill",
+                "inputs",
+            ));
+        }
+        Ok(inputs)
+    }
+}
+
+/// Resolves file against backfill_dir, rejects paths that lead outside of it.
+async fn backfill_file(file: &str, backfill_dir: Option<&Path>) -> Result<PathBuf> {
+    let dir = backfill_dir.ok_or_else(|| {
+        Error::new_validation(
+            "BackfillRequest",
+            "reading inputs from file is disabled, backfill_dir is not configured",
+            "file",
+        )
+    })?;
+    let dir = canonicalize(dir).await?;
+    let path = canonicalize(&dir.join(file)).await?;
+    if !path.starts_with(&dir) {
+        return Err(Error::new_validation(
+            "BackfillRequest",
+            "file has to be inside of backfill_dir",
+            "file",
+        ));
+    }
+    Ok(path)
+}
+
+async fn canonicalize(path: &Path) -> Result<PathBuf> {
+    tokio::fs::canonicalize(path).await.map_err(|e| {
+        Error::new_internal(
+            String::from("BackfillRequest::into_inputs"),
+            format!("failed to resolve {}", path.display()),
+            e.to_string(),
+        )
+    })
+}
+
+async fn send(
+    sender: &Sender<TaskCommand>,
+    task_id: Uuid,
+    cmd: Command,
+    scope: &str,
+) -> Result<()> {
+    sender
+        .send(TaskCommand::new(task_id, cmd))
+        .await
+        .map_err(|e| {
+            Error::new_internal(
+                String::from(scope),
+                String::from("failed to send backfill command"),
+                e.to_string(),
+            )
+        })
+}
+
+/// Runs historical inputs through task's process and writes them one by one.
+/// Progress is available with GET, single report with outcome of every input is saved at the end.
+#[post("/backfill/<task_id>", format = "json", data = "<request>")]
+pub async fn start_backfill(
+    sender: &State<Sender<TaskCommand>>,
+    db: &State<Db>,
+    config: &State<BackfillConfig>,
+    task_id: String,
+    request: Json<BackfillRequest>,
+) -> Result<()> {
+    let uuid = parse_uuid(&task_id, "start_backfill")?;
+    // inputs of finished, stopped or deleted task would be dropped, it has no handler that handles them.
+    let tt = db.inner().clone().read_task(uuid).await?;
+    tt.ensure_status(&[TaskState::Created, TaskState::Running])?;
+    let inputs = request
+        .0
+        .into_inputs(config.backfill_dir.as_deref())
+        .await?;
+    let total = inputs.len();
+    send(sender, uuid, Command::Backfill(inputs), "start_backfill").await?;
+    info!("backfill of {} inputs of {} task started", total, task_id);
+    Ok(())
+}
+
+/// Returns progress of the last backfill of a task.
+#[get("/backfill/<task_id>")]
+pub async fn get_backfill(db: &State<Db>, task_id: String) -> Result<Option<Json<Backfill>>> {
+    let uuid = parse_uuid(&task_id, "get_backfill")?;
+    Ok(db.read_backfill(uuid).await?.map(Json))
+}
+
+/// Cancels running backfill, input that is being handled is finished first.
+#[delete("/backfill/<task_id>")]
+pub async fn cancel_backfill(sender: &State<Sender<TaskCommand>>, task_id: String) -> Result<()> {
+    let uuid = parse_uuid(&task_id, "cancel_backfill")?;
+    send(sender, uuid, Command::CancelBackfill, "cancel_backfill").await?;
+    info!("backfill of {} task cancelled", task_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BackfillRequest;
+    use crate::core::backfill::{Backfill, BackfillStatus};
+    use crate::core::manager::{Command, TaskCommand};
+    use crate::core::task::{InputData, TrackingTask};
+    use crate::core::types::{Direction, State};
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use crate::server::task::TaskKindRequest;
+    use rocket::http::{ContentType, Status};
+    use rocket::local::asynchronous::Client;
+    use std::fs;
+    use std::path::Path;
+    use tokio::sync::mpsc::channel;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_backfill_request_inputs() {
+        let dir = Path::new("test_backfill");
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("inputs.json"),
+            r#"[{"String": "2"}, {"Json": {"price": 3}}]"#,
+        )
+        .unwrap();
+        let request = BackfillRequest {
+            inputs: vec![InputData::String(String::from("1"))],
+            file: Some(String::from("inputs.json")),
+        };
+        // files can't be read without configured directory.
+        assert!(request.clone().into_inputs(None).await.is_err());
+        let inputs = request.into_inputs(Some(dir)).await.unwrap();
+        assert_eq!(
+            inputs,
+            vec![
+                InputData::String(String::from("1")),
+                InputData::String(String::from("2")),
+                InputData::Json(serde_json::json!({"price": 3})),
+            ]
+        );
+
+        assert!(BackfillRequest::default()
+            .into_inputs(Some(dir))
+            .await
+            .is_err());
+        for file in ["missing.json", "../Cargo.toml", "/etc/passwd"] {
+            assert!(BackfillRequest {
+                file: Some(String::from(file)),
+                ..Default::default()
+            }
+            .into_inputs(Some(dir))
+            .await
+            .is_err());
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn start_get_cancel_backfill() {
+        let (cmd_send, mut cmd_receive) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
+        let mut tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Clicked,
+        );
+        // created task has a handler too, e.g. clicked one that wasn't clicked yet.
+        db.save_task(&tt).await.unwrap();
+        let mut finished = tt.clone();
+        finished.id = Uuid::new_v4();
+        finished.status = State::Finished;
+        db.save_task(&finished).await.unwrap();
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .post(format!("/backfill/{}", tt.id))
+            .header(ContentType::JSON)
+            .body(r#"{"inputs": [{"String": "1"}, {"String": "2"}]}"#)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            cmd_receive.recv().await.unwrap(),
+            TaskCommand::new(
+                tt.id,
+                Command::Backfill(vec![
+                    InputData::String(String::from("1")),
+                    InputData::String(String::from("2"))
+                ])
+            )
+        );
+
+        // unknown task.
+        let response = client
+            .post(format!("/backfill/{}", Uuid::new_v4()))
+            .header(ContentType::JSON)
+            .body(r#"{"inputs": [{"String": "1"}]}"#)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::InternalServerError);
+
+        // finished task has no handler.
+        let response = client
+            .post(format!("/backfill/{}", finished.id))
+            .header(ContentType::JSON)
+            .body(r#"{"inputs": [{"String": "1"}]}"#)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client.get(format!("/backfill/{}", tt.id)).dispatch().await;
+        assert_eq!(response.status(), Status::NotFound);
+
+        let mut backfill = Backfill::new(tt.id, 2);
+        backfill.record(false);
+        db.save_backfill(&backfill).await.unwrap();
+        let response = client.get(format!("/backfill/{}", tt.id)).dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let progress: Backfill = response.into_json().await.unwrap();
+        assert_eq!(progress.status, BackfillStatus::Running);
+        assert_eq!(progress.processed, 1);
+
+        let response = client
+            .delete(format!("/backfill/{}", tt.id))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            cmd_receive.recv().await.unwrap(),
+            TaskCommand::new(tt.id, Command::CancelBackfill)
+        );
+    }
+}
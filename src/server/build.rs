@@ -1,10 +1,26 @@
+use super::alert::{get_alerts, list_alerts};
 use super::apply::apply;
+use super::backfill::{cancel_backfill, get_backfill, start_backfill, BackfillConfig};
+use super::dead_letter::{
+    discard_dead_letter, get_dead_letter, get_dead_letters, replay_dead_letter,
+};
+use super::dry_run::dry_run;
+use super::library::{
+    create_library_definition, delete_library_definition, get_latest_library_definition,
+    get_library_definition, list_library_definitions,
+};
+use super::pipeline::get_pipeline;
+use super::process::test_process;
+use super::quarantine::get_quarantined_inputs;
 use super::report::get_reports;
-use super::task::create;
+use super::secret::{create_secret, delete_secret, list_secrets};
+use super::task::{create, update};
 use crate::{
     core::{manager::TaskCommand, task::TrackingTask},
+    lang::registry::Registry,
     persistance::interface::Db,
 };
+use rocket::fairing::AdHoc;
 use rocket::{routes, Build, Rocket};
 use tokio::sync::mpsc::Sender;
 
@@ -12,10 +28,52 @@ pub fn rocket(
     cmd_send: Sender<TaskCommand>,
     tt_send: Sender<TrackingTask>,
     db: Db,
+) -> Rocket<Build> {
+    rocket_with_registry(cmd_send, tt_send, db, Registry::default())
+}
+
+/// Builds rocket that evaluates processes with native functions of given Registry,
+/// it should be the same Registry that is passed to Tracker::with_registry.
+pub fn rocket_with_registry(
+    cmd_send: Sender<TaskCommand>,
+    tt_send: Sender<TrackingTask>,
+    db: Db,
+    registry: Registry,
 ) -> Rocket<Build> {
     rocket::build()
-        .mount("/", routes![apply, create, get_reports])
+        .mount(
+            "/",
+            routes![
+                apply,
+                create,
+                update,
+                get_reports,
+                create_library_definition,
+                list_library_definitions,
+                get_latest_library_definition,
+                get_library_definition,
+                delete_library_definition,
+                test_process,
+                get_quarantined_inputs,
+                create_secret,
+                list_secrets,
+                delete_secret,
+                get_dead_letters,
+                get_dead_letter,
+                replay_dead_letter,
+                discard_dead_letter,
+                get_pipeline,
+                list_alerts,
+                get_alerts,
+                start_backfill,
+                get_backfill,
+                cancel_backfill,
+                dry_run
+            ],
+        )
         .manage(cmd_send)
         .manage(tt_send)
         .manage(db)
+        .manage(registry)
+        .attach(AdHoc::config::<BackfillConfig>())
 }
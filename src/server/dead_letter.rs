@@ -0,0 +1,154 @@
+use crate::core::dead_letter::DeadLetter;
+use crate::core::manager::{Command, TaskCommand};
+use crate::core::types::State as TaskState;
+use crate::error::types::{Error, Result};
+use crate::persistance::interface::Db;
+use crate::server::parse_uuid;
+use rocket::serde::json::Json;
+use rocket::State;
+use tokio::sync::mpsc::Sender;
+
+/// Lists inputs of a task which handling failed after all retries.
+#[get("/dead-letters/<task_id>")]
+pub async fn get_dead_letters(db: &State<Db>, task_id: String) -> Result<Json<Vec<DeadLetter>>> {
+    let uuid = parse_uuid(&task_id, "get_dead_letters")?;
+    Ok(Json(db.read_dead_letters(uuid).await?))
+}
+
+#[get("/dead-letter/<id>")]
+pub async fn get_dead_letter(db: &State<Db>, id: String) -> Result<Json<DeadLetter>> {
+    let uuid = parse_uuid(&id, "get_dead_letter")?;
+    Ok(Json(db.read_dead_letter(uuid).await?))
+}
+
+/// Sends dead letter's input to its task once again. Task removes dead letter after successful
+/// handling, if handling fails again dead letter is updated with the new error.
+#[post("/dead-letter/<id>/replay")]
+pub async fn replay_dead_letter(
+    db: &State<Db>,
+    sender: &State<Sender<TaskCommand>>,
+    id: String,
+) -> Result<()> {
+    let uuid = parse_uuid(&id, "replay_dead_letter")?;
+    let dead_letter = db.read_dead_letter(uuid).await?;
+    // input of task that is not running would be dropped.
+    let tt = db.inner().clone().read_task(dead_letter.task_id).await?;
+    tt.ensure_status(&[TaskState::Created, TaskState::Running])?;
+
+    sender
+        .send(TaskCommand::new(
+            dead_letter.task_id,
+            Command::Replay(uuid, dead_letter.input),
+        ))
+        .await
+        .map_err(|e| {
+            Error::new_internal(
+                String::from("replay_dead_letter"),
+                String::from("failed to send replay command"),
+                e.to_string(),
+            )
+        })?;
+    info!("dead letter {} sent to be replayed", id);
+    Ok(())
+}
+
+#[delete("/dead-letter/<id>")]
+pub async fn discard_dead_letter(db: &State<Db>, id: String) -> Result<()> {
+    let uuid = parse_uuid(&id, "discard_dead_letter")?;
+    db.delete_dead_letter(uuid).await?;
+    info!("dead letter {} discarded", id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::dead_letter::DeadLetter;
+    use crate::core::manager::{Command, TaskCommand};
+    use crate::core::retry::RetryPhase;
+    use crate::core::task::{InputData, TrackingTask};
+    use crate::core::types::{Direction, State};
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use crate::server::task::TaskKindRequest;
+    use rocket::http::Status;
+    use rocket::local::asynchronous::Client;
+    use tokio::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn list_replay_discard_dead_letters() {
+        let (cmd_send, mut cmd_receive) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
+
+        let mut tt = TrackingTask::new(
+            String::from("spreadsheet_id"),
+            String::default(),
+            String::from("A1"),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Clicked,
+        );
+        tt.status = State::Running;
+        db.save_task(&tt).await.unwrap();
+        let task_id = tt.id;
+        let input = InputData::String(String::from("input"));
+        let first = DeadLetter::new(
+            task_id,
+            input.clone(),
+            RetryPhase::Write,
+            String::from("quota exceeded"),
+            3,
+        );
+        let second = DeadLetter::new(
+            task_id,
+            input.clone(),
+            RetryPhase::Evaluate,
+            String::from("failed to evaluate"),
+            1,
+        );
+        db.save_dead_letter(&first).await.unwrap();
+        db.save_dead_letter(&second).await.unwrap();
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .get(format!("/dead-letters/{}", task_id))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        let listed: Vec<DeadLetter> = response.into_json().await.unwrap();
+        assert_eq!(listed.len(), 2);
+
+        let response = client
+            .post(format!("/dead-letter/{}/replay", first.id))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            cmd_receive.recv().await.unwrap(),
+            TaskCommand::new(task_id, Command::Replay(first.id, input))
+        );
+        // dead letter is removed by task's handler after successful replay.
+        assert!(db.read_dead_letter(first.id).await.is_ok());
+
+        // stopped task would drop replayed input.
+        db.update_task_status(task_id, State::Stopped)
+            .await
+            .unwrap();
+        let response = client
+            .post(format!("/dead-letter/{}/replay", first.id))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client
+            .delete(format!("/dead-letter/{}", second.id))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(db.read_dead_letters(task_id).await.unwrap(), vec![first]);
+    }
+}
@@ -0,0 +1,154 @@
+use super::task::TaskCreateRequest;
+use crate::core::dry_run::{dry_run as evaluate, DryRun};
+use crate::core::task::{InputData, TrackingTask};
+use crate::error::types::{Error, Result};
+use crate::lang::registry::Registry;
+use crate::persistance::interface::Db;
+use crate::server::parse_uuid;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+/// Task to evaluate, given as a new task or as an id of existing one, with optional sample input.
+pub struct DryRunRequest {
+    pub task: Option<TaskCreateRequest>,
+    pub task_id: Option<String>,
+    /// Input to evaluate, fetched with task's input if not given.
+    pub input: Option<InputData>,
+}
+
+impl DryRunRequest {
+    async fn into_task(self, db: &Db, registry: &Registry) -> Result<TrackingTask> {
+        match (self.task, self.task_id) {
+            (Some(mut task), None) => {
+                task.process = db.resolve_imports(task.process).await?;
+                TrackingTask::from_task_create_request(task, registry)
+            }
+            (None, Some(task_id)) => {
+                let uuid = parse_uuid(&task_id, "DryRunRequest::into_task")?;
+                Ok(db.clone().read_task(uuid).await?)
+            }
+            _ => Err(Error::new_validation(
+                "DryRunRequest",
+                "exactly one of task and task_id has to be given",
+                "task",
+            )),
+        }
+    }
+}
+
+/// Evaluates task and returns its output, grid and range that would be written together with
+/// evaluation trace. Nothing is written and location of the task is left untouched.
+#[post("/dry-run", format = "json", data = "<request>")]
+pub async fn dry_run(
+    db: &State<Db>,
+    registry: &State<Registry>,
+    request: Json<DryRunRequest>,
+) -> Result<Json<DryRun>> {
+    let input = request.0.input.clone();
+    let task = request.0.into_task(db, registry).await?;
+    let result = evaluate(task, db, registry, input).await?;
+    info!("dry run returned {} range", result.range);
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::manager::TaskCommand;
+    use crate::core::task::TrackingTask;
+    use crate::core::types::Direction;
+    use crate::lang::process::{Definition, Process};
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use crate::server::task::TaskKindRequest;
+    use rocket::http::{ContentType, Status};
+    use rocket::local::asynchronous::Client;
+    use serde_json::{json, Value};
+    use tokio::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn test_dry_run_endpoint() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
+        let mut tt = TrackingTask::new(
+            "spreadsheet_id".to_string(),
+            "".to_string(),
+            "A1".to_string(),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Clicked,
+        );
+        tt.process = Process::new(
+            "dry run",
+            vec![Definition::new(vec!["DEFINE(OUT, VEC(GET(IN), INT(2)))"])],
+            None,
+        );
+        db.save_task(&tt).await.unwrap();
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .post("/dry-run")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"task_id": "{}", "input": {{"String": "BTC"}}}}"#,
+                tt.id
+            ))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        let result: Value = response.into_json().await.unwrap();
+        assert_eq!(result["out"], json!(["BTC", 2]));
+        assert_eq!(result["grid"], json!([["BTC", 2]]));
+        assert_eq!(result["range"], json!("A1:B1"));
+        assert_eq!(
+            result["trace"][0]["step"],
+            json!("DEFINE(OUT, VEC(GET(IN), INT(2)))")
+        );
+        // nothing was written.
+        assert!(db.get(&tt.id).await.is_err());
+
+        let response = client
+            .post("/dry-run")
+            .header(ContentType::JSON)
+            .body(
+                r#"{
+                    "task": {
+                        "name": "name",
+                        "description": "description",
+                        "spreadsheet_id": "id",
+                        "sheet": "prices",
+                        "starting_position": "C3",
+                        "direction": "horizontal",
+                        "process": {
+                            "name": "process",
+                            "definitions": [{"steps": ["DEFINE(OUT, INT(1))"]}]
+                        },
+                        "input": null,
+                        "kind_request": "Clicked"
+                    },
+                    "input": {"String": ""}
+                }"#,
+            )
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        let result: Value = response.into_json().await.unwrap();
+        assert_eq!(result["out"], json!(1));
+        assert_eq!(result["range"], json!("prices!C3:C3"));
+
+        // task has to be given exactly once.
+        let response = client
+            .post("/dry-run")
+            .header(ContentType::JSON)
+            .body(r#"{"input": {"String": ""}}"#)
+            .dispatch()
+            .await;
+        assert_ne!(response.status(), Status::Ok);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::types::Result;
+use crate::lang::library::LibraryDefinition;
+use crate::lang::process::Definition;
+use crate::persistance::interface::Db;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct LibraryCreateRequest {
+    pub name: String,
+    pub definition: Definition,
+}
+
+/// Publishes new version of named LibraryDefinition, first version is 1.
+/// Version numbers of deleted definitions are never published again.
+#[post("/library", format = "json", data = "<request>")]
+pub async fn create_library_definition(
+    db: &State<Db>,
+    request: Json<LibraryCreateRequest>,
+) -> Result<Json<LibraryDefinition>> {
+    let mut library_definition = LibraryDefinition::new(request.0.name, 0, request.0.definition);
+    library_definition.validate()?;
+    library_definition.version = db.next_library_version(&library_definition.name).await?;
+    db.save_library_definition(&library_definition).await?;
+
+    info!(
+        "library definition {}@v{} created",
+        library_definition.name, library_definition.version
+    );
+    Ok(Json(library_definition))
+}
+
+#[get("/library")]
+pub async fn list_library_definitions(db: &State<Db>) -> Result<Json<Vec<LibraryDefinition>>> {
+    Ok(Json(db.list_library_definitions().await?))
+}
+
+/// Returns latest version of named LibraryDefinition.
+#[get("/library/<name>", rank = 2)]
+pub async fn get_latest_library_definition(
+    db: &State<Db>,
+    name: String,
+) -> Result<Json<LibraryDefinition>> {
+    Ok(Json(db.read_library_definition(&name, None).await?))
+}
+
+#[get("/library/<name>/<version>")]
+pub async fn get_library_definition(
+    db: &State<Db>,
+    name: String,
+    version: i32,
+) -> Result<Json<LibraryDefinition>> {
+    Ok(Json(
+        db.read_library_definition(&name, Some(version)).await?,
+    ))
+}
+
+#[delete("/library/<name>/<version>")]
+pub async fn delete_library_definition(db: &State<Db>, name: String, version: i32) -> Result<()> {
+    db.delete_library_definition(&name, version).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::manager::TaskCommand;
+    use crate::core::task::TrackingTask;
+    use crate::lang::library::LibraryDefinition;
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use rocket::http::{ContentType, Status};
+    use rocket::local::asynchronous::Client;
+    use tokio::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn create_library_definition_versions() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        for wanted_version in 1..=2 {
+            let response = client
+                .post("/library")
+                .header(ContentType::JSON)
+                .body(r#"{"name": "normalize", "definition": {"steps": ["DEFINE(OUT, GET(IN))"]}}"#)
+                .dispatch()
+                .await;
+            assert_eq!(response.status(), Status::Ok);
+            let created: LibraryDefinition = response.into_json().await.unwrap();
+            assert_eq!(created.version, wanted_version);
+        }
+
+        let response = client.get("/library/normalize").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let latest: LibraryDefinition = response.into_json().await.unwrap();
+        assert_eq!(latest.version, 2);
+
+        let response = client.delete("/library/normalize/2").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            db.read_library_definition("normalize", None)
+                .await
+                .unwrap()
+                .version,
+            1
+        );
+        let response = client.delete("/library/normalize/2").dispatch().await;
+        assert_ne!(response.status(), Status::Ok);
+
+        // deleted version is not published again.
+        let response = client
+            .post("/library")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "normalize", "definition": {"steps": ["DEFINE(OUT, GET(IN))"]}}"#)
+            .dispatch()
+            .await;
+        let created: LibraryDefinition = response.into_json().await.unwrap();
+        assert_eq!(created.version, 3);
+    }
+}
@@ -1,5 +1,29 @@
+pub mod alert;
 pub mod apply;
+pub mod backfill;
 pub mod build;
+pub mod dead_letter;
+pub mod dry_run;
+pub mod library;
+pub mod pipeline;
+pub mod process;
 pub mod proto;
+pub mod quarantine;
 pub mod report;
+pub mod secret;
 pub mod task;
+
+use crate::error::types::{Error, Result};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Parses id given in a route's path, scope is the name of the route.
+pub(crate) fn parse_uuid(value: &str, scope: &str) -> Result<Uuid> {
+    Uuid::from_str(value).map_err(|e| {
+        Error::new_internal(
+            String::from(scope),
+            String::from("failed to parse uuid"),
+            e.to_string(),
+        )
+    })
+}
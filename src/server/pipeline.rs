@@ -0,0 +1,98 @@
+use crate::core::pipeline::Pipeline;
+use crate::error::types::Result;
+use crate::persistance::interface::Db;
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// Returns dependency graph of tasks chained with Hook::Task.
+#[get("/pipeline")]
+pub async fn get_pipeline(db: &State<Db>) -> Result<Json<Pipeline>> {
+    Ok(Json(Pipeline::load(db).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::manager::TaskCommand;
+    use crate::core::task::TrackingTask;
+    use crate::core::types::{Direction, Hook};
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use crate::server::task::TaskKindRequest;
+    use rocket::http::{ContentType, Status};
+    use rocket::local::asynchronous::Client;
+    use serde_json::json;
+    use tokio::sync::mpsc::channel;
+    use uuid::Uuid;
+
+    fn task(kind_request: TaskKindRequest) -> TrackingTask {
+        TrackingTask::new(
+            String::from("spreadsheet_id"),
+            String::default(),
+            String::from("A1"),
+            Direction::Vertical,
+            None,
+            kind_request,
+        )
+    }
+
+    fn create_request(upstream_id: Uuid) -> String {
+        json!({
+            "name": "normalize",
+            "description": "",
+            "spreadsheet_id": "id",
+            "sheet": "",
+            "starting_position": "A1",
+            "direction": "vertical",
+            "process": {"name": "normalize", "definitions": [{"steps": ["DEFINE(OUT, GET(IN))"]}]},
+            "input": "None",
+            "kind_request": {"Triggered": {"Task": {"upstream_id": upstream_id}}}
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn create_and_get_pipeline() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, mut tt_receive) = channel::<TrackingTask>(1);
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
+
+        let upstream = task(TaskKindRequest::Ticker { interval_secs: 1 });
+        let downstream = task(TaskKindRequest::Triggered(Hook::Task {
+            upstream_id: upstream.id,
+        }));
+        db.save_task(&upstream).await.unwrap();
+        db.save_task(&downstream).await.unwrap();
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .post("/create")
+            .header(ContentType::JSON)
+            .body(create_request(Uuid::new_v4()))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client
+            .post("/create")
+            .header(ContentType::JSON)
+            .body(create_request(downstream.id))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        let created = tt_receive.recv().await.unwrap();
+        db.save_task(&created).await.unwrap();
+
+        let response = client.get("/pipeline").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let pipeline: serde_json::Value = response.into_json().await.unwrap();
+        assert_eq!(pipeline["nodes"].as_array().unwrap().len(), 3);
+        let edges = pipeline["edges"].as_array().unwrap();
+        assert_eq!(edges.len(), 2);
+        assert!(edges.contains(&json!({"upstream": upstream.id, "downstream": downstream.id})));
+        assert!(edges.contains(&json!({"upstream": downstream.id, "downstream": created.id})));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::types::{Error, Result};
+use crate::lang::process::Process;
+use crate::lang::registry::Registry;
+use crate::lang::testing::TestReport;
+use crate::persistance::interface::Db;
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// Runs test cases declared in Process and returns pass/fail report.
+/// Imports are resolved the same way as for running tasks.
+#[post("/process/test", format = "json", data = "<process>")]
+pub async fn test_process(
+    db: &State<Db>,
+    registry: &State<Registry>,
+    process: Json<Process>,
+) -> Result<Json<TestReport>> {
+    let process = db.resolve_imports(process.0).await?;
+
+    // evaluation may perform blocking http calls.
+    let registry = registry.inner().clone();
+    let report = tokio::task::spawn_blocking(move || process.run_tests(&registry))
+        .await
+        .map_err(|err| {
+            Error::new_internal(
+                String::from("test_process"),
+                String::from("failed to run tests"),
+                err.to_string(),
+            )
+        })?;
+
+    info!(
+        "process tests: {} passed, {} failed",
+        report.passed, report.failed
+    );
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::manager::TaskCommand;
+    use crate::core::task::TrackingTask;
+    use crate::lang::registry::{Double, Registry};
+    use crate::lang::testing::TestReport;
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::{rocket, rocket_with_registry};
+    use rocket::http::{ContentType, Status};
+    use rocket::local::asynchronous::Client;
+    use tokio::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn test_process_endpoint() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .post("/process/test")
+            .header(ContentType::JSON)
+            .body(
+                r#"{
+                    "name": "process",
+                    "definitions": [{"steps": ["DEFINE(OUT, INT(1))"]}],
+                    "tests": [
+                        {"name": "passing", "input": {"String": ""}, "expected_out": 1},
+                        {"name": "failing", "input": {"String": ""}, "expected_out": 2}
+                    ]
+                }"#,
+            )
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let report: TestReport = response.into_json().await.unwrap();
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.failed, 1);
+        assert!(!report.results[1].passed);
+    }
+
+    #[tokio::test]
+    async fn test_process_endpoint_with_native_function() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        let mut registry = Registry::new();
+        registry.register(Double).unwrap();
+
+        let client = Client::tracked(rocket_with_registry(cmd_send, tt_send, db, registry))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .post("/process/test")
+            .header(ContentType::JSON)
+            .body(
+                r#"{
+                    "name": "process",
+                    "definitions": [{"steps": ["DEFINE(OUT, DOUBLE(INT(2)))"]}],
+                    "tests": [{"name": "native", "input": {"String": ""}, "expected_out": 4}]
+                }"#,
+            )
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let report: TestReport = response.into_json().await.unwrap();
+        assert_eq!(report.passed, 1);
+    }
+}
@@ -3,6 +3,7 @@ use crate::core::types::State;
 use crate::persistance::interface::Db;
 use crate::stats::stats_server::Stats;
 use crate::stats::{GetStatsRequest, GetStatsResponse};
+use chrono::Utc;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
@@ -32,7 +33,13 @@ impl Stats for StatsService {
         let mut db = self.db.clone();
         tokio::spawn(async move {
             let tasks = db
-                .get_tasks_by_status(&[State::Created, State::Quit, State::Running, State::Stopped])
+                .get_tasks_by_status(&[
+                    State::Created,
+                    State::Quit,
+                    State::Running,
+                    State::Stopped,
+                    State::Finished,
+                ])
                 .await;
 
             match tasks {
@@ -58,10 +65,15 @@ fn get_stats_response_from_tts(tt: TrackingTask) -> GetStatsResponse {
         sheet: tt.sheet,
         direction: tt.direction.to_string(),
         interval_secs: 10_i32,
-        input: tt.input.unwrap_or_default().to_json(),
+        input: tt.input.unwrap_or_default().redacted().to_json(),
         status: tt.status.to_string(),
         eval_forest: tt.process.try_to_string().unwrap_or_default(),
-        till_next_call: 0,
-        currently_running: true,
+        till_next_call: tt
+            .kind_request
+            .till_next_call(Utc::now())
+            .map(|d| d.as_secs() as i32)
+            .unwrap_or_default(),
+        currently_running: tt.status == State::Running,
+        dropped_inputs: tt.dropped_inputs,
     }
 }
@@ -0,0 +1,16 @@
+use crate::core::quarantine::QuarantinedInput;
+use crate::error::types::Result;
+use crate::persistance::interface::Db;
+use crate::server::parse_uuid;
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// Lists inputs of a task that were rejected by its Process's input_schema.
+#[get("/quarantine/<task_id>")]
+pub async fn get_quarantined_inputs(
+    db: &State<Db>,
+    task_id: String,
+) -> Result<Json<Vec<QuarantinedInput>>> {
+    let uuid = parse_uuid(&task_id, "get_quarantined_inputs")?;
+    Ok(Json(db.read_quarantined_inputs(uuid).await?))
+}
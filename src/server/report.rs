@@ -1,11 +1,11 @@
-use crate::error::types::{Error, Result};
+use crate::error::types::Result;
+use crate::server::parse_uuid;
 use crate::{core::handler::Report, persistance::interface::Db};
 use rocket::http::{ContentType, Status};
 use rocket::response::{self, Responder, Response};
 use rocket::Request;
 use rocket::State;
 use serde::Serialize;
-use std::str::FromStr;
 
 #[derive(Serialize)]
 pub struct Reports {
@@ -25,13 +25,7 @@ impl<'r> Responder<'r, 'static> for Reports {
 
 #[get("/reports/<task_id>")]
 pub async fn get_reports(db: &State<Db>, task_id: String) -> Result<Option<Reports>> {
-    let uuid = uuid::Uuid::from_str(&task_id).map_err(|e| {
-        Error::new_internal(
-            String::from("get_reports"),
-            String::from("failed to parse uuid"),
-            e.to_string(),
-        )
-    })?;
+    let uuid = parse_uuid(&task_id, "get_reports")?;
     Ok(db
         .read_reports(uuid)
         .await?
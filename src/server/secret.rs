@@ -0,0 +1,123 @@
+use crate::core::secret::REDACTED;
+use crate::error::types::{Error, Result};
+use crate::persistance::interface::Db;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Debug};
+
+#[derive(Deserialize)]
+pub struct SecretCreateRequest {
+    pub name: String,
+    pub value: String,
+}
+
+impl Debug for SecretCreateRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SecretCreateRequest")
+            .field("name", &self.name)
+            .field("value", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+/// Secret's value is never returned by API.
+pub struct SecretResponse {
+    pub name: String,
+}
+
+/// Encrypts and saves secret, existing secret with the same name is overwritten.
+#[post("/secrets", format = "json", data = "<request>")]
+pub async fn create_secret(
+    db: &State<Db>,
+    request: Json<SecretCreateRequest>,
+) -> Result<Json<SecretResponse>> {
+    if request.name.is_empty() {
+        return Err(Error::new_validation(
+            "Secret",
+            "Name cannot be empty",
+            "name",
+        ));
+    }
+    db.save_secret(&request.name, &request.value).await?;
+
+    info!("secret {} saved", request.name);
+    Ok(Json(SecretResponse {
+        name: request.0.name,
+    }))
+}
+
+#[get("/secrets")]
+pub async fn list_secrets(db: &State<Db>) -> Result<Json<Vec<SecretResponse>>> {
+    Ok(Json(
+        db.list_secrets()
+            .await?
+            .into_iter()
+            .map(|name| SecretResponse { name })
+            .collect(),
+    ))
+}
+
+#[delete("/secrets/<name>")]
+pub async fn delete_secret(db: &State<Db>, name: String) -> Result<()> {
+    db.delete_secret(&name).await?;
+    info!("secret {} deleted", name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SecretCreateRequest;
+    use crate::core::manager::TaskCommand;
+    use crate::core::secret::MasterKey;
+    use crate::core::task::TrackingTask;
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use rocket::http::{ContentType, Status};
+    use rocket::local::asynchronous::Client;
+    use tokio::sync::mpsc::channel;
+
+    #[test]
+    fn secret_request_debug() {
+        let request = SecretCreateRequest {
+            name: String::from("psql_password"),
+            value: String::from("pass"),
+        };
+        assert_eq!(
+            format!("{:?}", request),
+            r#"SecretCreateRequest { name: "psql_password", value: "***" }"#
+        );
+    }
+
+    #[tokio::test]
+    async fn create_list_delete_secret() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let db = Db::new(Box::new(InMemoryPersistance::new()))
+            .with_master_key(MasterKey::from_bytes(&[1; 32]).unwrap());
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .post("/secrets")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "psql_password", "value": "pass"}"#)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(db.read_secret("psql_password").await.unwrap(), "pass");
+
+        let response = client.get("/secrets").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().await.unwrap();
+        assert_eq!(body, r#"[{"name":"psql_password"}]"#);
+
+        let response = client.delete("/secrets/psql_password").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        assert!(db.read_secret("psql_password").await.is_err());
+    }
+}
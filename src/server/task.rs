@@ -1,7 +1,21 @@
+use crate::core::alert::AlertConfig;
+use crate::core::buffer::{BatchConfig, BufferConfig};
+use crate::core::dedup::DedupConfig;
+use crate::core::manager::{Command, TaskCommand};
+use crate::core::pipeline::{upstream_of, Pipeline};
+use crate::core::retry::RetryPolicy;
+use crate::core::schedule::ActiveWindow;
+use crate::core::schedule::CronSchedule;
 use crate::core::task::{TaskInput, TrackingTask};
+use crate::core::types::State as TaskState;
 use crate::core::types::*;
+use crate::core::write_mode::WriteMode;
 use crate::error::types::{Error, Result};
 use crate::lang::process::Process;
+use crate::lang::registry::Registry;
+use crate::persistance::interface::Db;
+use crate::server::parse_uuid;
+use chrono::{DateTime, Utc};
 use rocket::http::{ContentType, Status};
 use rocket::response::{self, Responder, Response};
 use rocket::serde::json::Json;
@@ -9,14 +23,27 @@ use rocket::{Request, State};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 
 #[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
 // Will be translated into [`core::types::TaskKind`];
 pub enum TaskKindRequest {
-    Ticker { interval_secs: u64 }, // task with ticker.
+    Ticker {
+        interval_secs: u64,
+    }, // task with ticker.
     Triggered(Hook),
     Clicked, // creation should return url that can trigger action.
+    Cron {
+        expression: String, // e.g. "0 9 * * Mon-Fri".
+        #[serde(default = "default_timezone")]
+        timezone: String, // IANA timezone name, e.g. "Europe/Warsaw".
+    },
+}
+
+fn default_timezone() -> String {
+    String::from("UTC")
 }
 
 impl TaskKindRequest {
@@ -32,6 +59,35 @@ impl TaskKindRequest {
     pub fn to_json(&self) -> String {
         serde_json::json!(self).to_string()
     }
+
+    /// Validates request, e.g. checks if cron expression and timezone can be parsed
+    /// and if hook's password references stored secret.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Cron {
+                expression,
+                timezone,
+            } => {
+                CronSchedule::new(expression, timezone)?;
+            }
+            Self::Triggered(Hook::PSQL { password, .. }) => password.ensure_secret()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns time left till next scheduled call, known only for cron tasks.
+    pub fn till_next_call(&self, now: DateTime<Utc>) -> Option<Duration> {
+        match self {
+            Self::Cron {
+                expression,
+                timezone,
+            } => CronSchedule::new(expression, timezone)
+                .ok()?
+                .until_next(now),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -45,6 +101,40 @@ pub struct TaskCreateRequest {
     pub process: Process,
     pub input: Option<TaskInput>,
     pub kind_request: TaskKindRequest,
+    /// Values of params declared by process.
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    /// Number of runs after which task is finished.
+    pub invocations: Option<i32>,
+    /// Date after which task is finished.
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Policy of retrying failed phases, failed input is saved as dead letter after last attempt.
+    pub retry_policy: Option<RetryPolicy>,
+    /// Size and overflow policy of buffer for inputs waiting to be handled.
+    pub buffer: Option<BufferConfig>,
+    /// Enables accumulating inputs and writing them to a spreadsheet at once.
+    pub batch: Option<BatchConfig>,
+    /// Period in which task handles its inputs.
+    pub active_window: Option<ActiveWindow>,
+    /// Timestamp written next to every row or column of data.
+    pub timestamp: Option<TimestampConfig>,
+    /// Where data of every run is written, appended by default.
+    #[serde(default)]
+    pub write_mode: WriteMode,
+    /// Skips writes of output that did not change since the last write.
+    pub dedup: Option<DedupConfig>,
+    /// Rules checked on every output of the task, with notifiers of fired alerts.
+    pub alerts: Option<AlertConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+/// Changes of existing task, fields that are None are left untouched.
+pub struct TaskUpdateRequest {
+    pub name: Option<String>,
+    pub process: Option<Process>,
+    pub kind_request: Option<TaskKindRequest>,
+    pub spreadsheet_id: Option<String>,
+    pub sheet: Option<String>,
 }
 
 pub struct TaskCreateResponse {
@@ -71,11 +161,40 @@ impl<'r> Responder<'r, 'static> for TaskCreateResponse {
 #[post("/create", format = "json", data = "<request>")]
 pub async fn create(
     sender: &State<Sender<TrackingTask>>,
+    db: &State<Db>,
+    registry: &State<Registry>,
     request: Json<TaskCreateRequest>,
 ) -> TaskCreateResponse {
     info!("definition from request: {:?}", request.process);
 
-    let tt = TrackingTask::from_task_create_request(request.0);
+    // imports are resolved once, so publishing or deleting library versions doesn't affect the task.
+    let mut request = request.0;
+    request.process = match db.resolve_imports(request.process.clone()).await {
+        Ok(process) => process,
+        Err(e) => {
+            error!("{}", e);
+            return TaskCreateResponse::new(json!({ "err": format!("{}", e) }), Status::BadRequest);
+        }
+    };
+
+    let tt = TrackingTask::from_task_create_request(request, registry);
+
+    // chained task has to point to existing task and can't close a cycle.
+    if let Ok(tt) = &tt {
+        if let Some(upstream_id) = upstream_of(&tt.kind_request) {
+            let checked = match Pipeline::load(db).await {
+                Ok(pipeline) => pipeline.check(tt.id, upstream_id),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = checked {
+                error!("{}", e);
+                return TaskCreateResponse::new(
+                    json!({ "err": format!("{}", e) }),
+                    Status::BadRequest,
+                );
+            }
+        }
+    }
 
     match tt {
         Ok(tt) => {
@@ -95,13 +214,63 @@ pub async fn create(
     }
 }
 
+/// Changes process, kind, spreadsheet target or name of existing task.
+/// Change is persisted and swapped into running task between its runs.
+#[patch("/task/<id>", format = "json", data = "<request>")]
+pub async fn update(
+    sender: &State<Sender<TaskCommand>>,
+    db: &State<Db>,
+    id: String,
+    request: Json<TaskUpdateRequest>,
+) -> Result<()> {
+    let uuid = parse_uuid(&id, "update")?;
+    let mut db = db.inner().clone();
+    let mut tt = db.read_task(uuid).await?;
+    // finished and deleted tasks have no handler the change could be sent to.
+    tt.ensure_status(&[TaskState::Created, TaskState::Running, TaskState::Stopped])?;
+
+    let mut request = request.0;
+    if let Some(process) = request.process.take() {
+        request.process = Some(db.resolve_imports(process).await?);
+    }
+    if let Some(upstream_id) = request.kind_request.as_ref().and_then(upstream_of) {
+        Pipeline::load(&db).await?.check(uuid, upstream_id)?;
+    }
+    tt.apply_update(request.clone())?;
+    db.update_task(&tt).await?;
+
+    sender
+        .send(TaskCommand::new(uuid, Command::Update(Box::new(request))))
+        .await
+        .map_err(|e| {
+            Error::new_internal(
+                String::from("update"),
+                String::from("failed to send update command"),
+                e.to_string(),
+            )
+        })?;
+    info!("task {} updated", id);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::core::manager::{Command, TaskCommand};
+    use crate::core::secret::Credential;
     use crate::core::task::TaskInput;
-    use crate::core::types::Hook;
+    use crate::core::task::TrackingTask;
+    use crate::core::types::{Hook, State};
+    use crate::lang::library::LibraryDefinition;
     use crate::lang::process::{Definition, Process};
-    use crate::server::task::TaskKindRequest;
+    use crate::persistance::in_memory::InMemoryPersistance;
+    use crate::persistance::interface::Db;
+    use crate::server::build::rocket;
+    use crate::server::task::{TaskKindRequest, TaskUpdateRequest};
     use crate::{core::types::Direction, server::task::TaskCreateRequest};
+    use rocket::http::{ContentType, Status};
+    use rocket::local::asynchronous::Client;
+    use std::collections::HashMap;
+    use tokio::sync::mpsc::channel;
 
     #[test]
     fn proper_test_deserializing() {
@@ -147,6 +316,17 @@ mod tests {
                     direction: Direction::Horizontal,
                     process: Process::new("test process", vec![Definition::new(vec![String::from("MOCK DEFINITION 1"),String::from("MOCK DEFINITION 2"),])], None),
                     input: Some(TaskInput::None),
+                    params: HashMap::new(),
+                    invocations: None,
+                    ends_at: None,
+                    retry_policy: None,
+                    buffer: None,
+                    batch: None,
+                    active_window: None,
+                    write_mode: WriteMode::default(),
+                    dedup: None,
+                    alerts: None,
+                    timestamp: None,
                     kind_request: TaskKindRequest::Ticker{ interval_secs: 30 },
                 },
                 want_err: false,
@@ -185,6 +365,17 @@ mod tests {
                     direction: Direction::Horizontal,
                     process: Process::new("test process", vec![Definition::new(vec![String::from("MOCK DEFINITION 1"),String::from("MOCK DEFINITION 2"),])], None),
                     input: Some(TaskInput::None),
+                    params: HashMap::new(),
+                    invocations: None,
+                    ends_at: None,
+                    retry_policy: None,
+                    buffer: None,
+                    batch: None,
+                    active_window: None,
+                    write_mode: WriteMode::default(),
+                    dedup: None,
+                    alerts: None,
+                    timestamp: None,
                     kind_request:             TaskKindRequest::Clicked,
                 },
                 want_err: false,
@@ -226,11 +417,22 @@ mod tests {
                         host: String::from("host"),
                         port: 5432,
                         user: String::from("user"),
-                        password: String::from("pass"),
+                        password: Credential::from("pass"),
                         query: String::from("SELECT 1"),
                         db: String::from("test"),
                     }),
                     kind_request: TaskKindRequest::Clicked,
+                    params: HashMap::new(),
+                    invocations: None,
+                    ends_at: None,
+                    retry_policy: None,
+                    buffer: None,
+                    batch: None,
+                    active_window: None,
+                    write_mode: WriteMode::default(),
+                    dedup: None,
+                    alerts: None,
+                    timestamp: None,
                 },
                 want_err: false,
             },
@@ -267,11 +469,22 @@ mod tests {
                     direction: Direction::Horizontal,
                     process: Process::new("test process", vec![Definition::new(vec![String::from("MOCK DEFINITION 1"),String::from("MOCK DEFINITION 2"),])], None),
                     input: None,
+                    params: HashMap::new(),
+                    invocations: None,
+                    ends_at: None,
+                    retry_policy: None,
+                    buffer: None,
+                    batch: None,
+                    active_window: None,
+                    write_mode: WriteMode::default(),
+                    dedup: None,
+                    alerts: None,
+                    timestamp: None,
                     kind_request: TaskKindRequest::Triggered(Hook::PSQL{
                         host: String::from("host"),
                         port: 5432,
                         user: String::from("user"),
-                        password: String::from("pass"),
+                        password: Credential::from("pass"),
                         channel: String::from("channel"),
                         db: String::from("test"),
                     }),
@@ -287,4 +500,177 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn cron_kind_request() {
+        let kind =
+            TaskKindRequest::from_json(r#"{"Cron": {"expression": "0 9 * * Mon-Fri"}}"#).unwrap();
+        assert_eq!(
+            kind,
+            TaskKindRequest::Cron {
+                expression: String::from("0 9 * * Mon-Fri"),
+                timezone: String::from("UTC"),
+            }
+        );
+        assert!(kind.validate().is_ok());
+        assert!(kind.till_next_call(chrono::Utc::now()).is_some());
+
+        let kind = TaskKindRequest::Cron {
+            expression: String::from("0 9 * * Mon-Fri"),
+            timezone: String::from("Europe/Nowhere"),
+        };
+        assert!(kind.validate().is_err());
+    }
+
+    #[test]
+    fn psql_hook_requires_secret() {
+        let hook = |password: Credential| {
+            TaskKindRequest::Triggered(Hook::PSQL {
+                host: String::from("host"),
+                port: 5432,
+                user: String::from("user"),
+                password,
+                db: String::from("test"),
+                channel: String::from("prices"),
+            })
+        };
+        // plain password would be persisted with the task.
+        assert!(hook(Credential::from("pass")).validate().is_err());
+        assert!(hook(Credential::secret("psql_password")).validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn create_task_resolves_imports() {
+        let (cmd_send, _) = channel::<TaskCommand>(1);
+        let (tt_send, mut tt_receive) = channel::<TrackingTask>(1);
+        let db = Db::new(Box::new(InMemoryPersistance::new()));
+        db.save_library_definition(&LibraryDefinition::new(
+            "normalize",
+            1,
+            Definition::new(vec!["DEFINE(OUT, GET(IN))"]),
+        ))
+        .await
+        .unwrap();
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .post("/create")
+            .header(ContentType::JSON)
+            .body(
+                r#"{
+                "name": "name",
+                "description": "description",
+                "spreadsheet_id": "id",
+                "sheet": "sheet",
+                "starting_position": "A1",
+                "direction": "vertical",
+                "process": {
+                    "name": "process",
+                    "imports": ["normalize"],
+                    "definitions": [{"steps": ["RunSubtree(normalize)"]}]
+                },
+                "input": "None",
+                "kind_request": "Clicked"
+            }"#,
+            )
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        // imported definition is injected, so later versions don't change the task.
+        let tt = tt_receive.recv().await.unwrap();
+        assert!(tt.process.imports.is_none());
+        let subtrees = tt.process.definitions[0].subtrees.clone().unwrap();
+        assert_eq!(subtrees.len(), 1);
+        assert_eq!(subtrees[0].name, "normalize");
+
+        // unknown import is rejected.
+        let response = client
+            .post("/create")
+            .header(ContentType::JSON)
+            .body(
+                r#"{
+                "name": "name",
+                "description": "description",
+                "spreadsheet_id": "id",
+                "sheet": "sheet",
+                "starting_position": "A1",
+                "direction": "vertical",
+                "process": {"name": "process", "imports": ["missing"], "definitions": []},
+                "input": "None",
+                "kind_request": "Clicked"
+            }"#,
+            )
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[tokio::test]
+    async fn update_task() {
+        let (cmd_send, mut cmd_receive) = channel::<TaskCommand>(1);
+        let (tt_send, _) = channel::<TrackingTask>(1);
+        let mut db = Db::new(Box::new(InMemoryPersistance::new()));
+
+        let tt = TrackingTask::new(
+            String::from("spreadsheet_id"),
+            String::default(),
+            String::from("A1"),
+            Direction::Vertical,
+            None,
+            TaskKindRequest::Ticker { interval_secs: 1 },
+        );
+        db.save_task(&tt).await.unwrap();
+
+        let client = Client::tracked(rocket(cmd_send, tt_send, db.clone()))
+            .await
+            .expect("valid rocket instance");
+
+        let response = client
+            .patch(format!("/task/{}", tt.id))
+            .header(ContentType::JSON)
+            .body(r#"{"name": "renamed", "sheet": "archive"}"#)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            cmd_receive.recv().await.unwrap(),
+            TaskCommand::new(
+                tt.id,
+                Command::Update(Box::new(TaskUpdateRequest {
+                    name: Some(String::from("renamed")),
+                    sheet: Some(String::from("archive")),
+                    ..Default::default()
+                }))
+            )
+        );
+        let updated = db.read_task(tt.id).await.unwrap();
+        assert_eq!(updated.name, Some(String::from("renamed")));
+        assert_eq!(updated.sheet, String::from("archive"));
+
+        // invalid change is rejected and task is left untouched.
+        let response = client
+            .patch(format!("/task/{}", tt.id))
+            .header(ContentType::JSON)
+            .body(r#"{"name": "invalid", "kind_request": {"Cron": {"expression": "0 25 * * *"}}}"#)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(
+            db.read_task(tt.id).await.unwrap().name,
+            Some(String::from("renamed"))
+        );
+
+        // finished task has no handler, change is rejected.
+        db.update_task_status(tt.id, State::Finished).await.unwrap();
+        let response = client
+            .patch(format!("/task/{}", tt.id))
+            .header(ContentType::JSON)
+            .body(r#"{"name": "finished"}"#)
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::BadRequest);
+    }
 }
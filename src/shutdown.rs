@@ -34,6 +34,14 @@ impl Shutdown {
         self.shutdown
     }
 
+    /// Checks for the shutdown notice without waiting, returns `true` if it has been received.
+    pub(crate) fn try_recv(&mut self) -> bool {
+        if !self.shutdown && self.notify.try_recv().is_ok() {
+            self.shutdown = true;
+        }
+        self.shutdown
+    }
+
     /// Returns new Receiver for shutdown broadcaster.
     pub(crate) fn subscribe(&self) -> Receiver<()> {
         self.sender.subscribe()
@@ -1,3 +1,4 @@
+use crate::core::grid::{Cell, Grid};
 use crate::error::types::Error as IError;
 use crate::error::types::Result;
 use async_trait::async_trait; // crate for async traits.
@@ -11,7 +12,9 @@ use tokio::sync::mpsc::{channel, Receiver, Sender};
 #[automock]
 // API is a wrapper for the Google Sheets API.
 pub trait API {
-    async fn write(&self, values: Vec<Vec<String>>, sheet_id: &str, range: &str) -> Result<()>;
+    async fn write(&self, values: Grid, sheet_id: &str, range: &str) -> Result<()>;
+    /// Reads displayed values of a range, row by row. Trailing empty rows and cells are omitted.
+    async fn read(&self, sheet_id: &str, range: &str) -> Result<Vec<Vec<String>>>;
 }
 
 #[derive(Clone)]
@@ -23,9 +26,14 @@ pub struct APIWrapper {
 #[async_trait]
 impl API for APIWrapper {
     // writes data to a sheet.
-    async fn write(&self, values: Vec<Vec<String>>, sheet_id: &str, range: &str) -> Result<()> {
+    async fn write(&self, values: Grid, sheet_id: &str, range: &str) -> Result<()> {
         let req = ValueRange {
-            values: Some(values),
+            values: Some(
+                values
+                    .iter()
+                    .map(|line| line.iter().map(Cell::to_user_entered).collect())
+                    .collect(),
+            ),
             ..Default::default()
         };
 
@@ -36,7 +44,8 @@ impl API for APIWrapper {
             .spreadsheets()
             .values_update(req, sheet_id, range)
             .include_values_in_response(false)
-            .value_input_option("RAW")
+            // values are parsed as if typed by a user, so numbers and booleans keep their types.
+            .value_input_option("USER_ENTERED")
             .doit()
             .await;
 
@@ -66,6 +75,41 @@ impl API for APIWrapper {
             Ok(_) => Ok(()),
         };
     }
+
+    // reads displayed values from a sheet.
+    async fn read(&self, sheet_id: &str, range: &str) -> Result<Vec<Vec<String>>> {
+        let result = self
+            .client
+            .spreadsheets()
+            .values_get(sheet_id, range)
+            .doit()
+            .await;
+
+        match result {
+            Err(e) => match e {
+                Error::Failure(res) => Err(IError::new_internal(
+                    String::from("read"),
+                    String::from("failed to get cells"),
+                    read_response_body(res).await?,
+                )),
+                Error::HttpError(_)
+                | Error::Io(_)
+                | Error::MissingAPIKey
+                | Error::MissingToken(_)
+                | Error::Cancelled
+                | Error::UploadSizeLimitExceeded(_, _)
+                | Error::BadRequest(_)
+                | Error::FieldClash(_)
+                | Error::JsonDecodeError(_, _) => Err(IError::new_internal(
+                    String::from("read"),
+                    String::from("internal spreadsheet error"),
+                    e.to_string(),
+                )),
+            },
+            // empty range has no values.
+            Ok(vr) => Ok(vr.1.values.unwrap_or_default()),
+        }
+    }
 }
 
 impl APIWrapper {
@@ -106,45 +150,12 @@ impl APIWrapper {
 
     // get returns cell value from a sheet.
     pub async fn get(&self, sheet_id: &str, range: &str) -> Result<Vec<String>> {
-        let result = self
-            .client
-            .spreadsheets()
-            .values_get(sheet_id, range)
-            .doit()
-            .await;
-
-        match result {
-            Err(e) => match e {
-                Error::Failure(res) => Err(IError::new_internal(
-                    String::from("get"),
-                    String::from("failed to get cells"),
-                    read_response_body(res).await?,
-                )),
-                // The Error enum provides details about what exactly> happened.
-                // You can also just use its `Debug`, `Display` or `Error` traits
-                Error::HttpError(_)
-                | Error::Io(_)
-                | Error::MissingAPIKey
-                | Error::MissingToken(_)
-                | Error::Cancelled
-                | Error::UploadSizeLimitExceeded(_, _)
-                | Error::BadRequest(_)
-                | Error::FieldClash(_)
-                | Error::JsonDecodeError(_, _) => Err(IError::new_internal(
-                    String::from("get"),
-                    String::from("internal spreadsheet error"),
-                    e.to_string(),
-                )),
-            },
-            // we need to unwrap this 2d array properly here.
-            Ok(vr) => Ok(vr
-                .1
-                .values
-                .unwrap()
-                .into_iter()
-                .map(|v| v[0].clone())
-                .collect()),
-        }
+        Ok(self
+            .read(sheet_id, range)
+            .await?
+            .into_iter()
+            .map(|v| v[0].clone())
+            .collect())
     }
 }
 
@@ -171,27 +182,44 @@ pub struct StdoutAPI {}
 
 #[async_trait]
 impl API for StdoutAPI {
-    async fn write(&self, values: Vec<Vec<String>>, sheet_id: &str, range: &str) -> Result<()> {
+    async fn write(&self, values: Grid, sheet_id: &str, range: &str) -> Result<()> {
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         info!("{:?} {} {}", values, sheet_id, range);
         Ok(())
     }
+
+    async fn read(&self, _sheet_id: &str, _range: &str) -> Result<Vec<Vec<String>>> {
+        Ok(vec![])
+    }
 }
 
 pub struct TestAPI {
-    sender: Sender<Vec<Vec<String>>>,
+    sender: Sender<Grid>,
+    rows: Vec<Vec<String>>, // values returned by every read.
 }
 
 impl TestAPI {
-    pub fn new() -> (Self, Receiver<Vec<Vec<String>>>) {
-        let (sender, receiver) = channel::<Vec<Vec<String>>>(1);
-        (Self { sender }, receiver)
+    pub fn new() -> (Self, Receiver<Grid>) {
+        let (sender, receiver) = channel::<Grid>(1);
+        (
+            Self {
+                sender,
+                rows: vec![],
+            },
+            receiver,
+        )
+    }
+
+    /// Sets values returned by every read.
+    pub fn with_rows(mut self, rows: Vec<Vec<String>>) -> Self {
+        self.rows = rows;
+        self
     }
 }
 
 #[async_trait]
 impl API for TestAPI {
-    async fn write(&self, values: Vec<Vec<String>>, _sheet_id: &str, _range: &str) -> Result<()> {
+    async fn write(&self, values: Grid, _sheet_id: &str, _range: &str) -> Result<()> {
         self.sender.send(values).await.map_err(|err| {
             IError::new_internal(
                 String::from("TestAPI:write"),
@@ -200,6 +228,10 @@ impl API for TestAPI {
             )
         })
     }
+
+    async fn read(&self, _sheet_id: &str, _range: &str) -> Result<Vec<Vec<String>>> {
+        Ok(self.rows.clone())
+    }
 }
 
 pub enum APIType {
@@ -1,6 +1,7 @@
 use std::env;
 extern crate datatracker_rust;
 
+pub use datatracker_rust::core::grid::Cell;
 pub use datatracker_rust::wrap::{APIWrapper, API};
 
 #[tokio::test]
@@ -13,7 +14,7 @@ async fn test_api_wrapper() {
     let api = APIWrapper::new_with_init().await;
     let res = api
         .write(
-            vec![vec!["123".to_string(), "1232".to_string()]],
+            vec![vec![Cell::Int(123), Cell::Int(1232)]],
             &spreadsheet_id,
             "A1:A2",
         )
@@ -2,6 +2,7 @@ use datatracker_rust::{
     connector::kafka::{consume_topic, KafkaConfig},
     core::{
         channels::ChannelsManager,
+        grid::Cell,
         manager::TaskCommand,
         task::{InputData, TrackingTask},
         tracker::Tracker,
@@ -179,7 +180,7 @@ async fn test_kafka_connector_whole_flow() {
     loop {
         if let Some(values) = test_receiver.recv().await {
             println!("{:?}", values);
-            assert_eq!(values[0][0], String::from(r#"String("test")"#));
+            assert_eq!(values[0][0], Cell::String(String::from("test")));
             return;
         }
     }
@@ -3,7 +3,9 @@ extern crate datatracker_rust;
 use datatracker_rust::connector::factory::getter_from_task_input;
 use datatracker_rust::connector::psql::{monitor_changes, PSQLConfig};
 use datatracker_rust::core::channels::ChannelsManager;
+use datatracker_rust::core::grid::Cell;
 use datatracker_rust::core::manager::TaskCommand;
+use datatracker_rust::core::secret::Credential;
 use datatracker_rust::core::task::{InputData, TaskInput, TrackingTask};
 use datatracker_rust::core::tracker::Tracker;
 use datatracker_rust::core::types::{Direction, Hook};
@@ -109,7 +111,7 @@ async fn test_psql_connector() {
         tokio::select! {
             n = receiver.recv() => {
                 if let Some(n) =n {
-                    assert_eq!(n[0][0], String::from(r#"String("test")"#));
+                    assert_eq!(n[0][0], Cell::String(String::from("test")));
                     return;
                 }
             }
@@ -235,7 +237,7 @@ async fn test_changes_monitor_whole_flow() {
     loop {
         if let Some(values) = test_receiver.recv().await {
             println!("{:?}", values);
-            assert_eq!(values[0][0], String::from("Int(1)"));
+            assert_eq!(values[0][0], Cell::Int(1));
             return;
         }
     }
@@ -246,14 +248,15 @@ async fn prep_psql() -> (Client, PSQLConfig) {
         String::from("localhost"),
         5432,
         String::from("postgres"),
-        String::from("password"),
+        Credential::from("password"),
         String::from("postgres"),
         Some(String::from("test_channel")),
     );
 
-    let (client, connection) = tokio_postgres::connect(psql_cfg.to_conn_str().as_str(), NoTls)
-        .await
-        .unwrap();
+    let (client, connection) =
+        tokio_postgres::connect(psql_cfg.to_conn_str().unwrap().as_str(), NoTls)
+            .await
+            .unwrap();
     tokio::spawn(async move {
         if let Err(e) = connection.await {
             eprintln!("connection error: {}", e);